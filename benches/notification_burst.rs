@@ -0,0 +1,70 @@
+//! Times a burst of value notifications through the simulated network, as during a network
+//! wide poll, with watchers using more or less of each ValueID.
+//!
+//! Run with `cargo bench --bench notification_burst`.
+
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::manager::Manager;
+use openzwave::notification::Notification;
+use openzwave::value_classes::value_id::{set_metadata_cache, ValueContent, ValueID};
+use std::time::{Duration, Instant};
+
+const NODES: u8 = 20;
+const VALUES_PER_NODE: u16 = 10;
+const ROUNDS: i32 = 50;
+
+fn burst(home_id: u32, cache: bool, watcher: fn(&Notification)) -> Duration {
+    let network = SimulatedNetwork::new(home_id);
+    let manager = Manager::with_backend(network.clone());
+    let mut values: Vec<ValueID> = Vec::new();
+    for node_id in 2..NODES + 2 {
+        network.add_node(node_id, SimulatedNode::default());
+        for index in 0..VALUES_PER_NODE {
+            let value = SimulatedValue::new(0x31, index, "Temperature", ValueContent::Int(0));
+            values.push(network.add_value(node_id, value));
+        }
+    }
+    set_metadata_cache(cache);
+    let _watcher = manager.add_watcher(watcher).unwrap();
+
+    let start = Instant::now();
+    for round in 1..=ROUNDS {
+        for value_id in &values {
+            network.update_value(value_id, ValueContent::Int(round));
+        }
+    }
+    let elapsed = start.elapsed();
+    set_metadata_cache(false);
+    network.detach();
+    elapsed
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let notifications = ROUNDS as u32 * NODES as u32 * VALUES_PER_NODE as u32;
+    println!(
+        "{:<24} {:>8} notifications in {:>10.3?} ({:?} each)",
+        name,
+        notifications,
+        elapsed,
+        elapsed / notifications
+    );
+}
+
+fn main() {
+    report("ignored", burst(0x0b00_0001, false, |_| {}));
+    report("value read", burst(0x0b00_0002, false, |notification| {
+        if let Some(value_id) = &notification.value_id {
+            value_id.value();
+        }
+    }));
+    report("label read", burst(0x0b00_0003, false, |notification| {
+        if let Some(value_id) = &notification.value_id {
+            value_id.label();
+        }
+    }));
+    report("label read, cached", burst(0x0b00_0004, true, |notification| {
+        if let Some(value_id) = &notification.value_id {
+            value_id.label();
+        }
+    }));
+}
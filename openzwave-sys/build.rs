@@ -7,6 +7,7 @@ fn main() {
         .file("openzwave-c/options.cc")
         .file("openzwave-c/manager.cc")
         .file("openzwave-c/notification.cc")
+        .file("openzwave-c/log.cc")
         .file("openzwave-c/value_classes/value_id.cc")
         .cpp(true)
         .flag("-std=c++17") // to iterate with ranges
@@ -0,0 +1,97 @@
+#include <cstdarg>
+#include <cstdio>
+#include <string>
+#include <vector>
+#include <Options.h>
+#include "log.h"
+
+using OpenZWave::LogLevel;
+
+namespace {
+
+uint8 rust_log_level(LogLevel level) {
+  switch (level) {
+    case OpenZWave::LogLevel_Always:
+    case OpenZWave::LogLevel_Fatal:
+    case OpenZWave::LogLevel_Error:
+      return 1;
+    case OpenZWave::LogLevel_Warning:
+    case OpenZWave::LogLevel_Alert:
+      return 2;
+    case OpenZWave::LogLevel_Info:
+      return 3;
+    case OpenZWave::LogLevel_Detail:
+    case OpenZWave::LogLevel_Debug:
+      return 4;
+    case OpenZWave::LogLevel_StreamDetail:
+    case OpenZWave::LogLevel_Internal:
+      return 5;
+    default:
+      return 0;
+  }
+}
+
+// Replaces OpenZWave's file and console log: every line up to the SaveLogLevel goes to the
+// Rust callback instead.
+class RustLogImpl : public OpenZWave::i_LogImpl {
+public:
+  RustLogImpl(RustLogCallback callback, LogLevel saveLevel)
+    : m_callback(callback), m_saveLevel(saveLevel) {}
+
+  void Write(LogLevel _level, uint8 const _nodeId, char const* _format, va_list _args) override {
+    uint8 level = rust_log_level(_level);
+    if (level == 0 || _level > m_saveLevel) {
+      return;
+    }
+
+    va_list args;
+    va_copy(args, _args);
+    int length = vsnprintf(nullptr, 0, _format, args);
+    va_end(args);
+    if (length < 0) {
+      return;
+    }
+
+    std::vector<char> message(length + 1);
+    vsnprintf(message.data(), message.size(), _format, _args);
+    m_callback(level, _nodeId, message.data());
+  }
+
+  // Nothing is queued, the Rust logger decides what to keep.
+  void QueueDump() override {}
+  void QueueClear() override {}
+
+  void SetLoggingState(LogLevel _saveLevel, LogLevel, LogLevel) override {
+    m_saveLevel = _saveLevel;
+  }
+
+  void SetLogFileName(const std::string &) override {}
+
+private:
+  RustLogCallback m_callback;
+  LogLevel m_saveLevel;
+};
+
+}  // namespace
+
+extern "C" {
+
+// OpenZWave takes ownership of the logger and deletes it with the Log. The Manager was created
+// with Logging off, so that no file is written: it's turned on again for this logger only.
+bool log_set_rust_logger(RustLogCallback callback) {
+  int saveLevel = OpenZWave::LogLevel_Detail;
+  OpenZWave::Options *options = OpenZWave::Options::Get();
+  if (options != NULL) {
+    options->GetOptionAsInt("SaveLogLevel", &saveLevel);
+  }
+
+  RustLogImpl *logger = new RustLogImpl(callback, static_cast<LogLevel>(saveLevel));
+  if (!OpenZWave::Log::SetLoggingClass(logger)) {
+    delete logger;
+    return false;
+  }
+  OpenZWave::Log::SetLoggingState(true);
+  return true;
+}
+
+}  // extern "C"
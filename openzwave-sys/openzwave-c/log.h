@@ -0,0 +1,14 @@
+#include <platform/Log.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// level follows the `log` crate: 1 = Error, 2 = Warn, 3 = Info, 4 = Debug, 5 = Trace
+typedef void (*RustLogCallback) (uint8 level, uint8 nodeId, char const * message);
+
+bool log_set_rust_logger(RustLogCallback callback);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
@@ -1,3 +1,4 @@
+#include <cstring>
 #include <string>
 #include <memory>
 #include "manager.h"
@@ -24,6 +25,14 @@ void manager_request_all_config_params(Manager * manager, uint32 const homeId, u
   manager->RequestAllConfigParams(homeId, nodeId);
 }
 
+bool manager_set_config_param(Manager * manager, uint32 const homeId, uint8 const nodeId, uint8 const param, int32 value, uint8 const size) {
+  return manager->SetConfigParam(homeId, nodeId, param, value, size);
+}
+
+void manager_request_config_param(Manager * manager, uint32 const homeId, uint8 const nodeId, uint8 const param) {
+  manager->RequestConfigParam(homeId, nodeId, param);
+}
+
 void manager_cancel_controller_command(Manager * manager, uint32 home_id) {
   manager->CancelControllerCommand(home_id);
 }
@@ -60,6 +69,89 @@ void manager_heal_network(Manager * manager, uint32 home_id, bool do_rr) {
   manager->HealNetwork(home_id, do_rr);
 }
 
+void manager_get_driver_statistics(Manager * manager, uint32 home_id, DriverStatistics * statistics) {
+  Driver::DriverData data;
+  manager->GetDriverStatistics(home_id, &data);
+  statistics->sof_count = data.m_SOFCnt;
+  statistics->ack_waiting = data.m_ACKWaiting;
+  statistics->read_aborts = data.m_readAborts;
+  statistics->bad_checksum = data.m_badChecksum;
+  statistics->read_count = data.m_readCnt;
+  statistics->write_count = data.m_writeCnt;
+  statistics->can_count = data.m_CANCnt;
+  statistics->nak_count = data.m_NAKCnt;
+  statistics->ack_count = data.m_ACKCnt;
+  statistics->oof_count = data.m_OOFCnt;
+  statistics->dropped = data.m_dropped;
+  statistics->retries = data.m_retries;
+  statistics->callbacks = data.m_callbacks;
+  statistics->bad_routes = data.m_badroutes;
+  statistics->no_ack = data.m_noack;
+  statistics->net_busy = data.m_netbusy;
+  statistics->not_idle = data.m_notidle;
+  statistics->tx_verified = data.m_txverified;
+  statistics->non_delivery = data.m_nondelivery;
+  statistics->routed_busy = data.m_routedbusy;
+  statistics->broadcast_read_count = data.m_broadcastReadCnt;
+  statistics->broadcast_write_count = data.m_broadcastWriteCnt;
+}
+
+bool manager_remove_failed_node(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->RemoveFailedNode(home_id, node_id);
+}
+
+bool manager_has_node_failed(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->HasNodeFailed(home_id, node_id);
+}
+
+bool manager_replace_failed_node(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->ReplaceFailedNode(home_id, node_id);
+}
+
+bool manager_request_network_update(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->RequestNetworkUpdate(home_id, node_id);
+}
+
+bool manager_request_node_neighbor_update(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->RequestNodeNeighborUpdate(home_id, node_id);
+}
+
+bool manager_assign_return_route(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->AssignReturnRoute(home_id, node_id);
+}
+
+bool manager_delete_all_return_routes(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->DeleteAllReturnRoutes(home_id, node_id);
+}
+
+bool manager_send_node_information(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->SendNodeInformation(home_id, node_id);
+}
+
+bool manager_replication_send(Manager * manager, uint32 home_id, uint8_t node_id) {
+  return manager->ReplicationSend(home_id, node_id);
+}
+
+bool manager_create_new_primary(Manager * manager, uint32 home_id) {
+  return manager->CreateNewPrimary(home_id);
+}
+
+bool manager_receive_configuration(Manager * manager, uint32 home_id) {
+  return manager->ReceiveConfiguration(home_id);
+}
+
+bool manager_transfer_primary_role(Manager * manager, uint32 home_id) {
+  return manager->TransferPrimaryRole(home_id);
+}
+
+bool manager_create_button(Manager * manager, uint32 home_id, uint8_t node_id, uint8_t button_id) {
+  return manager->CreateButton(home_id, node_id, button_id);
+}
+
+bool manager_delete_button(Manager * manager, uint32 home_id, uint8_t node_id, uint8_t button_id) {
+  return manager->DeleteButton(home_id, node_id, button_id);
+}
+
 bool manager_add_watcher(Manager * manager, Manager::pfnOnNotification_t _watcher, void* _context) {
   return manager->AddWatcher(_watcher, _context);
 }
@@ -302,6 +394,28 @@ GET_VALUE_FUNC(list_values, void ** rust_value, const RustI32VecCreator vecCreat
   return res;
 }
 
+GET_VALUE_FUNC(as_bitset, uint8 pos, bool* value) {
+  return manager->GetValueAsBitSet(*vid, pos, value);
+}
+
+GET_VALUE_FUNC(bitmask, int32* value) {
+  return manager->GetBitMask(*vid, value);
+}
+
+GET_VALUE_FUNC(bitset_size, uint8* value) {
+  return manager->GetBitSetSize(*vid, value);
+}
+
+char * manager_get_value_bit_label(Manager * manager, const ValueID *vid, uint8 pos, const RustStringCreator stringCreator) {
+  std::string result = "";
+  try {
+    result = manager->GetValueLabel(*vid, pos);
+  } catch (...) {
+    // NOP
+  }
+  return stringCreator(result.c_str());
+}
+
 SET_VALUE_FUNC(bool, bool value) {
   return manager->SetValue(*vid, value);
 }
@@ -336,6 +450,35 @@ SET_VALUE_FUNC(list_selection_string, const char* selected_item) {
   return manager->SetValue(*vid, selected_item_string);
 }
 
+// Selects the item having this value, not the one at this index
+SET_VALUE_FUNC(list_selection_int, int32 selected_value) {
+  return manager->SetValue(*vid, selected_value);
+}
+
+SET_VALUE_FUNC(bitset, uint8 pos, bool value) {
+  return manager->SetValue(*vid, pos, value);
+}
+
+uint8 manager_get_num_switch_points(Manager * manager, const ValueID * vid) {
+  return manager->GetNumSwitchPoints(*vid);
+}
+
+bool manager_get_switch_point(Manager * manager, const ValueID * vid, uint8 idx, uint8 * hours, uint8 * minutes, int8 * setback) {
+  return manager->GetSwitchPoint(*vid, idx, hours, minutes, setback);
+}
+
+bool manager_set_switch_point(Manager * manager, const ValueID * vid, uint8 hours, uint8 minutes, int8 setback) {
+  return manager->SetSwitchPoint(*vid, hours, minutes, setback);
+}
+
+bool manager_remove_switch_point(Manager * manager, const ValueID * vid, uint8 hours, uint8 minutes) {
+  return manager->RemoveSwitchPoint(*vid, hours, minutes);
+}
+
+void manager_clear_switch_points(Manager * manager, const ValueID * vid) {
+  manager->ClearSwitchPoints(*vid);
+}
+
 
 #define GET_NODE_FUNC_IMPL(name, name_impl, type) \
   GET_NODE_FUNC_NO_ARGS(name, type) { \
@@ -389,6 +532,25 @@ GET_NODE_FUNC(get_neighbors, void *, const RustU8VecCreator vecCreator) {
   return nullptr;
 }
 
+GET_NODE_FUNC(get_statistics, void, NodeStatistics * statistics, const RustStringCreator stringCreator) {
+  Node::NodeData data;
+  manager->GetNodeStatistics(home_id, node_id, &data);
+  statistics->sent_count = data.m_sentCnt;
+  statistics->sent_failed = data.m_sentFailed;
+  statistics->retries = data.m_retries;
+  statistics->received_count = data.m_receivedCnt;
+  statistics->received_dups = data.m_receivedDups;
+  statistics->received_unsolicited = data.m_receivedUnsolicited;
+  statistics->sent_timestamp = stringCreator(data.m_sentTS.c_str());
+  statistics->received_timestamp = stringCreator(data.m_receivedTS.c_str());
+  statistics->last_request_rtt = data.m_lastRequestRTT;
+  statistics->average_request_rtt = data.m_averageRequestRTT;
+  statistics->last_response_rtt = data.m_lastResponseRTT;
+  statistics->average_response_rtt = data.m_averageResponseRTT;
+  statistics->quality = data.m_quality;
+  memcpy(statistics->last_received_message, data.m_lastReceivedMessage, sizeof(statistics->last_received_message));
+}
+
 GET_NODE_FUNC(
     get_class_information, bool,
     uint8 const command_class_id, char** class_name, uint8* class_version,
@@ -401,4 +563,41 @@ GET_NODE_FUNC(
   return has_class;
 }
 
+GET_NODE_FUNC_NO_ARGS(get_num_groups, uint8) {
+  return manager->GetNumGroups(home_id, node_id);
+}
+
+GET_NODE_FUNC(get_group_label, char *, uint8 const group_idx, const RustStringCreator stringCreator) {
+  std::string label = manager->GetGroupLabel(home_id, node_id, group_idx);
+  return stringCreator(label.c_str());
+}
+
+GET_NODE_FUNC(get_max_associations, uint8, uint8 const group_idx) {
+  return manager->GetMaxAssociations(home_id, node_id, group_idx);
+}
+
+GET_NODE_FUNC(is_multi_instance_group, bool, uint8 const group_idx) {
+  return manager->IsMultiInstance(home_id, node_id, group_idx);
+}
+
+GET_NODE_FUNC(get_associations, void *, uint8 const group_idx, const RustInstanceAssociationVecCreator vecCreator) {
+  InstanceAssociation* associations = nullptr;
+  uint32 associations_count = manager->GetAssociations(home_id, node_id, group_idx, &associations);
+  void * result = nullptr;
+  if (associations_count && associations) {
+    result = vecCreator(associations, associations_count);
+  }
+  // allocated by OpenZWave for the caller
+  delete[] associations;
+  return result;
+}
+
+GET_NODE_FUNC(add_association, void, uint8 const group_idx, uint8 const target_node_id, uint8 const instance) {
+  manager->AddAssociation(home_id, node_id, group_idx, target_node_id, instance);
+}
+
+GET_NODE_FUNC(remove_association, void, uint8 const group_idx, uint8 const target_node_id, uint8 const instance) {
+  manager->RemoveAssociation(home_id, node_id, group_idx, target_node_id, instance);
+}
+
 } /* extern "C" */
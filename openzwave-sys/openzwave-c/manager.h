@@ -1,5 +1,6 @@
 #include <Manager.h>
 #include <Driver.h>
+#include <Node.h>
 #include "value_classes/value_id.h"
 #include "utils.h"
 
@@ -9,7 +10,55 @@ extern "C" {
 
 typedef OpenZWave::Manager Manager;
 typedef OpenZWave::Driver Driver;
+typedef OpenZWave::Node Node;
 typedef OpenZWave::ValueID ValueID;
+typedef OpenZWave::InstanceAssociation InstanceAssociation;
+
+DEFINE_RUST_VEC_CREATOR(RustInstanceAssociationVecCreator, InstanceAssociation)
+
+// Plain copy of Driver::DriverData
+typedef struct {
+  uint32 sof_count;
+  uint32 ack_waiting;
+  uint32 read_aborts;
+  uint32 bad_checksum;
+  uint32 read_count;
+  uint32 write_count;
+  uint32 can_count;
+  uint32 nak_count;
+  uint32 ack_count;
+  uint32 oof_count;
+  uint32 dropped;
+  uint32 retries;
+  uint32 callbacks;
+  uint32 bad_routes;
+  uint32 no_ack;
+  uint32 net_busy;
+  uint32 not_idle;
+  uint32 tx_verified;
+  uint32 non_delivery;
+  uint32 routed_busy;
+  uint32 broadcast_read_count;
+  uint32 broadcast_write_count;
+} DriverStatistics;
+
+// The counters and link quality part of Node::NodeData
+typedef struct {
+  uint32 sent_count;
+  uint32 sent_failed;
+  uint32 retries;
+  uint32 received_count;
+  uint32 received_dups;
+  uint32 received_unsolicited;
+  char * sent_timestamp;
+  char * received_timestamp;
+  uint32 last_request_rtt;
+  uint32 average_request_rtt;
+  uint32 last_response_rtt;
+  uint32 average_response_rtt;
+  uint8 quality;
+  uint8 last_received_message[254];
+} NodeStatistics;
 
 Manager * manager_create();
 Manager * manager_get();
@@ -23,6 +72,8 @@ void manager_cancel_controller_command(Manager * manager, uint32 home_id);
 // Query functions
 bool manager_request_node_state(Manager * manager, uint32 const homeId, uint8 const nodeId);
 void manager_request_all_config_params(Manager * manager, uint32 const homeId, uint8 const nodeId);
+bool manager_set_config_param(Manager * manager, uint32 const homeId, uint8 const nodeId, uint8 const param, int32 value, uint8 const size);
+void manager_request_config_param(Manager * manager, uint32 const homeId, uint8 const nodeId, uint8 const param);
 
 // Network functions
 bool manager_add_node(Manager * manager, uint32 home_id, bool secure);
@@ -31,6 +82,23 @@ void manager_test_network_node(Manager * manager, uint32 home_id, uint8_t node_i
 void manager_test_network(Manager * manager, uint32 home_id, uint32_t count);
 void manager_heal_network_node(Manager * manager, uint32 home_id, uint8_t node_id, bool doRR);
 void manager_heal_network(Manager * manager, uint32 home_id, bool doRR);
+void manager_get_driver_statistics(Manager * manager, uint32 home_id, DriverStatistics * statistics);
+
+// Controller commands, their progress is reported through ControllerCommand notifications
+bool manager_remove_failed_node(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_has_node_failed(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_replace_failed_node(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_request_network_update(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_request_node_neighbor_update(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_assign_return_route(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_delete_all_return_routes(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_send_node_information(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_replication_send(Manager * manager, uint32 home_id, uint8_t node_id);
+bool manager_create_new_primary(Manager * manager, uint32 home_id);
+bool manager_receive_configuration(Manager * manager, uint32 home_id);
+bool manager_transfer_primary_role(Manager * manager, uint32 home_id);
+bool manager_create_button(Manager * manager, uint32 home_id, uint8_t node_id, uint8_t button_id);
+bool manager_delete_button(Manager * manager, uint32 home_id, uint8_t node_id, uint8_t button_id);
 bool manager_add_watcher(Manager * manager, Manager::pfnOnNotification_t _watcher, void* _context);
 bool manager_remove_watcher(Manager * manager, Manager::pfnOnNotification_t _watcher, void* _context);
 bool manager_add_driver(Manager * manager, const char * _controllerPath, const Driver::ControllerInterface * _interface);
@@ -84,6 +152,10 @@ GET_VALUE_FUNC(list_selection_as_string, char**, const RustStringCreator);
 GET_VALUE_FUNC(list_selection_as_int, int32*);
 GET_VALUE_FUNC(list_items, void ** value, const RustStringVecCreator);
 GET_VALUE_FUNC(list_values, void ** value, const RustI32VecCreator);
+GET_VALUE_FUNC(as_bitset, uint8 pos, bool*);
+GET_VALUE_FUNC(bitmask, int32*);
+GET_VALUE_FUNC(bitset_size, uint8*);
+char * manager_get_value_bit_label(Manager * manager, const ValueID *vid, uint8 pos, const RustStringCreator stringCreator);
 
 
 #define SET_VALUE_FUNC(name, ...) \
@@ -97,6 +169,15 @@ SET_VALUE_FUNC(short, int16);
 SET_VALUE_FUNC(string, const char*);
 SET_VALUE_FUNC(raw, const uint8 * value, uint8 len);
 SET_VALUE_FUNC(list_selection_string, const char*);
+SET_VALUE_FUNC(list_selection_int, int32);
+SET_VALUE_FUNC(bitset, uint8 pos, bool);
+
+// Switch points of ValueType_Schedule values
+uint8 manager_get_num_switch_points(Manager * manager, const ValueID * vid);
+bool manager_get_switch_point(Manager * manager, const ValueID * vid, uint8 idx, uint8 * hours, uint8 * minutes, int8 * setback);
+bool manager_set_switch_point(Manager * manager, const ValueID * vid, uint8 hours, uint8 minutes, int8 setback);
+bool manager_remove_switch_point(Manager * manager, const ValueID * vid, uint8 hours, uint8 minutes);
+void manager_clear_switch_points(Manager * manager, const ValueID * vid);
 
 #define GET_NODE_FUNC(name, return_type, ...) \
   return_type manager_node_ ## name (Manager * manager, uint32 home_id, uint8 node_id, __VA_ARGS__)
@@ -143,6 +224,15 @@ GET_NODE_FUNC(
     get_class_information, bool,
     uint8 const command_class_id, char** class_name, uint8* class_version,
     const RustStringCreator);
+GET_NODE_FUNC(get_statistics, void, NodeStatistics * statistics, const RustStringCreator);
+
+GET_NODE_FUNC_NO_ARGS(get_num_groups, uint8);
+GET_NODE_FUNC(get_group_label, char *, uint8 const group_idx, const RustStringCreator);
+GET_NODE_FUNC(get_max_associations, uint8, uint8 const group_idx);
+GET_NODE_FUNC(is_multi_instance_group, bool, uint8 const group_idx);
+GET_NODE_FUNC(get_associations, void *, uint8 const group_idx, const RustInstanceAssociationVecCreator);
+GET_NODE_FUNC(add_association, void, uint8 const group_idx, uint8 const target_node_id, uint8 const instance);
+GET_NODE_FUNC(remove_association, void, uint8 const group_idx, uint8 const target_node_id, uint8 const instance);
 
 //TODO node neighbors
 #ifdef __cplusplus
@@ -42,6 +42,18 @@ extern "C" {
     return notification->GetByte();
   }
 
+  uint8 notification_get_command(const Notification * notification) {
+    return notification->GetCommand();
+  }
+
+  uint8 notification_get_user_alert_type(const Notification * notification) {
+    return static_cast<uint8>(notification->GetUserAlertType());
+  }
+
+  uint8 notification_get_retry(const Notification * notification) {
+    return notification->GetRetry();
+  }
+
   char const * notification_get_as_string(const Notification * notification, const RustStringCreator stringCreator) {
     // We can't just return c_str() because the underlying buffer for "string"
     // would be deallocated after the end of this function. Thats why we have a
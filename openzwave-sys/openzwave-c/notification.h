@@ -31,6 +31,14 @@ uint8 notification_get_notification(const Notification *);
 
 uint8 notification_get_byte(const Notification *);
 
+// Only for Type_ControllerCommand notifications
+uint8 notification_get_command(const Notification *);
+
+// Only for Type_UserAlerts notifications
+uint8 notification_get_user_alert_type(const Notification *);
+
+uint8 notification_get_retry(const Notification *);
+
 // RustStringCreator will be passed a NUL-ended C String so that it can
 // allocates a Rust-owned buffer. This is made necessary by OpenZWave's
 // Notification::GetAsString that returns a std::string by value and thus that
@@ -1,6 +1,7 @@
 extern crate libc;
 
 pub mod utils;
+pub mod log;
 pub mod manager;
 pub mod options;
 pub mod notification;
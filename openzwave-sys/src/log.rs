@@ -0,0 +1,8 @@
+use libc::c_char;
+
+/// `level` follows the `log` crate: 1 = Error, 2 = Warn, 3 = Info, 4 = Debug, 5 = Trace
+pub type RustLogCallback = extern "C" fn(level: u8, node_id: u8, message: *const c_char);
+
+extern {
+    pub fn log_set_rust_logger(callback: RustLogCallback) -> bool;
+}
@@ -5,6 +5,59 @@ use value_classes::value_id::ValueID;
 
 pub enum Manager {}
 
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InstanceAssociation {
+    pub node_id: u8,
+    pub instance: u8,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DriverStatistics {
+    pub sof_count: u32,
+    pub ack_waiting: u32,
+    pub read_aborts: u32,
+    pub bad_checksum: u32,
+    pub read_count: u32,
+    pub write_count: u32,
+    pub can_count: u32,
+    pub nak_count: u32,
+    pub ack_count: u32,
+    pub oof_count: u32,
+    pub dropped: u32,
+    pub retries: u32,
+    pub callbacks: u32,
+    pub bad_routes: u32,
+    pub no_ack: u32,
+    pub net_busy: u32,
+    pub not_idle: u32,
+    pub tx_verified: u32,
+    pub non_delivery: u32,
+    pub routed_busy: u32,
+    pub broadcast_read_count: u32,
+    pub broadcast_write_count: u32,
+}
+
+// The timestamps are created with the RustStringCreator given to manager_node_get_statistics
+#[repr(C)]
+pub struct NodeStatistics {
+    pub sent_count: u32,
+    pub sent_failed: u32,
+    pub retries: u32,
+    pub received_count: u32,
+    pub received_dups: u32,
+    pub received_unsolicited: u32,
+    pub sent_timestamp: *mut c_char,
+    pub received_timestamp: *mut c_char,
+    pub last_request_rtt: u32,
+    pub average_request_rtt: u32,
+    pub last_response_rtt: u32,
+    pub average_response_rtt: u32,
+    pub quality: u8,
+    pub last_received_message: [u8; 254],
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub enum ControllerInterface {
@@ -15,24 +68,33 @@ pub enum ControllerInterface {
 
 pub use self::{
     manager_add_driver as add_driver, manager_add_node as add_node,
-    manager_add_watcher as add_watcher,
-    manager_cancel_controller_command as cancel_controller_command, manager_create as create,
-    manager_destroy as destroy, manager_disable_poll as disable_poll,
-    manager_enable_poll as enable_poll,
+    manager_add_watcher as add_watcher, manager_assign_return_route as assign_return_route,
+    manager_cancel_controller_command as cancel_controller_command,
+    manager_clear_switch_points as clear_switch_points, manager_create as create,
+    manager_create_button as create_button, manager_create_new_primary as create_new_primary,
+    manager_delete_all_return_routes as delete_all_return_routes,
+    manager_delete_button as delete_button, manager_destroy as destroy,
+    manager_disable_poll as disable_poll, manager_enable_poll as enable_poll,
     manager_enable_poll_with_intensity as enable_poll_with_intensity, manager_get as get,
     manager_get_controller_interface_type as get_controller_interface_type,
     manager_get_controller_node_id as get_controller_node_id,
     manager_get_controller_path as get_controller_path,
+    manager_get_driver_statistics as get_driver_statistics,
     manager_get_library_type_name as get_library_type_name,
     manager_get_library_version as get_library_version,
+    manager_get_num_switch_points as get_num_switch_points,
     manager_get_poll_intensity as get_poll_intensity,
     manager_get_poll_interval as get_poll_interval,
     manager_get_send_queue_count as get_send_queue_count,
-    manager_get_suc_node_id as get_suc_node_id, manager_get_value_as_bool as get_value_as_bool,
-    manager_get_value_as_byte as get_value_as_byte,
+    manager_get_suc_node_id as get_suc_node_id, manager_get_switch_point as get_switch_point,
+    manager_get_value_as_bitset as get_value_as_bitset,
+    manager_get_value_as_bool as get_value_as_bool, manager_get_value_as_byte as get_value_as_byte,
     manager_get_value_as_float as get_value_as_float, manager_get_value_as_int as get_value_as_int,
     manager_get_value_as_raw as get_value_as_raw, manager_get_value_as_short as get_value_as_short,
     manager_get_value_as_string as get_value_as_string,
+    manager_get_value_bit_label as get_value_bit_label,
+    manager_get_value_bitmask as get_value_bitmask,
+    manager_get_value_bitset_size as get_value_bitset_size,
     manager_get_value_float_precision as get_value_float_precision,
     manager_get_value_help as get_value_help, manager_get_value_label as get_value_label,
     manager_get_value_list_items as get_value_list_items,
@@ -40,21 +102,28 @@ pub use self::{
     manager_get_value_list_selection_as_string as get_value_list_selection_as_string,
     manager_get_value_list_values as get_value_list_values, manager_get_value_max as get_value_max,
     manager_get_value_min as get_value_min, manager_get_value_units as get_value_units,
-    manager_heal_network as heal_network, manager_heal_network_node as heal_network_node,
+    manager_has_node_failed as has_node_failed, manager_heal_network as heal_network,
+    manager_heal_network_node as heal_network_node,
     manager_is_bridge_controller as is_bridge_controller, manager_is_polled as is_polled,
     manager_is_primary_controller as is_primary_controller,
     manager_is_value_polled as is_value_polled, manager_is_value_read_only as is_value_read_only,
     manager_is_value_set as is_value_set, manager_is_value_write_only as is_value_write_only,
     manager_log_driver_statistics as log_driver_statistics,
+    manager_node_add_association as add_node_association,
+    manager_node_get_associations as get_node_associations,
     manager_node_get_basic as get_node_basic,
     manager_node_get_class_information as get_node_class_information,
     manager_node_get_device_type as get_node_device_type,
     manager_node_get_device_type_string as get_node_device_type_string,
-    manager_node_get_generic as get_node_generic, manager_node_get_location as get_node_location,
+    manager_node_get_generic as get_node_generic,
+    manager_node_get_group_label as get_node_group_label,
+    manager_node_get_location as get_node_location,
     manager_node_get_manufacturer_id as get_node_manufacturer_id,
     manager_node_get_manufacturer_name as get_node_manufacturer_name,
+    manager_node_get_max_associations as get_node_max_associations,
     manager_node_get_max_baud_rate as get_node_max_baud_rate,
     manager_node_get_name as get_node_name, manager_node_get_neighbors as get_node_neighbors,
+    manager_node_get_num_groups as get_node_num_groups,
     manager_node_get_plus_type as get_node_plus_type,
     manager_node_get_plus_type_string as get_node_plus_type_string,
     manager_node_get_product_id as get_node_product_id,
@@ -63,29 +132,42 @@ pub use self::{
     manager_node_get_query_stage as get_node_query_stage, manager_node_get_role as get_node_role,
     manager_node_get_role_string as get_node_role_string,
     manager_node_get_security as get_node_security, manager_node_get_specific as get_node_specific,
-    manager_node_get_type as get_node_type, manager_node_get_version as get_node_version,
-    manager_node_is_awake as is_node_awake,
+    manager_node_get_statistics as get_node_statistics, manager_node_get_type as get_node_type,
+    manager_node_get_version as get_node_version, manager_node_is_awake as is_node_awake,
     manager_node_is_beaming_device as is_node_beaming_device,
     manager_node_is_failed as is_node_failed,
     manager_node_is_frequent_listening_device as is_node_frequent_listening_device,
     manager_node_is_info_received as is_node_info_received,
     manager_node_is_listening_device as is_node_listening_device,
+    manager_node_is_multi_instance_group as is_node_multi_instance_group,
     manager_node_is_routing_device as is_node_routing_device,
     manager_node_is_security_device as is_node_security_device,
-    manager_node_is_zwave_plus as is_node_zwave_plus, manager_remove_driver as remove_driver,
-    manager_remove_node as remove_node, manager_remove_watcher as remove_watcher,
+    manager_node_is_zwave_plus as is_node_zwave_plus,
+    manager_node_remove_association as remove_node_association,
+    manager_receive_configuration as receive_configuration, manager_remove_driver as remove_driver,
+    manager_remove_failed_node as remove_failed_node, manager_remove_node as remove_node,
+    manager_remove_switch_point as remove_switch_point, manager_remove_watcher as remove_watcher,
+    manager_replace_failed_node as replace_failed_node,
+    manager_replication_send as replication_send,
     manager_request_all_config_params as request_all_config_params,
+    manager_request_config_param as request_config_param,
+    manager_request_network_update as request_network_update,
+    manager_request_node_neighbor_update as request_node_neighbor_update,
     manager_request_node_state as request_node_state, manager_reset_controller as reset_controller,
-    manager_set_poll_intensity as set_poll_intensity,
-    manager_set_poll_interval as set_poll_interval, manager_set_value_bool as set_value_bool,
+    manager_send_node_information as send_node_information,
+    manager_set_config_param as set_config_param, manager_set_poll_intensity as set_poll_intensity,
+    manager_set_poll_interval as set_poll_interval, manager_set_switch_point as set_switch_point,
+    manager_set_value_bitset as set_value_bitset, manager_set_value_bool as set_value_bool,
     manager_set_value_byte as set_value_byte, manager_set_value_float as set_value_float,
     manager_set_value_help as set_value_help, manager_set_value_int as set_value_int,
     manager_set_value_label as set_value_label,
+    manager_set_value_list_selection_int as set_value_list_selection_int,
     manager_set_value_list_selection_string as set_value_list_selection_string,
     manager_set_value_raw as set_value_raw, manager_set_value_short as set_value_short,
     manager_set_value_string as set_value_string, manager_set_value_units as set_value_units,
     manager_soft_reset_controller as soft_reset_controller, manager_test_network as test_network,
     manager_test_network_node as test_network_node,
+    manager_transfer_primary_role as transfer_primary_role,
 };
 
 extern "C" {
@@ -94,6 +176,15 @@ extern "C" {
     pub fn manager_destroy();
     pub fn manager_request_node_state(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
     pub fn manager_request_all_config_params(manager: *mut Manager, home_id: u32, node_id: u8);
+    pub fn manager_set_config_param(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        param: u8,
+        value: i32,
+        size: u8,
+    ) -> bool;
+    pub fn manager_request_config_param(manager: *mut Manager, home_id: u32, node_id: u8, param: u8);
     pub fn manager_reset_controller(manager: *mut Manager, home_id: u32);
     pub fn manager_soft_reset_controller(manager: *mut Manager, home_id: u32);
     pub fn manager_cancel_controller_command(manager: *mut Manager, home_id: u32);
@@ -103,6 +194,21 @@ extern "C" {
     pub fn manager_test_network(manager: *mut Manager, home_id: u32, count: u32);
     pub fn manager_heal_network_node(manager: *mut Manager, home_id: u32, node_id: u8, do_rr: bool);
     pub fn manager_heal_network(manager: *mut Manager, home_id: u32, do_rr: bool);
+    pub fn manager_get_driver_statistics(manager: *mut Manager, home_id: u32, statistics: *mut DriverStatistics);
+    pub fn manager_remove_failed_node(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_has_node_failed(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_replace_failed_node(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_request_network_update(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_request_node_neighbor_update(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_assign_return_route(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_delete_all_return_routes(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_send_node_information(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_replication_send(manager: *mut Manager, home_id: u32, node_id: u8) -> bool;
+    pub fn manager_create_new_primary(manager: *mut Manager, home_id: u32) -> bool;
+    pub fn manager_receive_configuration(manager: *mut Manager, home_id: u32) -> bool;
+    pub fn manager_transfer_primary_role(manager: *mut Manager, home_id: u32) -> bool;
+    pub fn manager_create_button(manager: *mut Manager, home_id: u32, node_id: u8, button_id: u8) -> bool;
+    pub fn manager_delete_button(manager: *mut Manager, home_id: u32, node_id: u8, button_id: u8) -> bool;
     pub fn manager_add_watcher(
         manager: *mut Manager,
         cb: extern "C" fn(notification: *const Notification, ctx: *const c_void),
@@ -251,6 +357,20 @@ extern "C" {
         result: *mut *mut c_void,
         vecCreator: RustVecCreator<i32>,
     ) -> bool;
+    pub fn manager_get_value_as_bitset(
+        manager: *mut Manager,
+        id: *const ValueID,
+        pos: u8,
+        result: *mut bool,
+    ) -> bool;
+    pub fn manager_get_value_bitmask(manager: *mut Manager, id: *const ValueID, result: *mut i32) -> bool;
+    pub fn manager_get_value_bitset_size(manager: *mut Manager, id: *const ValueID, result: *mut u8) -> bool;
+    pub fn manager_get_value_bit_label(
+        manager: *mut Manager,
+        id: *const ValueID,
+        pos: u8,
+        stringCreator: RustStringCreator,
+    ) -> *mut c_char;
 
     pub fn manager_set_value_bool(manager: *mut Manager, id: *const ValueID, value: bool) -> bool;
     pub fn manager_set_value_byte(manager: *mut Manager, id: *const ValueID, value: u8) -> bool;
@@ -277,6 +397,27 @@ extern "C" {
         id: *const ValueID,
         value: *const c_char,
     ) -> bool;
+    pub fn manager_set_value_list_selection_int(manager: *mut Manager, id: *const ValueID, value: i32) -> bool;
+    pub fn manager_set_value_bitset(manager: *mut Manager, id: *const ValueID, pos: u8, value: bool) -> bool;
+
+    pub fn manager_get_num_switch_points(manager: *mut Manager, id: *const ValueID) -> u8;
+    pub fn manager_get_switch_point(
+        manager: *mut Manager,
+        id: *const ValueID,
+        idx: u8,
+        hours: *mut u8,
+        minutes: *mut u8,
+        setback: *mut i8,
+    ) -> bool;
+    pub fn manager_set_switch_point(
+        manager: *mut Manager,
+        id: *const ValueID,
+        hours: u8,
+        minutes: u8,
+        setback: i8,
+    ) -> bool;
+    pub fn manager_remove_switch_point(manager: *mut Manager, id: *const ValueID, hours: u8, minutes: u8) -> bool;
+    pub fn manager_clear_switch_points(manager: *mut Manager, id: *const ValueID);
 
     pub fn manager_node_is_listening_device(
         manager: *mut Manager,
@@ -391,6 +532,13 @@ extern "C" {
         node_id: u8,
         vecCreator: RustVecCreator<u8>,
     ) -> *mut c_void;
+    pub fn manager_node_get_statistics(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        statistics: *mut NodeStatistics,
+        rust_string_creator: RustStringCreator,
+    );
     pub fn manager_node_get_class_information(
         manager: *mut Manager,
         home_id: u32,
@@ -400,4 +548,48 @@ extern "C" {
         class_version: *mut u8,
         stringCreator: RustStringCreator,
     ) -> bool;
+
+    pub fn manager_node_get_num_groups(manager: *mut Manager, home_id: u32, node_id: u8) -> u8;
+    pub fn manager_node_get_group_label(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+        stringCreator: RustStringCreator,
+    ) -> *mut c_char;
+    pub fn manager_node_get_max_associations(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+    ) -> u8;
+    pub fn manager_node_is_multi_instance_group(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+    ) -> bool;
+    pub fn manager_node_get_associations(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+        vecCreator: RustVecCreator<InstanceAssociation>,
+    ) -> *mut c_void;
+    pub fn manager_node_add_association(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+        target_node_id: u8,
+        instance: u8,
+    );
+    pub fn manager_node_remove_association(
+        manager: *mut Manager,
+        home_id: u32,
+        node_id: u8,
+        group_idx: u8,
+        target_node_id: u8,
+        instance: u8,
+    );
 }
@@ -105,6 +105,55 @@ impl fmt::Display for ControllerError {
     }
 }
 
+c_like_enum! {
+    ControllerCommandType {
+        None = 0,
+        AddDevice = 1,
+        CreateNewPrimary = 2,
+        ReceiveConfiguration = 3,
+        RemoveDevice = 4,
+        RemoveFailedNode = 5,
+        HasNodeFailed = 6,
+        ReplaceFailedNode = 7,
+        TransferPrimaryRole = 8,
+        RequestNetworkUpdate = 9,
+        RequestNodeNeighborUpdate = 10,
+        AssignReturnRoute = 11,
+        DeleteAllReturnRoutes = 12,
+        SendNodeInformation = 13,
+        ReplicationSend = 14,
+        CreateButton = 15,
+        DeleteButton = 16
+    }
+}
+
+impl fmt::Display for ControllerCommandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self))
+    }
+}
+
+c_like_enum! {
+    UserAlertType {
+        None = 0,
+        ConfigOutOfDate = 1,           // A newer device config file is available
+        MFSOutOfDate = 2,              // A newer manufacturer specific database is available
+        ConfigFileDownloadFailed = 3,  // A config file failed to download
+        DNSError = 4,                  // The version lookup failed
+        NodeReloadRequired = 5,        // A new config file was loaded, the node must be refreshed
+        UnsupportedController = 6,     // The controller library is not a static or bridge one
+        ApplicationStatusRetry = 7,    // The device is busy, retry later (see GetRetry)
+        ApplicationStatusQueued = 8,   // The command will run later
+        ApplicationStatusRejected = 9  // The command was rejected
+    }
+}
+
+impl fmt::Display for UserAlertType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self))
+    }
+}
+
 pub enum Notification {}
 extern "C" {
     pub fn notification_get_type(notification: *const Notification) -> u8;
@@ -117,6 +166,9 @@ extern "C" {
     pub fn notification_get_scene_id(notification: *const Notification) -> u8;
     pub fn notification_get_notification(notification: *const Notification) -> u8;
     pub fn notification_get_byte(notification: *const Notification) -> u8;
+    pub fn notification_get_command(notification: *const Notification) -> u8;
+    pub fn notification_get_user_alert_type(notification: *const Notification) -> u8;
+    pub fn notification_get_retry(notification: *const Notification) -> u8;
     pub fn notification_get_as_string(
         notification: *const Notification,
         rust_string_creator: RustStringCreator,
@@ -40,9 +40,10 @@ pub enum ValueType {
     String,
     Button,
     Raw,
+    BitSet,
     //
     Unknown = 255,
-    //ValueType_Max = ValueType_Raw // likely useless in Rust wrapper
+    //ValueType_Max = ValueType_BitSet // likely useless in Rust wrapper
 }
 
 impl TryFrom<u8> for ValueType {
@@ -59,6 +60,7 @@ impl TryFrom<u8> for ValueType {
             7 => Ok(Self::String),
             8 => Ok(Self::Button),
             9 => Ok(Self::Raw),
+            10 => Ok(Self::BitSet),
             _ => Err(()),
         }
     }
@@ -0,0 +1,161 @@
+use crate::backend;
+use crate::error::{ConfirmationError, Error, GetSetError, Result};
+use crate::node::Node;
+use crate::notification::{NotificationType, NotificationValue};
+use crate::value_classes::confirm::confirm;
+use std::time::Duration;
+
+pub use ffi::manager::InstanceAssociation;
+
+/// What a node reports about one of its association groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssociationGroup {
+    pub index: u8,
+    pub label: String,
+    pub max_associations: u8,
+    /// Whether the group accepts associations to a given instance of the target node
+    pub multi_instance: bool,
+    pub associations: Vec<InstanceAssociation>,
+}
+
+impl AssociationGroup {
+    pub fn contains(&self, node_id: u8, instance: u8) -> bool {
+        self.associations.contains(&InstanceAssociation { node_id, instance })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.associations.len() >= self.max_associations as usize
+    }
+}
+
+/// Association groups of a node, see `Node::associations`.
+///
+/// Groups are numbered from 1. Instance 0 targets the node itself rather than one of its
+/// instances.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Associations {
+    node: Node,
+}
+
+impl Associations {
+    pub(crate) fn new(node: Node) -> Associations {
+        Associations { node }
+    }
+
+    pub fn num_groups(&self) -> u8 {
+        let (home_id, node_id) = (self.node.get_home_id(), self.node.get_id());
+        backend::get(home_id).get_node_num_groups(home_id, node_id)
+    }
+
+    pub fn group(&self, index: u8) -> Option<AssociationGroup> {
+        if index == 0 || index > self.num_groups() {
+            return None;
+        }
+        let backend = backend::get(self.node.get_home_id());
+        let (home_id, node_id) = (self.node.get_home_id(), self.node.get_id());
+        Some(AssociationGroup {
+            index,
+            label: backend.get_node_group_label(home_id, node_id, index),
+            max_associations: backend.get_node_max_associations(home_id, node_id, index),
+            multi_instance: backend.is_node_multi_instance_group(home_id, node_id, index),
+            associations: backend.get_node_associations(home_id, node_id, index),
+        })
+    }
+
+    pub fn groups(&self) -> Vec<AssociationGroup> {
+        (1..=self.num_groups()).filter_map(|index| self.group(index)).collect()
+    }
+
+    /// Associates `target_node_id` to the group and waits for the node to report the group.
+    ///
+    /// Fails with `ConfirmationError::Rejected` if the report doesn't include the association,
+    /// e.g. when the group is full.
+    pub async fn add(
+        &self,
+        group: u8,
+        target_node_id: u8,
+        instance: u8,
+        timeout: Duration,
+    ) -> Result<AssociationGroup> {
+        let target = self.check_target(group, target_node_id, instance, "Associations::add")?;
+        let updated = self
+            .change(group, timeout, |backend, home_id, node_id| {
+                backend.add_node_association(home_id, node_id, group, target)
+            })
+            .await?;
+        if updated.contains(target_node_id, instance) {
+            Ok(updated)
+        } else {
+            Err(Error::ConfirmationError(ConfirmationError::Rejected))
+        }
+    }
+
+    /// Removes `target_node_id` from the group and waits for the node to report the group.
+    pub async fn remove(
+        &self,
+        group: u8,
+        target_node_id: u8,
+        instance: u8,
+        timeout: Duration,
+    ) -> Result<AssociationGroup> {
+        let target = self.check_target(group, target_node_id, instance, "Associations::remove")?;
+        let updated = self
+            .change(group, timeout, |backend, home_id, node_id| {
+                backend.remove_node_association(home_id, node_id, group, target)
+            })
+            .await?;
+        if updated.contains(target_node_id, instance) {
+            Err(Error::ConfirmationError(ConfirmationError::Rejected))
+        } else {
+            Ok(updated)
+        }
+    }
+
+    fn check_target(
+        &self,
+        group: u8,
+        target_node_id: u8,
+        instance: u8,
+        method: &'static str,
+    ) -> Result<InstanceAssociation> {
+        let found = self.group(group).ok_or(Error::InvalidParameter("group", method))?;
+        if target_node_id == 0 {
+            return Err(Error::InvalidParameter("target_node_id", method));
+        }
+        if instance != 0 && !found.multi_instance {
+            return Err(Error::InvalidParameter("instance", method));
+        }
+        Ok(InstanceAssociation {
+            node_id: target_node_id,
+            instance,
+        })
+    }
+
+    // Resolves once OpenZWave notifies the group again, with its new content
+    async fn change<F>(&self, group: u8, timeout: Duration, write: F) -> Result<AssociationGroup>
+    where
+        F: FnOnce(&dyn backend::ZWaveBackend, u32, u8),
+    {
+        let (home_id, node_id) = (self.node.get_home_id(), self.node.get_id());
+        confirm(
+            home_id,
+            node_id,
+            move |notification| match (notification.notification_type, &notification.value) {
+                (NotificationType::Group, Some(NotificationValue::Group(index)))
+                    if notification.node_id == node_id && *index == group =>
+                {
+                    Some(())
+                }
+                _ => None,
+            },
+            || {
+                write(&*backend::get(home_id), home_id, node_id);
+                Ok(())
+            },
+            timeout,
+        )
+        .await?;
+        self.group(group)
+            .ok_or(Error::GetError(GetSetError::APIError("GetAssociations")))
+    }
+}
@@ -0,0 +1,198 @@
+pub mod openzwave;
+pub mod simulated;
+
+use crate::manager::NotificationWatcher;
+use crate::statistics::{DriverStatistics, NodeStatistics};
+use crate::value_classes::value_id::{ExternValueID, SwitchPoint};
+use ffi::manager::{ControllerInterface, InstanceAssociation};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+pub use self::openzwave::OpenZWaveBackend;
+pub use self::simulated::{SimulatedGroup, SimulatedNetwork, SimulatedNode, SimulatedValue};
+
+/// Everything `Node`, `Controller`, `ValueID` and `Manager` need from the Z-Wave stack.
+///
+/// `OpenZWaveBackend` forwards to the C++ library, `SimulatedNetwork` keeps an in-memory
+/// network that can be scripted from tests.
+pub trait ZWaveBackend: Send + Sync {
+    // Manager
+    fn add_watcher(&self, watcher: Arc<dyn NotificationWatcher>) -> bool;
+    fn remove_watcher(&self, watcher: &Arc<dyn NotificationWatcher>) -> bool;
+    fn add_driver(&self, device: &str, interface: ControllerInterface) -> bool;
+    fn remove_driver(&self, device: &str) -> bool;
+    fn get_poll_interval(&self) -> i32;
+    fn set_poll_interval(&self, interval_ms: i32, is_between_each_poll: bool);
+
+    // Controller
+    fn get_controller_node_id(&self, home_id: u32) -> u8;
+    fn get_suc_node_id(&self, home_id: u32) -> u8;
+    fn is_primary_controller(&self, home_id: u32) -> bool;
+    fn is_bridge_controller(&self, home_id: u32) -> bool;
+    fn get_send_queue_count(&self, home_id: u32) -> i32;
+    fn log_driver_statistics(&self, home_id: u32);
+    fn get_driver_statistics(&self, home_id: u32) -> DriverStatistics;
+    fn get_controller_interface_type(&self, home_id: u32) -> ControllerInterface;
+    fn get_library_version(&self, home_id: u32) -> String;
+    fn get_library_type_name(&self, home_id: u32) -> String;
+    fn get_controller_path(&self, home_id: u32) -> String;
+    fn reset_controller(&self, home_id: u32);
+    fn soft_reset_controller(&self, home_id: u32);
+    fn cancel_controller_command(&self, home_id: u32);
+    fn add_node(&self, home_id: u32, secure: bool) -> bool;
+    fn remove_node(&self, home_id: u32) -> bool;
+    fn test_network(&self, home_id: u32, count: u32);
+    fn test_network_node(&self, home_id: u32, node_id: u8, count: u32);
+    fn heal_network(&self, home_id: u32, do_rr: bool);
+    fn heal_network_node(&self, home_id: u32, node_id: u8, do_rr: bool);
+
+    // Controller commands: true if the command was started, its outcome is notified
+    fn remove_failed_node(&self, home_id: u32, node_id: u8) -> bool;
+    fn has_node_failed(&self, home_id: u32, node_id: u8) -> bool;
+    fn replace_failed_node(&self, home_id: u32, node_id: u8) -> bool;
+    fn request_network_update(&self, home_id: u32, node_id: u8) -> bool;
+    fn request_node_neighbor_update(&self, home_id: u32, node_id: u8) -> bool;
+    fn assign_return_route(&self, home_id: u32, node_id: u8) -> bool;
+    fn delete_all_return_routes(&self, home_id: u32, node_id: u8) -> bool;
+    fn send_node_information(&self, home_id: u32, node_id: u8) -> bool;
+    fn replication_send(&self, home_id: u32, node_id: u8) -> bool;
+    fn create_new_primary(&self, home_id: u32) -> bool;
+    fn receive_configuration(&self, home_id: u32) -> bool;
+    fn transfer_primary_role(&self, home_id: u32) -> bool;
+    fn create_button(&self, home_id: u32, node_id: u8, button_id: u8) -> bool;
+    fn delete_button(&self, home_id: u32, node_id: u8, button_id: u8) -> bool;
+
+    // Node
+    fn request_node_state(&self, home_id: u32, node_id: u8) -> bool;
+    fn request_all_config_params(&self, home_id: u32, node_id: u8);
+    fn set_config_param(&self, home_id: u32, node_id: u8, param: u8, value: i32, size: u8) -> bool;
+    fn request_config_param(&self, home_id: u32, node_id: u8, param: u8);
+    fn is_node_listening_device(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_frequent_listening_device(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_beaming_device(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_routing_device(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_security_device(&self, home_id: u32, node_id: u8) -> bool;
+    fn get_node_max_baud_rate(&self, home_id: u32, node_id: u8) -> u32;
+    fn get_node_version(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_security(&self, home_id: u32, node_id: u8) -> u8;
+    fn is_node_zwave_plus(&self, home_id: u32, node_id: u8) -> bool;
+    fn get_node_basic(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_generic(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_specific(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_device_type(&self, home_id: u32, node_id: u8) -> u16;
+    fn get_node_role(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_plus_type(&self, home_id: u32, node_id: u8) -> u8;
+    fn is_node_info_received(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_awake(&self, home_id: u32, node_id: u8) -> bool;
+    fn is_node_failed(&self, home_id: u32, node_id: u8) -> bool;
+    fn get_node_type(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_manufacturer_name(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_product_name(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_name(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_location(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_manufacturer_id(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_product_type(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_product_id(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_query_stage(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_device_type_string(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_role_string(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_plus_type_string(&self, home_id: u32, node_id: u8) -> String;
+    fn get_node_neighbors(&self, home_id: u32, node_id: u8) -> Option<Vec<u8>>;
+    fn get_node_class_information(
+        &self,
+        home_id: u32,
+        node_id: u8,
+        command_class_id: u8,
+    ) -> Option<(String, u8)>;
+    fn get_node_statistics(&self, home_id: u32, node_id: u8) -> NodeStatistics;
+
+    // Associations, groups are numbered from 1
+    fn get_node_num_groups(&self, home_id: u32, node_id: u8) -> u8;
+    fn get_node_group_label(&self, home_id: u32, node_id: u8, group_idx: u8) -> String;
+    fn get_node_max_associations(&self, home_id: u32, node_id: u8, group_idx: u8) -> u8;
+    fn is_node_multi_instance_group(&self, home_id: u32, node_id: u8, group_idx: u8) -> bool;
+    fn get_node_associations(&self, home_id: u32, node_id: u8, group_idx: u8) -> Vec<InstanceAssociation>;
+    fn add_node_association(&self, home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation);
+    fn remove_node_association(&self, home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation);
+
+    // Values
+    fn get_value_label(&self, vid: &ExternValueID) -> String;
+    fn set_value_label(&self, vid: &ExternValueID, label: &str);
+    fn get_value_units(&self, vid: &ExternValueID) -> String;
+    fn set_value_units(&self, vid: &ExternValueID, units: &str);
+    fn get_value_help(&self, vid: &ExternValueID) -> String;
+    fn set_value_help(&self, vid: &ExternValueID, help: &str);
+    fn get_value_min(&self, vid: &ExternValueID) -> i32;
+    fn get_value_max(&self, vid: &ExternValueID) -> i32;
+    fn is_value_read_only(&self, vid: &ExternValueID) -> bool;
+    fn is_value_write_only(&self, vid: &ExternValueID) -> bool;
+    fn is_value_set(&self, vid: &ExternValueID) -> bool;
+    fn is_value_polled(&self, vid: &ExternValueID) -> bool;
+
+    fn get_value_as_bool(&self, vid: &ExternValueID) -> Option<bool>;
+    fn get_value_as_byte(&self, vid: &ExternValueID) -> Option<u8>;
+    fn get_value_as_float(&self, vid: &ExternValueID) -> Option<f32>;
+    fn get_value_float_precision(&self, vid: &ExternValueID) -> Option<u8>;
+    fn get_value_as_int(&self, vid: &ExternValueID) -> Option<i32>;
+    fn get_value_as_short(&self, vid: &ExternValueID) -> Option<i16>;
+    fn get_value_as_string(&self, vid: &ExternValueID) -> Option<String>;
+    fn get_value_as_raw(&self, vid: &ExternValueID) -> Option<Vec<u8>>;
+    fn get_value_list_selection_as_string(&self, vid: &ExternValueID) -> Option<String>;
+    fn get_value_list_selection_as_int(&self, vid: &ExternValueID) -> Option<i32>;
+    fn get_value_list_items(&self, vid: &ExternValueID) -> Option<Vec<String>>;
+    fn get_value_list_values(&self, vid: &ExternValueID) -> Option<Vec<i32>>;
+
+    fn set_value_bool(&self, vid: &ExternValueID, value: bool) -> bool;
+    fn set_value_byte(&self, vid: &ExternValueID, value: u8) -> bool;
+    fn set_value_float(&self, vid: &ExternValueID, value: f32) -> bool;
+    fn set_value_int(&self, vid: &ExternValueID, value: i32) -> bool;
+    fn set_value_short(&self, vid: &ExternValueID, value: i16) -> bool;
+    fn set_value_string(&self, vid: &ExternValueID, value: &str) -> bool;
+    fn set_value_raw(&self, vid: &ExternValueID, value: &[u8]) -> bool;
+    fn set_value_list_selection_string(&self, vid: &ExternValueID, value: &str) -> bool;
+    fn set_value_list_selection_int(&self, vid: &ExternValueID, value: i32) -> bool;
+
+    // Schedules
+    fn get_num_switch_points(&self, vid: &ExternValueID) -> u8;
+    fn get_switch_point(&self, vid: &ExternValueID, idx: u8) -> Option<SwitchPoint>;
+    fn set_switch_point(&self, vid: &ExternValueID, point: SwitchPoint) -> bool;
+    fn remove_switch_point(&self, vid: &ExternValueID, hours: u8, minutes: u8) -> bool;
+    fn clear_switch_points(&self, vid: &ExternValueID);
+
+    // BitSets, positions start at 1
+    fn get_value_bitset_size(&self, vid: &ExternValueID) -> Option<u8>;
+    fn get_value_bitmask(&self, vid: &ExternValueID) -> Option<u32>;
+    fn get_value_as_bitset(&self, vid: &ExternValueID, pos: u8) -> Option<bool>;
+    fn get_value_bit_label(&self, vid: &ExternValueID, pos: u8) -> String;
+    fn set_value_bitset(&self, vid: &ExternValueID, pos: u8, value: bool) -> bool;
+
+    // Polling
+    fn enable_poll(&self, vid: &ExternValueID) -> bool;
+    fn enable_poll_with_intensity(&self, vid: &ExternValueID, intensity: u8) -> bool;
+    fn disable_poll(&self, vid: &ExternValueID) -> bool;
+    fn is_polled(&self, vid: &ExternValueID) -> bool;
+    fn set_poll_intensity(&self, vid: &ExternValueID, intensity: u8);
+    fn get_poll_intensity(&self, vid: &ExternValueID) -> u8;
+}
+
+// Backends registered for a specific home id. Everything else goes to OpenZWave.
+static BACKENDS: RwLock<BTreeMap<u32, Arc<dyn ZWaveBackend>>> = RwLock::new(BTreeMap::new());
+
+/// Routes every call for `home_id` to `backend` instead of OpenZWave.
+pub fn register(home_id: u32, backend: Arc<dyn ZWaveBackend>) {
+    BACKENDS.write().unwrap().insert(home_id, backend);
+}
+
+pub fn unregister(home_id: u32) -> Option<Arc<dyn ZWaveBackend>> {
+    BACKENDS.write().unwrap().remove(&home_id)
+}
+
+/// Returns the backend serving `home_id`.
+pub fn get(home_id: u32) -> Arc<dyn ZWaveBackend> {
+    BACKENDS
+        .read()
+        .unwrap()
+        .get(&home_id)
+        .cloned()
+        .unwrap_or_else(openzwave::shared)
+}
@@ -0,0 +1,623 @@
+use super::ZWaveBackend;
+use crate::manager::NotificationWatcher;
+use crate::notification::{ExternNotification, Notification};
+use crate::panic_safety::panic_message;
+use crate::statistics::{DriverStatistics, NodeStatistics};
+use crate::value_classes::value_id::{ExternValueID, SwitchPoint};
+use ffi::manager as extern_manager;
+use ffi::manager::{ControllerInterface, InstanceAssociation};
+use ffi::utils::{
+    recover_string, recover_vec, rust_string_creator, rust_string_vec_creator, rust_vec_creator,
+};
+use libc::{c_char, c_void};
+use std::ffi::CString;
+use std::mem;
+use std::panic;
+use std::ptr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The real thing: forwards every call to the C++ `OpenZWave::Manager` singleton.
+pub struct OpenZWaveBackend {
+    // The boxes give each watcher a stable address we can hand to the C++ side as context.
+    #[allow(clippy::vec_box)]
+    watchers: Mutex<Vec<Box<Arc<dyn NotificationWatcher>>>>,
+}
+
+static SHARED: OnceLock<Arc<OpenZWaveBackend>> = OnceLock::new();
+
+pub(crate) fn shared() -> Arc<dyn ZWaveBackend> {
+    SHARED
+        .get_or_init(|| {
+            Arc::new(OpenZWaveBackend {
+                watchers: Mutex::new(Vec::new()),
+            })
+        })
+        .clone()
+}
+
+// watcher is actually a Box<Arc<dyn NotificationWatcher>> owned by OpenZWaveBackend::watchers
+extern "C" fn watcher_cb(notification: *const ExternNotification, watcher: *const c_void) {
+    // Unwinding into OpenZWave's C++ thread is undefined behaviour: stop any panic here.
+    // Watchers added through a Manager already catch their own, this is the last resort.
+    let result = panic::catch_unwind(|| {
+        let watcher: &Arc<dyn NotificationWatcher> =
+            unsafe { &*(watcher as *const Arc<dyn NotificationWatcher>) };
+        // OpenZWave hands the notification to its watchers for the time of this call
+        let rust_notification = unsafe { Notification::new(notification) };
+        watcher.on_notification(&rust_notification);
+    });
+
+    if let Err(payload) = result {
+        log::error!("Notification watcher panicked: {}", panic_message(&*payload));
+    }
+}
+
+fn manager_ptr() -> *mut extern_manager::Manager {
+    unsafe { extern_manager::get() }
+}
+
+fn with_c_string<F: FnOnce(*const c_char) -> R, R>(value: &str, f: F) -> Option<R> {
+    CString::new(value).ok().map(|c_string| f(c_string.as_ptr()))
+}
+
+macro_rules! controller_impl {
+    ( $( $name: ident -> $t: ty ),+ ) => {
+        $(fn $name(&self, home_id: u32) -> $t {
+            unsafe { extern_manager::$name(manager_ptr(), home_id) }
+        })*
+    };
+}
+
+macro_rules! controller_string_impl {
+    ( $( $name: ident ),+ ) => {
+        $(fn $name(&self, home_id: u32) -> String {
+            recover_string(unsafe {
+                extern_manager::$name(manager_ptr(), home_id, rust_string_creator)
+            })
+        })*
+    };
+}
+
+macro_rules! node_impl {
+    ( $( $name: ident -> $t: ty ),+ ) => {
+        $(fn $name(&self, home_id: u32, node_id: u8) -> $t {
+            unsafe { extern_manager::$name(manager_ptr(), home_id, node_id) }
+        })*
+    };
+}
+
+macro_rules! node_string_impl {
+    ( $( $name: ident ),+ ) => {
+        $(fn $name(&self, home_id: u32, node_id: u8) -> String {
+            recover_string(unsafe {
+                extern_manager::$name(manager_ptr(), home_id, node_id, rust_string_creator)
+            })
+        })*
+    };
+}
+
+macro_rules! value_impl {
+    ( $( $name: ident -> $t: ty ),+ ) => {
+        $(fn $name(&self, vid: &ExternValueID) -> $t {
+            unsafe { extern_manager::$name(manager_ptr(), vid) }
+        })*
+    };
+}
+
+macro_rules! value_string_impl {
+    ( $( $getter: ident / $setter: ident ),+ ) => {
+        $(
+            fn $getter(&self, vid: &ExternValueID) -> String {
+                recover_string(unsafe {
+                    extern_manager::$getter(manager_ptr(), vid, rust_string_creator)
+                })
+            }
+
+            fn $setter(&self, vid: &ExternValueID, value: &str) {
+                with_c_string(value, |c_string| unsafe {
+                    extern_manager::$setter(manager_ptr(), vid, c_string)
+                });
+            }
+        )*
+    };
+}
+
+macro_rules! value_getter_impl {
+    ( $( $name: ident -> $t: ty = $default: expr ),+ ) => {
+        $(fn $name(&self, vid: &ExternValueID) -> Option<$t> {
+            let mut val: $t = $default;
+            if unsafe { extern_manager::$name(manager_ptr(), vid, &mut val) } {
+                Some(val)
+            } else {
+                None
+            }
+        })*
+    };
+}
+
+macro_rules! value_setter_impl {
+    ( $( $name: ident ($t: ty) ),+ ) => {
+        $(fn $name(&self, vid: &ExternValueID, value: $t) -> bool {
+            unsafe { extern_manager::$name(manager_ptr(), vid, value) }
+        })*
+    };
+}
+
+impl ZWaveBackend for OpenZWaveBackend {
+    fn add_watcher(&self, watcher: Arc<dyn NotificationWatcher>) -> bool {
+        if manager_ptr().is_null() {
+            return false;
+        }
+        let watcher = Box::new(watcher);
+        let watcher_ptr = &*watcher as *const _ as *const c_void;
+        let api_res =
+            unsafe { extern_manager::manager_add_watcher(manager_ptr(), watcher_cb, watcher_ptr) };
+        if api_res {
+            self.watchers.lock().unwrap().push(watcher);
+        }
+        api_res
+    }
+
+    fn remove_watcher(&self, watcher: &Arc<dyn NotificationWatcher>) -> bool {
+        // Taken out before calling OpenZWave, which holds its notification lock while removing:
+        // keeping ours meanwhile would deadlock with a callback registering a watcher.
+        let removed = {
+            let mut watchers = self.watchers.lock().unwrap();
+            match watchers.iter().position(|w| Arc::ptr_eq(w, watcher)) {
+                Some(position) => watchers.remove(position),
+                None => return false,
+            }
+        };
+
+        // The C++ manager is already gone, and its watchers with it.
+        if manager_ptr().is_null() {
+            return true;
+        }
+
+        // The box keeps the address OpenZWave knows the watcher by.
+        let watcher_ptr = &*removed as *const _ as *const c_void;
+        let api_res = unsafe {
+            extern_manager::manager_remove_watcher(manager_ptr(), watcher_cb, watcher_ptr)
+        };
+        if !api_res {
+            self.watchers.lock().unwrap().push(removed);
+        }
+        api_res
+    }
+
+    fn add_driver(&self, device: &str, interface: ControllerInterface) -> bool {
+        with_c_string(device, |device| unsafe {
+            extern_manager::manager_add_driver(manager_ptr(), device, &interface)
+        })
+        .unwrap_or(false)
+    }
+
+    fn remove_driver(&self, device: &str) -> bool {
+        with_c_string(device, |device| unsafe {
+            extern_manager::manager_remove_driver(manager_ptr(), device)
+        })
+        .unwrap_or(false)
+    }
+
+    fn get_poll_interval(&self) -> i32 {
+        unsafe { extern_manager::get_poll_interval(manager_ptr()) }
+    }
+
+    fn set_poll_interval(&self, interval_ms: i32, is_between_each_poll: bool) {
+        unsafe { extern_manager::set_poll_interval(manager_ptr(), interval_ms, is_between_each_poll) }
+    }
+
+    controller_impl! {
+        get_controller_node_id -> u8,
+        get_suc_node_id -> u8,
+        is_primary_controller -> bool,
+        is_bridge_controller -> bool,
+        get_send_queue_count -> i32,
+        log_driver_statistics -> (),
+        get_controller_interface_type -> ControllerInterface,
+        reset_controller -> (),
+        soft_reset_controller -> (),
+        cancel_controller_command -> (),
+        remove_node -> bool,
+        create_new_primary -> bool,
+        receive_configuration -> bool,
+        transfer_primary_role -> bool
+    }
+
+    controller_string_impl! {
+        get_library_version,
+        get_library_type_name,
+        get_controller_path
+    }
+
+    fn add_node(&self, home_id: u32, secure: bool) -> bool {
+        unsafe { extern_manager::add_node(manager_ptr(), home_id, secure) }
+    }
+
+    fn test_network(&self, home_id: u32, count: u32) {
+        unsafe { extern_manager::test_network(manager_ptr(), home_id, count) }
+    }
+
+    fn test_network_node(&self, home_id: u32, node_id: u8, count: u32) {
+        unsafe { extern_manager::test_network_node(manager_ptr(), home_id, node_id, count) }
+    }
+
+    fn heal_network(&self, home_id: u32, do_rr: bool) {
+        unsafe { extern_manager::heal_network(manager_ptr(), home_id, do_rr) }
+    }
+
+    fn heal_network_node(&self, home_id: u32, node_id: u8, do_rr: bool) {
+        unsafe { extern_manager::heal_network_node(manager_ptr(), home_id, node_id, do_rr) }
+    }
+
+    fn create_button(&self, home_id: u32, node_id: u8, button_id: u8) -> bool {
+        unsafe { extern_manager::create_button(manager_ptr(), home_id, node_id, button_id) }
+    }
+
+    fn delete_button(&self, home_id: u32, node_id: u8, button_id: u8) -> bool {
+        unsafe { extern_manager::delete_button(manager_ptr(), home_id, node_id, button_id) }
+    }
+
+    fn set_config_param(&self, home_id: u32, node_id: u8, param: u8, value: i32, size: u8) -> bool {
+        unsafe { extern_manager::set_config_param(manager_ptr(), home_id, node_id, param, value, size) }
+    }
+
+    fn request_config_param(&self, home_id: u32, node_id: u8, param: u8) {
+        unsafe { extern_manager::request_config_param(manager_ptr(), home_id, node_id, param) }
+    }
+
+    node_impl! {
+        remove_failed_node -> bool,
+        has_node_failed -> bool,
+        replace_failed_node -> bool,
+        request_network_update -> bool,
+        request_node_neighbor_update -> bool,
+        assign_return_route -> bool,
+        delete_all_return_routes -> bool,
+        send_node_information -> bool,
+        replication_send -> bool,
+        request_node_state -> bool,
+        request_all_config_params -> (),
+        is_node_listening_device -> bool,
+        is_node_frequent_listening_device -> bool,
+        is_node_beaming_device -> bool,
+        is_node_routing_device -> bool,
+        is_node_security_device -> bool,
+        get_node_max_baud_rate -> u32,
+        get_node_version -> u8,
+        get_node_security -> u8,
+        is_node_zwave_plus -> bool,
+        get_node_basic -> u8,
+        get_node_generic -> u8,
+        get_node_specific -> u8,
+        get_node_device_type -> u16,
+        get_node_role -> u8,
+        get_node_plus_type -> u8,
+        is_node_info_received -> bool,
+        is_node_awake -> bool,
+        is_node_failed -> bool
+    }
+
+    node_string_impl! {
+        get_node_type,
+        get_node_manufacturer_name,
+        get_node_product_name,
+        get_node_name,
+        get_node_location,
+        get_node_manufacturer_id,
+        get_node_product_type,
+        get_node_product_id,
+        get_node_query_stage,
+        get_node_device_type_string,
+        get_node_role_string,
+        get_node_plus_type_string
+    }
+
+    fn get_driver_statistics(&self, home_id: u32) -> DriverStatistics {
+        let mut stats = extern_manager::DriverStatistics::default();
+        unsafe { extern_manager::get_driver_statistics(manager_ptr(), home_id, &mut stats) };
+        stats.into()
+    }
+
+    fn get_node_statistics(&self, home_id: u32, node_id: u8) -> NodeStatistics {
+        // plain integers and null pointers, both timestamps are then set by the C side
+        let mut stats: extern_manager::NodeStatistics = unsafe { mem::zeroed() };
+        unsafe {
+            extern_manager::get_node_statistics(manager_ptr(), home_id, node_id, &mut stats, rust_string_creator)
+        };
+        stats.into()
+    }
+
+    node_impl! {
+        get_node_num_groups -> u8
+    }
+
+    fn get_node_group_label(&self, home_id: u32, node_id: u8, group_idx: u8) -> String {
+        recover_string(unsafe {
+            extern_manager::get_node_group_label(manager_ptr(), home_id, node_id, group_idx, rust_string_creator)
+        })
+    }
+
+    fn get_node_max_associations(&self, home_id: u32, node_id: u8, group_idx: u8) -> u8 {
+        unsafe { extern_manager::get_node_max_associations(manager_ptr(), home_id, node_id, group_idx) }
+    }
+
+    fn is_node_multi_instance_group(&self, home_id: u32, node_id: u8, group_idx: u8) -> bool {
+        unsafe { extern_manager::is_node_multi_instance_group(manager_ptr(), home_id, node_id, group_idx) }
+    }
+
+    fn get_node_associations(&self, home_id: u32, node_id: u8, group_idx: u8) -> Vec<InstanceAssociation> {
+        let result_ptr = unsafe {
+            extern_manager::get_node_associations(
+                manager_ptr(), home_id, node_id, group_idx,
+                rust_vec_creator::<InstanceAssociation>
+            )
+        } as *mut Vec<InstanceAssociation>;
+
+        if result_ptr.is_null() {
+            Vec::new()
+        } else {
+            *recover_vec(result_ptr)
+        }
+    }
+
+    fn add_node_association(&self, home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation) {
+        unsafe {
+            extern_manager::add_node_association(
+                manager_ptr(), home_id, node_id, group_idx, target.node_id, target.instance
+            )
+        }
+    }
+
+    fn remove_node_association(&self, home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation) {
+        unsafe {
+            extern_manager::remove_node_association(
+                manager_ptr(), home_id, node_id, group_idx, target.node_id, target.instance
+            )
+        }
+    }
+
+    fn get_node_neighbors(&self, home_id: u32, node_id: u8) -> Option<Vec<u8>> {
+        let result_ptr = unsafe {
+            extern_manager::get_node_neighbors(manager_ptr(), home_id, node_id, rust_vec_creator::<u8>)
+        } as *mut Vec<u8>;
+
+        if result_ptr.is_null() {
+            None
+        } else {
+            Some(*recover_vec(result_ptr))
+        }
+    }
+
+    fn get_node_class_information(
+        &self,
+        home_id: u32,
+        node_id: u8,
+        command_class_id: u8,
+    ) -> Option<(String, u8)> {
+        let mut class_name: *mut c_char = ptr::null_mut();
+        let mut class_version: u8 = 0;
+
+        let has_class = unsafe {
+            extern_manager::get_node_class_information(
+                manager_ptr(), home_id, node_id,
+                command_class_id, &mut class_name, &mut class_version,
+                rust_string_creator
+            )
+        };
+
+        if has_class {
+            Some((recover_string(class_name), class_version))
+        } else {
+            None
+        }
+    }
+
+    value_string_impl! {
+        get_value_label / set_value_label,
+        get_value_units / set_value_units,
+        get_value_help / set_value_help
+    }
+
+    value_impl! {
+        get_value_min -> i32,
+        get_value_max -> i32,
+        is_value_read_only -> bool,
+        is_value_write_only -> bool,
+        is_value_set -> bool,
+        is_value_polled -> bool,
+        enable_poll -> bool,
+        disable_poll -> bool,
+        is_polled -> bool,
+        get_poll_intensity -> u8
+    }
+
+    value_getter_impl! {
+        get_value_as_bool -> bool = false,
+        get_value_as_byte -> u8 = 0,
+        get_value_as_float -> f32 = 0.0,
+        get_value_float_precision -> u8 = 0,
+        get_value_as_int -> i32 = 0,
+        get_value_as_short -> i16 = 0,
+        get_value_list_selection_as_int -> i32 = 0,
+        get_value_bitset_size -> u8 = 0
+    }
+
+    fn get_value_as_string(&self, vid: &ExternValueID) -> Option<String> {
+        let mut raw_string: *mut c_char = ptr::null_mut();
+        let res = unsafe {
+            extern_manager::get_value_as_string(manager_ptr(), vid, &mut raw_string, rust_string_creator)
+        };
+        if res {
+            Some(recover_string(raw_string))
+        } else {
+            None
+        }
+    }
+
+    fn get_value_as_raw(&self, vid: &ExternValueID) -> Option<Vec<u8>> {
+        let mut raw_ptr: *mut Vec<u8> = ptr::null_mut();
+        let raw_ptr_c_void = &mut raw_ptr as *mut *mut _ as *mut *mut c_void;
+        let res = unsafe {
+            extern_manager::get_value_as_raw(manager_ptr(), vid, raw_ptr_c_void, rust_vec_creator::<u8>)
+        };
+        if res {
+            Some(*recover_vec(raw_ptr))
+        } else {
+            None
+        }
+    }
+
+    fn get_value_list_selection_as_string(&self, vid: &ExternValueID) -> Option<String> {
+        let mut raw_string: *mut c_char = ptr::null_mut();
+        let res = unsafe {
+            extern_manager::get_value_list_selection_as_string(
+                manager_ptr(),
+                vid,
+                &mut raw_string,
+                rust_string_creator,
+            )
+        };
+        if res {
+            Some(recover_string(raw_string))
+        } else {
+            None
+        }
+    }
+
+    fn get_value_list_items(&self, vid: &ExternValueID) -> Option<Vec<String>> {
+        let mut c_items: *mut Vec<String> = ptr::null_mut();
+        let c_items_void_ptr = &mut c_items as *mut *mut _ as *mut *mut c_void;
+        let res = unsafe {
+            extern_manager::get_value_list_items(
+                manager_ptr(),
+                vid,
+                c_items_void_ptr,
+                rust_string_vec_creator,
+            )
+        };
+        if res {
+            Some(*recover_vec(c_items))
+        } else {
+            None
+        }
+    }
+
+    fn get_value_list_values(&self, vid: &ExternValueID) -> Option<Vec<i32>> {
+        let mut c_values: *mut Vec<i32> = ptr::null_mut();
+        let c_values_void_ptr = &mut c_values as *mut *mut _ as *mut *mut c_void;
+        let res = unsafe {
+            extern_manager::get_value_list_values(
+                manager_ptr(),
+                vid,
+                c_values_void_ptr,
+                rust_vec_creator::<i32>,
+            )
+        };
+        if res {
+            Some(*recover_vec(c_values))
+        } else {
+            None
+        }
+    }
+
+    value_setter_impl! {
+        set_value_bool(bool),
+        set_value_byte(u8),
+        set_value_float(f32),
+        set_value_int(i32),
+        set_value_short(i16)
+    }
+
+    fn set_value_string(&self, vid: &ExternValueID, value: &str) -> bool {
+        with_c_string(value, |value| unsafe {
+            extern_manager::set_value_string(manager_ptr(), vid, value)
+        })
+        .unwrap_or(false)
+    }
+
+    fn set_value_raw(&self, vid: &ExternValueID, value: &[u8]) -> bool {
+        if value.len() > u8::MAX as usize {
+            return false;
+        }
+        unsafe { extern_manager::set_value_raw(manager_ptr(), vid, value.as_ptr(), value.len() as u8) }
+    }
+
+    fn set_value_list_selection_string(&self, vid: &ExternValueID, value: &str) -> bool {
+        with_c_string(value, |value| unsafe {
+            extern_manager::set_value_list_selection_string(manager_ptr(), vid, value)
+        })
+        .unwrap_or(false)
+    }
+
+    fn set_value_list_selection_int(&self, vid: &ExternValueID, value: i32) -> bool {
+        unsafe { extern_manager::set_value_list_selection_int(manager_ptr(), vid, value) }
+    }
+
+    fn get_num_switch_points(&self, vid: &ExternValueID) -> u8 {
+        unsafe { extern_manager::get_num_switch_points(manager_ptr(), vid) }
+    }
+
+    fn get_switch_point(&self, vid: &ExternValueID, idx: u8) -> Option<SwitchPoint> {
+        let mut point = SwitchPoint::new(0, 0, 0);
+        let res = unsafe {
+            extern_manager::get_switch_point(
+                manager_ptr(), vid, idx,
+                &mut point.hours, &mut point.minutes, &mut point.setback
+            )
+        };
+        if res {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    fn set_switch_point(&self, vid: &ExternValueID, point: SwitchPoint) -> bool {
+        unsafe { extern_manager::set_switch_point(manager_ptr(), vid, point.hours, point.minutes, point.setback) }
+    }
+
+    fn remove_switch_point(&self, vid: &ExternValueID, hours: u8, minutes: u8) -> bool {
+        unsafe { extern_manager::remove_switch_point(manager_ptr(), vid, hours, minutes) }
+    }
+
+    fn clear_switch_points(&self, vid: &ExternValueID) {
+        unsafe { extern_manager::clear_switch_points(manager_ptr(), vid) }
+    }
+
+    fn get_value_bitmask(&self, vid: &ExternValueID) -> Option<u32> {
+        let mut mask: i32 = 0;
+        if unsafe { extern_manager::get_value_bitmask(manager_ptr(), vid, &mut mask) } {
+            Some(mask as u32)
+        } else {
+            None
+        }
+    }
+
+    fn get_value_as_bitset(&self, vid: &ExternValueID, pos: u8) -> Option<bool> {
+        let mut value = false;
+        if unsafe { extern_manager::get_value_as_bitset(manager_ptr(), vid, pos, &mut value) } {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn get_value_bit_label(&self, vid: &ExternValueID, pos: u8) -> String {
+        recover_string(unsafe {
+            extern_manager::get_value_bit_label(manager_ptr(), vid, pos, rust_string_creator)
+        })
+    }
+
+    fn set_value_bitset(&self, vid: &ExternValueID, pos: u8, value: bool) -> bool {
+        unsafe { extern_manager::set_value_bitset(manager_ptr(), vid, pos, value) }
+    }
+
+    fn enable_poll_with_intensity(&self, vid: &ExternValueID, intensity: u8) -> bool {
+        unsafe { extern_manager::enable_poll_with_intensity(manager_ptr(), vid, intensity) }
+    }
+
+    fn set_poll_intensity(&self, vid: &ExternValueID, intensity: u8) {
+        unsafe { extern_manager::set_poll_intensity(manager_ptr(), vid, intensity) }
+    }
+}
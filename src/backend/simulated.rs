@@ -0,0 +1,1393 @@
+use super::ZWaveBackend;
+use crate::manager::NotificationWatcher;
+use crate::notification::{
+    descriptions_enabled, ControllerCommandType, ControllerError, ControllerState, Event,
+    Notification, NotificationType, NotificationValue, UserAlert,
+};
+use crate::statistics::{DriverStatistics, NodeStatistics};
+use crate::value_classes::validation::parse_bool;
+use crate::value_classes::value_id::{
+    BitSetValue, DecimalValue, ExternValueID, ListValue, Schedule, SwitchPoint, ValueContent,
+    ValueGenre, ValueID, ValueIdKey, ValueType,
+};
+use ffi::manager::{ControllerInterface, InstanceAssociation};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+// Like the devices, OpenZWave keeps at most 9 switch points a day
+const MAX_SWITCH_POINTS: usize = 9;
+
+/// Static description of a simulated node. Everything OpenZWave would report for it.
+#[derive(Debug, Clone)]
+pub struct SimulatedNode {
+    pub listening: bool,
+    pub frequent_listening: bool,
+    pub beaming: bool,
+    pub routing: bool,
+    pub security_device: bool,
+    pub max_baud_rate: u32,
+    pub version: u8,
+    pub security: u8,
+    pub zwave_plus: bool,
+    pub basic: u8,
+    pub generic: u8,
+    pub specific: u8,
+    pub device_type: u16,
+    pub role: u8,
+    pub plus_type: u8,
+    pub info_received: bool,
+    pub awake: bool,
+    pub failed: bool,
+    pub type_name: String,
+    pub manufacturer_name: String,
+    pub product_name: String,
+    pub name: String,
+    pub location: String,
+    pub manufacturer_id: String,
+    pub product_type: String,
+    pub product_id: String,
+    pub query_stage: String,
+    pub device_type_string: String,
+    pub role_string: String,
+    pub plus_type_string: String,
+    pub neighbors: Vec<u8>,
+    /// command class id -> (name, version)
+    pub command_classes: BTreeMap<u8, (String, u8)>,
+    pub statistics: NodeStatistics,
+    /// association groups by index, from 1
+    pub groups: BTreeMap<u8, SimulatedGroup>,
+}
+
+impl Default for SimulatedNode {
+    fn default() -> Self {
+        SimulatedNode {
+            listening: true,
+            frequent_listening: false,
+            beaming: true,
+            routing: true,
+            security_device: false,
+            max_baud_rate: 40000,
+            version: 4,
+            security: 0,
+            zwave_plus: true,
+            basic: 4, // Routing Slave
+            generic: 0,
+            specific: 0,
+            device_type: 0,
+            role: 0,
+            plus_type: 0,
+            info_received: true,
+            awake: true,
+            failed: false,
+            type_name: String::new(),
+            manufacturer_name: String::new(),
+            product_name: String::new(),
+            name: String::new(),
+            location: String::new(),
+            manufacturer_id: String::new(),
+            product_type: String::new(),
+            product_id: String::new(),
+            query_stage: String::from("Complete"),
+            device_type_string: String::new(),
+            role_string: String::new(),
+            plus_type_string: String::new(),
+            neighbors: Vec::new(),
+            command_classes: BTreeMap::new(),
+            statistics: NodeStatistics::default(),
+            groups: BTreeMap::new(),
+        }
+    }
+}
+
+/// An association group of a simulated node.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedGroup {
+    pub label: String,
+    pub max_associations: u8,
+    pub multi_instance: bool,
+    pub associations: Vec<InstanceAssociation>,
+}
+
+impl SimulatedGroup {
+    pub fn new(label: &str, max_associations: u8) -> Self {
+        SimulatedGroup {
+            label: label.to_string(),
+            max_associations,
+            ..SimulatedGroup::default()
+        }
+    }
+}
+
+/// A value living on a simulated node.
+#[derive(Debug, Clone)]
+pub struct SimulatedValue {
+    pub genre: ValueGenre,
+    pub command_class: u8,
+    pub instance: u8,
+    pub index: u16,
+    pub value_type: ValueType,
+    pub label: String,
+    pub units: String,
+    pub help: String,
+    pub min: i32,
+    pub max: i32,
+    pub read_only: bool,
+    pub write_only: bool,
+    /// For `ValueType::List` it also holds the items of the list
+    pub content: ValueContent,
+}
+
+impl SimulatedValue {
+    pub fn new(command_class: u8, index: u16, label: &str, content: ValueContent) -> Self {
+        SimulatedValue {
+            genre: ValueGenre::User,
+            command_class,
+            instance: 1,
+            index,
+            value_type: content.value_type(),
+            label: label.to_string(),
+            units: String::new(),
+            help: String::new(),
+            min: 0,
+            max: 0,
+            read_only: false,
+            write_only: false,
+            content,
+        }
+    }
+
+    fn packed_id(&self, node_id: u8) -> u64 {
+        // the home id isn't part of the packed id
+        ValueIdKey::new(0, node_id, self.command_class)
+            .genre(self.genre)
+            .instance(self.instance)
+            .index(self.index)
+            .value_type(self.value_type)
+            .packed_id()
+            .unwrap()
+    }
+
+    fn is_config_param(&self, param: u8) -> bool {
+        self.genre == ValueGenre::Config && self.command_class == 0x70 && self.index == param as u16
+    }
+
+    // What the device would report after its configuration parameter was set to `value`
+    fn config_content(&self, value: i32) -> Option<ValueContent> {
+        match self.value_type {
+            ValueType::Bool => Some(ValueContent::Bool(value != 0)),
+            ValueType::Byte => Some(ValueContent::Byte(value as u8)),
+            ValueType::Short => Some(ValueContent::Short(value as i16)),
+            ValueType::Int => Some(ValueContent::Int(value)),
+            ValueType::List => self.select_list_value(value),
+            _ => None,
+        }
+    }
+
+    fn list(&self) -> Option<&ListValue> {
+        match &self.content {
+            ValueContent::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    fn schedule(&self) -> Option<&Schedule> {
+        match &self.content {
+            ValueContent::Schedule(schedule) => Some(schedule),
+            _ => None,
+        }
+    }
+
+    // The schedule with `f` applied to its switch points, None when refused
+    fn change_schedule<F: FnOnce(&mut Vec<SwitchPoint>) -> bool>(&self, f: F) -> Option<ValueContent> {
+        let mut switch_points = self.schedule()?.switch_points.clone();
+        if f(&mut switch_points) {
+            Some(ValueContent::Schedule(Schedule::new(switch_points)))
+        } else {
+            None
+        }
+    }
+
+    fn bitset(&self) -> Option<&BitSetValue> {
+        match &self.content {
+            ValueContent::BitSet(bitset) => Some(bitset),
+            _ => None,
+        }
+    }
+
+    fn select_list_value(&self, value: i32) -> Option<ValueContent> {
+        self.list()
+            .and_then(|list| list.with_selection(value))
+            .map(ValueContent::List)
+    }
+
+    fn as_string(&self) -> String {
+        match &self.content {
+            ValueContent::String(val) => val.clone(),
+            ValueContent::List(list) => list.selected.clone(),
+            ValueContent::Bool(val) | ValueContent::Button(val) => {
+                String::from(if *val { "True" } else { "False" })
+            }
+            other => other.to_string(),
+        }
+    }
+
+    // OpenZWave accepts a string for any value type and converts it itself.
+    fn parse(&self, value: &str) -> Option<ValueContent> {
+        match self.value_type {
+            ValueType::Bool => parse_bool(value).map(ValueContent::Bool),
+            ValueType::Button => parse_bool(value).map(ValueContent::Button),
+            ValueType::Byte => value.parse().ok().map(ValueContent::Byte),
+            ValueType::Int => value.parse().ok().map(ValueContent::Int),
+            ValueType::Short => value.parse().ok().map(ValueContent::Short),
+            ValueType::Decimal => value.parse().ok().map(ValueContent::Decimal),
+            ValueType::String => Some(ValueContent::String(value.to_string())),
+            ValueType::List => self
+                .list()
+                .and_then(|list| list.item_by_label(value))
+                .and_then(|item| self.select_list_value(item.value)),
+            _ => None,
+        }
+    }
+}
+
+fn packed_id(vid: &ExternValueID) -> u64 {
+    (vid.id1 as u64) << 32 | vid.id as u64
+}
+
+// Like OpenZWave, only when the descriptions are turned on
+fn describe<F: FnOnce() -> String>(description: F) -> String {
+    if descriptions_enabled() {
+        description()
+    } else {
+        String::new()
+    }
+}
+
+// The controller command waiting for a device
+#[derive(PartialEq)]
+enum Waiting {
+    Inclusion,
+    Exclusion,
+    Other,
+}
+
+struct NetworkModel {
+    controller_node_id: u8,
+    suc_node_id: u8,
+    primary: bool,
+    bridge: bool,
+    library_version: String,
+    library_type_name: String,
+    controller_path: String,
+    poll_interval: i32,
+    auto_confirm: bool,
+    nodes: BTreeMap<u8, SimulatedNode>,
+    values: BTreeMap<u64, SimulatedValue>,
+    polled: BTreeMap<u64, u8>,
+    drivers: BTreeSet<String>,
+    waiting: Option<Waiting>,
+    command: ControllerCommandType,
+    driver_statistics: DriverStatistics,
+}
+
+/// An in-memory Z-Wave network, scriptable from tests.
+///
+/// Creating a network registers it as the backend for its home id, so `Node`, `Controller`
+/// and `ValueID` handles for that home id operate on the simulation. Notifications are
+/// delivered synchronously, on the thread that caused them.
+pub struct SimulatedNetwork {
+    home_id: u32,
+    model: Mutex<NetworkModel>,
+    watchers: Mutex<Vec<Arc<dyn NotificationWatcher>>>,
+}
+
+impl SimulatedNetwork {
+    pub fn new(home_id: u32) -> Arc<SimulatedNetwork> {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(
+            1,
+            SimulatedNode {
+                basic: 2, // Static Controller
+                product_name: String::from("Simulated Controller"),
+                ..SimulatedNode::default()
+            },
+        );
+
+        let network = Arc::new(SimulatedNetwork {
+            home_id,
+            model: Mutex::new(NetworkModel {
+                controller_node_id: 1,
+                suc_node_id: 1,
+                primary: true,
+                bridge: false,
+                library_version: String::from("Z-Wave 4.05"),
+                library_type_name: String::from("Static Controller"),
+                controller_path: String::from("simulated"),
+                poll_interval: 30_000,
+                auto_confirm: true,
+                nodes,
+                values: BTreeMap::new(),
+                polled: BTreeMap::new(),
+                drivers: BTreeSet::new(),
+                waiting: None,
+                command: ControllerCommandType::None,
+                driver_statistics: DriverStatistics::default(),
+            }),
+            watchers: Mutex::new(Vec::new()),
+        });
+        super::register(home_id, network.clone());
+        network
+    }
+
+    pub fn home_id(&self) -> u32 {
+        self.home_id
+    }
+
+    /// Stops routing calls for this home id to the simulation.
+    pub fn detach(&self) {
+        super::unregister(self.home_id);
+    }
+
+    /// When enabled (the default) every write is immediately reported back by the
+    /// simulated device with a `ValueChanged` notification.
+    pub fn set_auto_confirm(&self, auto_confirm: bool) {
+        self.model.lock().unwrap().auto_confirm = auto_confirm;
+    }
+
+    pub fn add_node(&self, node_id: u8, node: SimulatedNode) {
+        self.model.lock().unwrap().nodes.insert(node_id, node);
+        self.emit_node(NotificationType::NodeAdded, node_id);
+    }
+
+    pub fn remove_node(&self, node_id: u8) {
+        let removed: Vec<u64> = {
+            let mut model = self.model.lock().unwrap();
+            model.nodes.remove(&node_id);
+            let removed: Vec<u64> = model
+                .values
+                .keys()
+                .filter(|id| (**id as u32 >> 24) as u8 == node_id)
+                .cloned()
+                .collect();
+            for id in &removed {
+                model.values.remove(id);
+            }
+            removed
+        };
+        for id in removed {
+            self.emit_value_removed(id);
+        }
+        self.emit_node(NotificationType::NodeRemoved, node_id);
+    }
+
+    /// A device joins the network during an inclusion started with `add_node`.
+    /// Returns false if no inclusion is running.
+    pub fn include_node(&self, node_id: u8, node: SimulatedNode) -> bool {
+        if !self.stop_waiting(Waiting::Inclusion) {
+            return false;
+        }
+        self.emit_controller_state(ControllerState::InProgress);
+        self.add_node(node_id, node);
+        self.emit_controller_state(ControllerState::Completed);
+        true
+    }
+
+    /// A device leaves the network during an exclusion started with `remove_node`.
+    /// Returns false if no exclusion is running.
+    pub fn exclude_node(&self, node_id: u8) -> bool {
+        if !self.stop_waiting(Waiting::Exclusion) {
+            return false;
+        }
+        self.emit_controller_state(ControllerState::InProgress);
+        self.remove_node(node_id);
+        self.emit_controller_state(ControllerState::Completed);
+        true
+    }
+
+    /// OpenZWave warns the user, about `node_id` or the driver itself when it's 0.
+    pub fn user_alert(&self, node_id: u8, alert: UserAlert) {
+        let mut notification = self.notification(NotificationType::UserAlerts, node_id);
+        notification.value = Some(NotificationValue::UserAlert(alert));
+        self.emit(&notification);
+    }
+
+    /// Changes the static description of a node, without emitting anything.
+    pub fn update_node<F: FnOnce(&mut SimulatedNode)>(&self, node_id: u8, f: F) {
+        if let Some(node) = self.model.lock().unwrap().nodes.get_mut(&node_id) {
+            f(node);
+        }
+    }
+
+    pub fn set_neighbors(&self, node_id: u8, neighbors: Vec<u8>) {
+        self.update_node(node_id, |node| node.neighbors = neighbors);
+    }
+
+    pub fn set_node_statistics(&self, node_id: u8, statistics: NodeStatistics) {
+        self.update_node(node_id, |node| node.statistics = statistics);
+    }
+
+    pub fn set_driver_statistics(&self, statistics: DriverStatistics) {
+        self.model.lock().unwrap().driver_statistics = statistics;
+    }
+
+    pub fn node_ids(&self) -> Vec<u8> {
+        self.model.lock().unwrap().nodes.keys().cloned().collect()
+    }
+
+    pub fn add_value(&self, node_id: u8, value: SimulatedValue) -> ValueID {
+        let id = value.packed_id(node_id);
+        self.model.lock().unwrap().values.insert(id, value);
+        self.emit_value(NotificationType::ValueAdded, id)
+    }
+
+    pub fn remove_value(&self, value_id: &ValueID) {
+        let removed = self
+            .model
+            .lock()
+            .unwrap()
+            .values
+            .remove(&value_id.id())
+            .is_some();
+        if removed {
+            self.emit_value_removed(value_id.id());
+        }
+    }
+
+    /// The device reports a new value, as if it was changed locally.
+    pub fn update_value(&self, value_id: &ValueID, content: ValueContent) {
+        let changed = {
+            let mut model = self.model.lock().unwrap();
+            match model.values.get_mut(&value_id.id()) {
+                Some(value) => {
+                    let changed = value.content.to_string() != content.to_string();
+                    value.content = content;
+                    Some(changed)
+                }
+                None => None,
+            }
+        };
+        match changed {
+            Some(true) => {
+                self.emit_value(NotificationType::ValueChanged, value_id.id());
+            }
+            Some(false) => {
+                self.emit_value(NotificationType::ValueRefreshed, value_id.id());
+            }
+            None => {}
+        }
+    }
+
+    pub fn values(&self, node_id: u8) -> Vec<ValueID> {
+        let ids: Vec<u64> = self
+            .model
+            .lock()
+            .unwrap()
+            .values
+            .keys()
+            .filter(|id| (**id as u32 >> 24) as u8 == node_id)
+            .cloned()
+            .collect();
+        ids.into_iter()
+            .map(|id| ValueID::from_packed_id(self.home_id, id))
+            .collect()
+    }
+
+    /// Sends an arbitrary notification to every watcher.
+    pub fn emit(&self, notification: &Notification) {
+        let watchers = self.watchers.lock().unwrap().clone();
+        for watcher in watchers {
+            watcher.on_notification(notification);
+        }
+    }
+
+    fn notification(&self, notification_type: NotificationType, node_id: u8) -> Notification {
+        Notification {
+            notification_type,
+            home_id: self.home_id,
+            node_id,
+            value_id: None,
+            value: None,
+            event: None,
+            command: None,
+            byte: 0,
+            description: describe(|| format!("{:?}", notification_type)),
+        }
+    }
+
+    fn emit_node(&self, notification_type: NotificationType, node_id: u8) {
+        self.emit(&self.notification(notification_type, node_id));
+    }
+
+    fn emit_value(&self, notification_type: NotificationType, id: u64) -> ValueID {
+        let value_id = ValueID::from_packed_id(self.home_id, id);
+        let mut notification = self.notification(notification_type, value_id.get_node_id());
+        notification.value_id = Some(value_id.clone());
+        self.emit(&notification);
+        value_id
+    }
+
+    fn emit_value_removed(&self, id: u64) {
+        let value_id = ValueID::removed(self.home_id, id);
+        let mut notification = self.notification(NotificationType::ValueRemoved, value_id.get_node_id());
+        notification.value_id = Some(value_id);
+        self.emit(&notification);
+    }
+
+    fn emit_group(&self, node_id: u8, group_idx: u8) {
+        let mut notification = self.notification(NotificationType::Group, node_id);
+        notification.value = Some(NotificationValue::Group(group_idx));
+        notification.byte = group_idx;
+        self.emit(&notification);
+    }
+
+    // The device applies the change if it can and reports the group, which OpenZWave notifies
+    fn change_association<F: FnOnce(&mut SimulatedGroup)>(&self, node_id: u8, group_idx: u8, f: F) {
+        {
+            let mut model = self.model.lock().unwrap();
+            if !model.auto_confirm {
+                return;
+            }
+            match model.nodes.get_mut(&node_id).and_then(|node| node.groups.get_mut(&group_idx)) {
+                Some(group) => f(group),
+                None => return,
+            }
+        }
+        self.emit_group(node_id, group_idx);
+    }
+
+    fn emit_controller_state(&self, state: ControllerState) {
+        self.emit_controller_error(state, ControllerError::None);
+    }
+
+    fn emit_controller_error(&self, state: ControllerState, error: ControllerError) {
+        let (controller_node_id, command) = {
+            let model = self.model.lock().unwrap();
+            (model.controller_node_id, model.command)
+        };
+        let mut notification = self.notification(NotificationType::ControllerCommand, controller_node_id);
+        notification.value = Some(NotificationValue::ControllerError(error));
+        notification.event = Some(Event::Controller(Some(state)));
+        notification.command = Some(command);
+        notification.byte = error as u8;
+        notification.description = describe(|| format!("ControllerCommand - {}", state));
+        self.emit(&notification);
+    }
+
+    // The command the next controller notifications are about
+    fn begin_command(&self, command: ControllerCommandType) {
+        self.model.lock().unwrap().command = command;
+    }
+
+    // Runs a command the simulated devices carry out immediately.
+    fn run_command(&self, outcome: Result<(), ControllerError>) -> bool {
+        self.emit_controller_state(ControllerState::Starting);
+        match outcome {
+            Ok(()) => self.emit_controller_state(ControllerState::Completed),
+            Err(error) => self.emit_controller_error(ControllerState::Failed, error),
+        }
+        true
+    }
+
+    fn run_node_command(&self, node_id: u8) -> bool {
+        let outcome = match self.with_node(node_id, |_| ()) {
+            Some(()) => Ok(()),
+            None => Err(ControllerError::NodeNotFound),
+        };
+        self.run_command(outcome)
+    }
+
+    fn button_command(&self, node_id: u8) -> bool {
+        if self.model.lock().unwrap().bridge {
+            self.run_node_command(node_id)
+        } else {
+            self.run_command(Err(ControllerError::NotBridge))
+        }
+    }
+
+    // Runs a command waiting for the user to act on another device.
+    fn wait_command(&self, waiting: Waiting, refused: Option<ControllerError>) -> bool {
+        match refused {
+            Some(error) => self.run_command(Err(error)),
+            None => {
+                self.model.lock().unwrap().waiting = Some(waiting);
+                self.emit_controller_state(ControllerState::Starting);
+                self.emit_controller_state(ControllerState::Waiting);
+                true
+            }
+        }
+    }
+
+    fn stop_waiting(&self, waiting: Waiting) -> bool {
+        let mut model = self.model.lock().unwrap();
+        if model.waiting.as_ref() == Some(&waiting) {
+            model.waiting = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Replays the whole network the way OpenZWave does after a driver is added.
+    fn announce(&self) {
+        let (controller_node_id, nodes, values) = {
+            let model = self.model.lock().unwrap();
+            (
+                model.controller_node_id,
+                model.nodes.keys().cloned().collect::<Vec<u8>>(),
+                model.values.keys().cloned().collect::<Vec<u64>>(),
+            )
+        };
+
+        self.emit_node(NotificationType::DriverReady, controller_node_id);
+        for node_id in &nodes {
+            self.emit_node(NotificationType::NodeAdded, *node_id);
+        }
+        for id in values {
+            self.emit_value(NotificationType::ValueAdded, id);
+        }
+        for node_id in &nodes {
+            self.emit_node(NotificationType::NodeQueriesComplete, *node_id);
+        }
+        self.emit_node(NotificationType::AllNodesQueried, controller_node_id);
+    }
+
+    fn with_node<R, F: FnOnce(&SimulatedNode) -> R>(&self, node_id: u8, f: F) -> Option<R> {
+        self.model.lock().unwrap().nodes.get(&node_id).map(f)
+    }
+
+    fn with_group<R, F: FnOnce(&SimulatedGroup) -> R>(&self, node_id: u8, group_idx: u8, f: F) -> Option<R> {
+        self.model
+            .lock()
+            .unwrap()
+            .nodes
+            .get(&node_id)
+            .and_then(|node| node.groups.get(&group_idx))
+            .map(f)
+    }
+
+    fn with_value<R, F: FnOnce(&SimulatedValue) -> Option<R>>(
+        &self,
+        vid: &ExternValueID,
+        f: F,
+    ) -> Option<R> {
+        self.model
+            .lock()
+            .unwrap()
+            .values
+            .get(&packed_id(vid))
+            .and_then(f)
+    }
+
+    fn config_params(&self, node_id: u8, param: Option<u8>) -> Vec<u64> {
+        self.model
+            .lock()
+            .unwrap()
+            .values
+            .iter()
+            .filter(|(id, value)| {
+                (**id as u32 >> 24) as u8 == node_id
+                    && value.genre == ValueGenre::Config
+                    && value.command_class == 0x70
+                    && param.is_none_or(|param| value.index == param as u16)
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn write_value<F: FnOnce(&SimulatedValue) -> Option<ValueContent>>(
+        &self,
+        vid: &ExternValueID,
+        f: F,
+    ) -> bool {
+        let confirm = {
+            let mut model = self.model.lock().unwrap();
+            let auto_confirm = model.auto_confirm;
+            let value = match model.values.get_mut(&packed_id(vid)) {
+                Some(value) if !value.read_only => value,
+                _ => return false,
+            };
+            match f(value) {
+                Some(content) => {
+                    if auto_confirm {
+                        value.content = content;
+                    }
+                    auto_confirm
+                }
+                None => return false,
+            }
+        };
+        if confirm {
+            self.emit_value(NotificationType::ValueChanged, packed_id(vid));
+        }
+        true
+    }
+}
+
+macro_rules! node_field {
+    ( $( $name: ident -> $t: ty = $field: ident ),+ ) => {
+        $(fn $name(&self, _home_id: u32, node_id: u8) -> $t {
+            self.with_node(node_id, |node| node.$field.clone()).unwrap_or_default()
+        })*
+    };
+}
+
+macro_rules! value_field {
+    ( $( $name: ident -> $t: ty = $field: ident ),+ ) => {
+        $(fn $name(&self, vid: &ExternValueID) -> $t {
+            self.with_value(vid, |value| Some(value.$field.clone())).unwrap_or_default()
+        })*
+    };
+}
+
+macro_rules! value_field_setter {
+    ( $( $name: ident = $field: ident ),+ ) => {
+        $(fn $name(&self, vid: &ExternValueID, value: &str) {
+            if let Some(val) = self.model.lock().unwrap().values.get_mut(&packed_id(vid)) {
+                val.$field = value.to_string();
+            }
+        })*
+    };
+}
+
+impl ZWaveBackend for SimulatedNetwork {
+    fn add_watcher(&self, watcher: Arc<dyn NotificationWatcher>) -> bool {
+        let mut watchers = self.watchers.lock().unwrap();
+        if watchers.iter().any(|w| Arc::ptr_eq(w, &watcher)) {
+            return false;
+        }
+        watchers.push(watcher);
+        true
+    }
+
+    fn remove_watcher(&self, watcher: &Arc<dyn NotificationWatcher>) -> bool {
+        let mut watchers = self.watchers.lock().unwrap();
+        let before = watchers.len();
+        watchers.retain(|w| !Arc::ptr_eq(w, watcher));
+        watchers.len() != before
+    }
+
+    fn add_driver(&self, device: &str, _interface: ControllerInterface) -> bool {
+        let added = self.model.lock().unwrap().drivers.insert(device.to_string());
+        if added {
+            self.announce();
+        }
+        added
+    }
+
+    fn remove_driver(&self, device: &str) -> bool {
+        let removed = self.model.lock().unwrap().drivers.remove(device);
+        if removed {
+            let controller_node_id = self.model.lock().unwrap().controller_node_id;
+            self.emit_node(NotificationType::DriverRemoved, controller_node_id);
+        }
+        removed
+    }
+
+    fn get_poll_interval(&self) -> i32 {
+        self.model.lock().unwrap().poll_interval
+    }
+
+    fn set_poll_interval(&self, interval_ms: i32, _is_between_each_poll: bool) {
+        self.model.lock().unwrap().poll_interval = interval_ms;
+    }
+
+    fn get_controller_node_id(&self, _home_id: u32) -> u8 {
+        self.model.lock().unwrap().controller_node_id
+    }
+
+    fn get_suc_node_id(&self, _home_id: u32) -> u8 {
+        self.model.lock().unwrap().suc_node_id
+    }
+
+    fn is_primary_controller(&self, _home_id: u32) -> bool {
+        self.model.lock().unwrap().primary
+    }
+
+    fn is_bridge_controller(&self, _home_id: u32) -> bool {
+        self.model.lock().unwrap().bridge
+    }
+
+    fn get_send_queue_count(&self, _home_id: u32) -> i32 {
+        0
+    }
+
+    fn log_driver_statistics(&self, _home_id: u32) {}
+
+    fn get_driver_statistics(&self, _home_id: u32) -> DriverStatistics {
+        self.model.lock().unwrap().driver_statistics
+    }
+
+    fn get_controller_interface_type(&self, _home_id: u32) -> ControllerInterface {
+        ControllerInterface::Unknown
+    }
+
+    fn get_library_version(&self, _home_id: u32) -> String {
+        self.model.lock().unwrap().library_version.clone()
+    }
+
+    fn get_library_type_name(&self, _home_id: u32) -> String {
+        self.model.lock().unwrap().library_type_name.clone()
+    }
+
+    fn get_controller_path(&self, _home_id: u32) -> String {
+        self.model.lock().unwrap().controller_path.clone()
+    }
+
+    fn reset_controller(&self, _home_id: u32) {
+        let controller_node_id = {
+            let mut model = self.model.lock().unwrap();
+            let controller_node_id = model.controller_node_id;
+            model.nodes.retain(|node_id, _| *node_id == controller_node_id);
+            model.values.retain(|id, _| (*id as u32 >> 24) as u8 == controller_node_id);
+            controller_node_id
+        };
+        self.emit_node(NotificationType::DriverReset, controller_node_id);
+    }
+
+    fn soft_reset_controller(&self, _home_id: u32) {}
+
+    fn cancel_controller_command(&self, _home_id: u32) {
+        self.model.lock().unwrap().waiting = None;
+        self.emit_controller_state(ControllerState::Cancel);
+    }
+
+    fn add_node(&self, _home_id: u32, _secure: bool) -> bool {
+        self.begin_command(ControllerCommandType::AddDevice);
+        self.wait_command(Waiting::Inclusion, None)
+    }
+
+    fn remove_node(&self, _home_id: u32) -> bool {
+        self.begin_command(ControllerCommandType::RemoveDevice);
+        self.wait_command(Waiting::Exclusion, None)
+    }
+
+    fn remove_failed_node(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::RemoveFailedNode);
+        match self.with_node(node_id, |node| node.failed) {
+            Some(true) => {
+                self.emit_controller_state(ControllerState::Starting);
+                self.emit_controller_state(ControllerState::InProgress);
+                self.remove_node(node_id);
+                self.emit_controller_state(ControllerState::Completed);
+                true
+            }
+            Some(false) => self.run_command(Err(ControllerError::NotFound)),
+            None => self.run_command(Err(ControllerError::NodeNotFound)),
+        }
+    }
+
+    fn has_node_failed(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::HasNodeFailed);
+        match self.with_node(node_id, |node| node.failed) {
+            Some(failed) => {
+                self.emit_controller_state(ControllerState::Starting);
+                self.emit_controller_state(ControllerState::InProgress);
+                self.emit_controller_state(if failed {
+                    ControllerState::NodeFailed
+                } else {
+                    ControllerState::NodeOK
+                });
+                true
+            }
+            None => self.run_command(Err(ControllerError::NodeNotFound)),
+        }
+    }
+
+    fn replace_failed_node(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::ReplaceFailedNode);
+        match self.with_node(node_id, |node| node.failed) {
+            Some(true) => self.wait_command(Waiting::Other, None),
+            Some(false) => self.run_command(Err(ControllerError::NotFound)),
+            None => self.run_command(Err(ControllerError::NodeNotFound)),
+        }
+    }
+
+    fn request_network_update(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::RequestNetworkUpdate);
+        self.run_node_command(node_id)
+    }
+
+    fn request_node_neighbor_update(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::RequestNodeNeighborUpdate);
+        self.run_node_command(node_id)
+    }
+
+    fn assign_return_route(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::AssignReturnRoute);
+        self.run_node_command(node_id)
+    }
+
+    fn delete_all_return_routes(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::DeleteAllReturnRoutes);
+        self.run_node_command(node_id)
+    }
+
+    fn send_node_information(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::SendNodeInformation);
+        self.run_node_command(node_id)
+    }
+
+    fn replication_send(&self, _home_id: u32, node_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::ReplicationSend);
+        self.run_node_command(node_id)
+    }
+
+    fn create_new_primary(&self, _home_id: u32) -> bool {
+        self.begin_command(ControllerCommandType::CreateNewPrimary);
+        self.wait_command(Waiting::Other, None)
+    }
+
+    fn receive_configuration(&self, _home_id: u32) -> bool {
+        self.begin_command(ControllerCommandType::ReceiveConfiguration);
+        let primary = self.model.lock().unwrap().primary;
+        self.wait_command(Waiting::Other, if primary { Some(ControllerError::IsPrimary) } else { None })
+    }
+
+    fn transfer_primary_role(&self, _home_id: u32) -> bool {
+        self.begin_command(ControllerCommandType::TransferPrimaryRole);
+        let primary = self.model.lock().unwrap().primary;
+        self.wait_command(Waiting::Other, if primary { None } else { Some(ControllerError::NotPrimary) })
+    }
+
+    fn create_button(&self, _home_id: u32, node_id: u8, _button_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::CreateButton);
+        self.button_command(node_id)
+    }
+
+    fn delete_button(&self, _home_id: u32, node_id: u8, _button_id: u8) -> bool {
+        self.begin_command(ControllerCommandType::DeleteButton);
+        self.button_command(node_id)
+    }
+
+    fn test_network(&self, _home_id: u32, _count: u32) {}
+
+    fn test_network_node(&self, _home_id: u32, _node_id: u8, _count: u32) {}
+
+    fn heal_network(&self, _home_id: u32, _do_rr: bool) {}
+
+    fn heal_network_node(&self, _home_id: u32, _node_id: u8, _do_rr: bool) {}
+
+    fn request_node_state(&self, _home_id: u32, node_id: u8) -> bool {
+        self.with_node(node_id, |_| ()).is_some()
+    }
+
+    fn request_all_config_params(&self, _home_id: u32, node_id: u8) {
+        for id in self.config_params(node_id, None) {
+            self.emit_value(NotificationType::ValueRefreshed, id);
+        }
+    }
+
+    fn set_config_param(&self, _home_id: u32, node_id: u8, param: u8, value: i32, size: u8) -> bool {
+        if self.with_node(node_id, |_| ()).is_none() {
+            return false;
+        }
+        let report = {
+            let mut model = self.model.lock().unwrap();
+            if !model.auto_confirm {
+                return true;
+            }
+            let existing = model.values.iter_mut().find(|(id, current)| {
+                (**id as u32 >> 24) as u8 == node_id && current.is_config_param(param)
+            });
+            match existing {
+                Some((&id, current)) => match current.config_content(value) {
+                    Some(content) => {
+                        let notification_type = if current.content.to_string() != content.to_string() {
+                            NotificationType::ValueChanged
+                        } else {
+                            NotificationType::ValueRefreshed
+                        };
+                        current.content = content;
+                        Some((id, notification_type))
+                    }
+                    None => None,
+                },
+                None => {
+                    // Like OpenZWave, values reported for unknown parameters are created on the fly
+                    let content = match size {
+                        1 => ValueContent::Byte(value as u8),
+                        2 => ValueContent::Short(value as i16),
+                        _ => ValueContent::Int(value),
+                    };
+                    let mut created =
+                        SimulatedValue::new(0x70, param as u16, &format!("Parameter #{}", param), content);
+                    created.genre = ValueGenre::Config;
+                    let id = created.packed_id(node_id);
+                    model.values.insert(id, created);
+                    Some((id, NotificationType::ValueAdded))
+                }
+            }
+        };
+        if let Some((id, notification_type)) = report {
+            self.emit_value(notification_type, id);
+        }
+        true
+    }
+
+    fn request_config_param(&self, _home_id: u32, node_id: u8, param: u8) {
+        for id in self.config_params(node_id, Some(param)) {
+            self.emit_value(NotificationType::ValueRefreshed, id);
+        }
+    }
+
+    node_field! {
+        is_node_listening_device -> bool = listening,
+        is_node_frequent_listening_device -> bool = frequent_listening,
+        is_node_beaming_device -> bool = beaming,
+        is_node_routing_device -> bool = routing,
+        is_node_security_device -> bool = security_device,
+        get_node_max_baud_rate -> u32 = max_baud_rate,
+        get_node_version -> u8 = version,
+        get_node_security -> u8 = security,
+        is_node_zwave_plus -> bool = zwave_plus,
+        get_node_basic -> u8 = basic,
+        get_node_generic -> u8 = generic,
+        get_node_specific -> u8 = specific,
+        get_node_device_type -> u16 = device_type,
+        get_node_role -> u8 = role,
+        get_node_plus_type -> u8 = plus_type,
+        is_node_info_received -> bool = info_received,
+        is_node_awake -> bool = awake,
+        is_node_failed -> bool = failed,
+        get_node_type -> String = type_name,
+        get_node_manufacturer_name -> String = manufacturer_name,
+        get_node_product_name -> String = product_name,
+        get_node_name -> String = name,
+        get_node_location -> String = location,
+        get_node_manufacturer_id -> String = manufacturer_id,
+        get_node_product_type -> String = product_type,
+        get_node_product_id -> String = product_id,
+        get_node_query_stage -> String = query_stage,
+        get_node_device_type_string -> String = device_type_string,
+        get_node_role_string -> String = role_string,
+        get_node_plus_type_string -> String = plus_type_string
+    }
+
+    fn get_node_neighbors(&self, _home_id: u32, node_id: u8) -> Option<Vec<u8>> {
+        self.with_node(node_id, |node| node.neighbors.clone())
+            .filter(|neighbors| !neighbors.is_empty())
+    }
+
+    fn get_node_class_information(
+        &self,
+        _home_id: u32,
+        node_id: u8,
+        command_class_id: u8,
+    ) -> Option<(String, u8)> {
+        self.with_node(node_id, |node| node.command_classes.get(&command_class_id).cloned())
+            .and_then(|class| class)
+    }
+
+    fn get_node_num_groups(&self, _home_id: u32, node_id: u8) -> u8 {
+        self.with_node(node_id, |node| node.groups.keys().next_back().cloned().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    fn get_node_group_label(&self, _home_id: u32, node_id: u8, group_idx: u8) -> String {
+        self.with_group(node_id, group_idx, |group| group.label.clone())
+            .unwrap_or_default()
+    }
+
+    fn get_node_max_associations(&self, _home_id: u32, node_id: u8, group_idx: u8) -> u8 {
+        self.with_group(node_id, group_idx, |group| group.max_associations)
+            .unwrap_or(0)
+    }
+
+    fn is_node_multi_instance_group(&self, _home_id: u32, node_id: u8, group_idx: u8) -> bool {
+        self.with_group(node_id, group_idx, |group| group.multi_instance)
+            .unwrap_or(false)
+    }
+
+    fn get_node_associations(&self, _home_id: u32, node_id: u8, group_idx: u8) -> Vec<InstanceAssociation> {
+        self.with_group(node_id, group_idx, |group| group.associations.clone())
+            .unwrap_or_default()
+    }
+
+    fn add_node_association(&self, _home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation) {
+        self.change_association(node_id, group_idx, |group| {
+            let full = group.associations.len() >= group.max_associations as usize;
+            if !full && !group.associations.contains(&target) {
+                group.associations.push(target);
+            }
+        });
+    }
+
+    fn remove_node_association(&self, _home_id: u32, node_id: u8, group_idx: u8, target: InstanceAssociation) {
+        self.change_association(node_id, group_idx, |group| {
+            group.associations.retain(|association| *association != target)
+        });
+    }
+
+    fn get_node_statistics(&self, _home_id: u32, node_id: u8) -> NodeStatistics {
+        self.with_node(node_id, |node| node.statistics.clone())
+            .unwrap_or_default()
+    }
+
+    value_field! {
+        get_value_label -> String = label,
+        get_value_units -> String = units,
+        get_value_help -> String = help,
+        get_value_min -> i32 = min,
+        get_value_max -> i32 = max,
+        is_value_read_only -> bool = read_only,
+        is_value_write_only -> bool = write_only
+    }
+
+    value_field_setter! {
+        set_value_label = label,
+        set_value_units = units,
+        set_value_help = help
+    }
+
+    fn is_value_set(&self, vid: &ExternValueID) -> bool {
+        self.with_value(vid, |value| match value.content {
+            ValueContent::Unknown => None,
+            _ => Some(()),
+        })
+        .is_some()
+    }
+
+    fn is_value_polled(&self, vid: &ExternValueID) -> bool {
+        self.is_polled(vid)
+    }
+
+    fn get_value_as_bool(&self, vid: &ExternValueID) -> Option<bool> {
+        self.with_value(vid, |value| match value.content {
+            ValueContent::Bool(val) | ValueContent::Button(val) => Some(val),
+            _ => None,
+        })
+    }
+
+    fn get_value_as_byte(&self, vid: &ExternValueID) -> Option<u8> {
+        self.with_value(vid, |value| match value.content {
+            ValueContent::Byte(val) => Some(val),
+            _ => None,
+        })
+    }
+
+    fn get_value_as_float(&self, vid: &ExternValueID) -> Option<f32> {
+        self.with_value(vid, |value| match &value.content {
+            ValueContent::Decimal(val) => Some(val.to_f32()),
+            _ => None,
+        })
+    }
+
+    fn get_value_float_precision(&self, vid: &ExternValueID) -> Option<u8> {
+        self.with_value(vid, |value| match &value.content {
+            ValueContent::Decimal(val) => Some(val.precision()),
+            _ => None,
+        })
+    }
+
+    fn get_value_as_int(&self, vid: &ExternValueID) -> Option<i32> {
+        self.with_value(vid, |value| match value.content {
+            ValueContent::Int(val) => Some(val),
+            _ => None,
+        })
+    }
+
+    fn get_value_as_short(&self, vid: &ExternValueID) -> Option<i16> {
+        self.with_value(vid, |value| match value.content {
+            ValueContent::Short(val) => Some(val),
+            _ => None,
+        })
+    }
+
+    fn get_value_as_string(&self, vid: &ExternValueID) -> Option<String> {
+        self.with_value(vid, |value| Some(value.as_string()))
+    }
+
+    fn get_value_as_raw(&self, vid: &ExternValueID) -> Option<Vec<u8>> {
+        self.with_value(vid, |value| match &value.content {
+            ValueContent::Raw(val) => Some(val.clone()),
+            _ => None,
+        })
+    }
+
+    fn get_value_list_selection_as_string(&self, vid: &ExternValueID) -> Option<String> {
+        self.with_value(vid, |value| value.list().map(|list| list.selected.clone()))
+    }
+
+    fn get_value_list_selection_as_int(&self, vid: &ExternValueID) -> Option<i32> {
+        self.with_value(vid, |value| value.list().map(|list| list.selected_value))
+    }
+
+    fn get_value_list_items(&self, vid: &ExternValueID) -> Option<Vec<String>> {
+        self.with_value(vid, |value| {
+            value.list().map(|list| list.items.iter().map(|item| item.label.clone()).collect())
+        })
+    }
+
+    fn get_value_list_values(&self, vid: &ExternValueID) -> Option<Vec<i32>> {
+        self.with_value(vid, |value| {
+            value.list().map(|list| list.items.iter().map(|item| item.value).collect())
+        })
+    }
+
+    fn set_value_bool(&self, vid: &ExternValueID, value: bool) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::Bool => Some(ValueContent::Bool(value)),
+            ValueType::Button => Some(ValueContent::Button(value)),
+            _ => None,
+        })
+    }
+
+    fn set_value_byte(&self, vid: &ExternValueID, value: u8) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::Byte => Some(ValueContent::Byte(value)),
+            _ => None,
+        })
+    }
+
+    fn set_value_float(&self, vid: &ExternValueID, value: f32) -> bool {
+        self.write_value(vid, |current| match &current.content {
+            ValueContent::Decimal(val) => {
+                DecimalValue::from_f32(value, val.precision()).map(ValueContent::Decimal)
+            }
+            _ => None,
+        })
+    }
+
+    fn set_value_int(&self, vid: &ExternValueID, value: i32) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::Int => Some(ValueContent::Int(value)),
+            _ => None,
+        })
+    }
+
+    fn set_value_short(&self, vid: &ExternValueID, value: i16) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::Short => Some(ValueContent::Short(value)),
+            _ => None,
+        })
+    }
+
+    fn set_value_string(&self, vid: &ExternValueID, value: &str) -> bool {
+        self.write_value(vid, |current| current.parse(value))
+    }
+
+    fn set_value_raw(&self, vid: &ExternValueID, value: &[u8]) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::Raw => Some(ValueContent::Raw(value.to_vec())),
+            _ => None,
+        })
+    }
+
+    fn set_value_list_selection_string(&self, vid: &ExternValueID, value: &str) -> bool {
+        self.write_value(vid, |current| match current.value_type {
+            ValueType::List => current.parse(value),
+            _ => None,
+        })
+    }
+
+    fn set_value_list_selection_int(&self, vid: &ExternValueID, value: i32) -> bool {
+        self.write_value(vid, |current| current.select_list_value(value))
+    }
+
+    fn get_num_switch_points(&self, vid: &ExternValueID) -> u8 {
+        self.with_value(vid, |value| value.schedule().map(|schedule| schedule.switch_points.len() as u8))
+            .unwrap_or(0)
+    }
+
+    fn get_switch_point(&self, vid: &ExternValueID, idx: u8) -> Option<SwitchPoint> {
+        self.with_value(vid, |value| {
+            value.schedule().and_then(|schedule| schedule.switch_points.get(idx as usize).cloned())
+        })
+    }
+
+    fn set_switch_point(&self, vid: &ExternValueID, point: SwitchPoint) -> bool {
+        self.write_value(vid, |current| {
+            current.change_schedule(|switch_points| {
+                let same_time = |other: &SwitchPoint| other.hours == point.hours && other.minutes == point.minutes;
+                switch_points.retain(|other| !same_time(other));
+                switch_points.push(point);
+                switch_points.len() <= MAX_SWITCH_POINTS
+            })
+        })
+    }
+
+    fn remove_switch_point(&self, vid: &ExternValueID, hours: u8, minutes: u8) -> bool {
+        self.write_value(vid, |current| {
+            current.change_schedule(|switch_points| {
+                let count = switch_points.len();
+                switch_points.retain(|point| point.hours != hours || point.minutes != minutes);
+                switch_points.len() < count
+            })
+        })
+    }
+
+    fn clear_switch_points(&self, vid: &ExternValueID) {
+        self.write_value(vid, |current| {
+            current.change_schedule(|switch_points| {
+                switch_points.clear();
+                true
+            })
+        });
+    }
+
+    fn get_value_bitset_size(&self, vid: &ExternValueID) -> Option<u8> {
+        self.with_value(vid, |value| value.bitset().map(|bitset| bitset.size))
+    }
+
+    fn get_value_bitmask(&self, vid: &ExternValueID) -> Option<u32> {
+        self.with_value(vid, |value| value.bitset().map(|bitset| bitset.mask))
+    }
+
+    fn get_value_as_bitset(&self, vid: &ExternValueID, pos: u8) -> Option<bool> {
+        self.with_value(vid, |value| value.bitset().and_then(|bitset| bitset.get(pos)))
+    }
+
+    fn get_value_bit_label(&self, vid: &ExternValueID, pos: u8) -> String {
+        self.with_value(vid, |value| {
+            value.bitset().and_then(|bitset| bitset.bit(pos)).map(|bit| bit.label.clone())
+        })
+        .unwrap_or_default()
+    }
+
+    fn set_value_bitset(&self, vid: &ExternValueID, pos: u8, value: bool) -> bool {
+        self.write_value(vid, |current| {
+            current
+                .bitset()
+                .and_then(|bitset| bitset.with_bit(pos, value))
+                .map(ValueContent::BitSet)
+        })
+    }
+
+    fn enable_poll(&self, vid: &ExternValueID) -> bool {
+        self.enable_poll_with_intensity(vid, 1)
+    }
+
+    fn enable_poll_with_intensity(&self, vid: &ExternValueID, intensity: u8) -> bool {
+        let mut model = self.model.lock().unwrap();
+        let id = packed_id(vid);
+        if !model.values.contains_key(&id) {
+            return false;
+        }
+        model.polled.insert(id, intensity);
+        true
+    }
+
+    fn disable_poll(&self, vid: &ExternValueID) -> bool {
+        self.model.lock().unwrap().polled.remove(&packed_id(vid)).is_some()
+    }
+
+    fn is_polled(&self, vid: &ExternValueID) -> bool {
+        self.model.lock().unwrap().polled.contains_key(&packed_id(vid))
+    }
+
+    fn set_poll_intensity(&self, vid: &ExternValueID, intensity: u8) {
+        if let Some(current) = self.model.lock().unwrap().polled.get_mut(&packed_id(vid)) {
+            *current = intensity;
+        }
+    }
+
+    fn get_poll_intensity(&self, vid: &ExternValueID) -> u8 {
+        self.model
+            .lock()
+            .unwrap()
+            .polled
+            .get(&packed_id(vid))
+            .cloned()
+            .unwrap_or(0)
+    }
+}
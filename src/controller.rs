@@ -1,6 +1,10 @@
+use crate::backend;
+use crate::controller_command::{ControllerCommand, NodeCommand};
+use crate::error::Result;
+use crate::notification::ControllerCommandType;
+use crate::statistics::DriverStatistics;
 use ffi::manager as extern_manager;
-use ffi::utils::{recover_string, rust_string_creator};
-use libc::c_char;
+use std::time::Duration;
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Controller {
@@ -11,8 +15,7 @@ macro_rules! network_impl {
     ( $( $name: ident -> $type_: ty ),+ ) => {
         $(
             pub fn $name(&self) -> $type_ {
-                let manager_ptr = unsafe { extern_manager::get() };
-                unsafe { extern_manager::$name(manager_ptr, self.home_id) }
+                backend::get(self.home_id).$name(self.home_id)
             }
         )*
     };
@@ -22,17 +25,28 @@ macro_rules! network_impl_string {
     ( $( $name: ident ),+ ) => {
         $(
             pub fn $name(&self) -> String {
-                let manager_ptr = unsafe { extern_manager::get() };
-                recover_string(
-                    unsafe {
-                        extern_manager::$name(manager_ptr, self.home_id, rust_string_creator)
-                    } as *mut c_char
-                )
+                backend::get(self.home_id).$name(self.home_id)
             }
          )*
     };
 }
 
+// starts controller commands taking a node id, and possibly other arguments, each
+// tracked as the ControllerCommandType OpenZWave notifies it with
+macro_rules! command_impl {
+    ( $( $(#[$doc: meta])* $name: ident ( $( $arg: ident: $t: ty ),* ) => $command: ident ),+ ) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(&self $(, $arg: $t)*) -> Result<ControllerCommand> {
+                let home_id = self.home_id;
+                ControllerCommand::start(home_id, ControllerCommandType::$command, |backend| {
+                    backend.$name(home_id $(, $arg)*)
+                })
+            }
+        )*
+    };
+}
+
 impl Controller {
     pub fn new(home_id: u32) -> Controller {
         Controller { home_id: home_id }
@@ -49,7 +63,8 @@ impl Controller {
         is_bridge_controller -> bool,
         get_send_queue_count -> i32,
         log_driver_statistics -> (),
-        get_controller_interface_type -> extern_manager::ControllerInterface
+        get_controller_interface_type -> extern_manager::ControllerInterface,
+        get_driver_statistics -> DriverStatistics
     }
 
     network_impl_string! {
@@ -57,6 +72,49 @@ impl Controller {
         get_library_type_name,
         get_controller_path
     }
+
+    /// Puts the controller in inclusion mode until a device joins or `timeout` expires.
+    pub fn include(&self, secure: bool, timeout: Duration) -> Result<NodeCommand> {
+        let home_id = self.home_id;
+        let command = ControllerCommand::start(home_id, ControllerCommandType::AddDevice, |backend| {
+            backend.add_node(home_id, secure)
+        })?;
+        Ok(NodeCommand::new(command, timeout))
+    }
+
+    /// Puts the controller in exclusion mode until a device leaves or `timeout` expires.
+    pub fn exclude(&self, timeout: Duration) -> Result<NodeCommand> {
+        let home_id = self.home_id;
+        let command = ControllerCommand::start(home_id, ControllerCommandType::RemoveDevice, |backend| {
+            backend.remove_node(home_id)
+        })?;
+        Ok(NodeCommand::new(command, timeout))
+    }
+
+    command_impl! {
+        /// Removes a node the controller has marked as failed.
+        remove_failed_node(node_id: u8) => RemoveFailedNode,
+        /// Resolves with `NodeOK` or `NodeFailed`.
+        has_node_failed(node_id: u8) => HasNodeFailed,
+        /// Replaces a failed node by the next device put in inclusion mode, keeping its id.
+        replace_failed_node(node_id: u8) => ReplaceFailedNode,
+        /// Asks the SUC for the latest network changes.
+        request_network_update(node_id: u8) => RequestNetworkUpdate,
+        request_node_neighbor_update(node_id: u8) => RequestNodeNeighborUpdate,
+        /// Tells the node how to reach the controller.
+        assign_return_route(node_id: u8) => AssignReturnRoute,
+        delete_all_return_routes(node_id: u8) => DeleteAllReturnRoutes,
+        send_node_information(node_id: u8) => SendNodeInformation,
+        /// Sends the network configuration to a secondary controller.
+        replication_send(node_id: u8) => ReplicationSend,
+        create_new_primary() => CreateNewPrimary,
+        /// Receives the network configuration from another controller.
+        receive_configuration() => ReceiveConfiguration,
+        transfer_primary_role() => TransferPrimaryRole,
+        /// Only for bridge controllers.
+        create_button(node_id: u8, button_id: u8) => CreateButton,
+        delete_button(node_id: u8, button_id: u8) => DeleteButton
+    }
 }
 
 use std::fmt::{self, Debug, Display, Formatter};
@@ -0,0 +1,235 @@
+use crate::backend::{self, ZWaveBackend};
+use crate::error::{Error, Result};
+use crate::manager::{NotificationWatcher, WatcherGuard};
+use crate::node::Node;
+use crate::event::ZWaveEvent;
+use crate::notification::{ControllerCommandType, ControllerError, ControllerState, Notification};
+use futures::channel::{mpsc, oneshot};
+use futures::stream::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Sleep;
+
+struct CommandWatcher {
+    home_id: u32,
+    command_type: ControllerCommandType,
+    state: Mutex<ControllerState>,
+    node_id: Mutex<Option<u8>>,
+    states: Mutex<Option<mpsc::UnboundedSender<ControllerState>>>,
+    sender: Mutex<Option<oneshot::Sender<Result<ControllerState>>>>,
+}
+
+impl NotificationWatcher for CommandWatcher {
+    fn on_notification(&self, notification: &Notification) {
+        // Nor the last states of an earlier command, e.g. one cancelled when dropped
+        if notification.home_id != self.home_id
+            || notification.command.is_some_and(|command| command != self.command_type)
+        {
+            return;
+        }
+        let (state, error) = match notification.to_event() {
+            Some(ZWaveEvent::ControllerCommand { state, error, .. }) => (state, error),
+            // Only the node the command is working on: nodes may also come and go meanwhile,
+            // e.g. when the network is queried again.
+            Some(ZWaveEvent::NodeAdded { node_id, .. })
+            | Some(ZWaveEvent::NodeRemoved { node_id, .. }) => {
+                if *self.state.lock().unwrap() == ControllerState::InProgress {
+                    self.node_id.lock().unwrap().get_or_insert(node_id);
+                }
+                return;
+            }
+            _ => return,
+        };
+        *self.state.lock().unwrap() = state;
+        if let Some(ref states) = *self.states.lock().unwrap() {
+            let _ = states.unbounded_send(state);
+        }
+
+        let outcome = match state {
+            ControllerState::Completed | ControllerState::NodeOK | ControllerState::NodeFailed => {
+                Ok(state)
+            }
+            ControllerState::Cancel | ControllerState::Error | ControllerState::Failed => {
+                Err(Error::ControllerCommandError(state, error))
+            }
+            _ => return,
+        };
+
+        // Ends the progress stream
+        self.states.lock().unwrap().take();
+        if let Some(sender) = self.sender.lock().unwrap().take() {
+            let _ = sender.send(outcome);
+        }
+    }
+}
+
+/// A running controller command, resolving with its final `ControllerState`.
+///
+/// `Completed` is the usual success, `HasNodeFailed` ends with `NodeOK` or `NodeFailed`.
+/// Commands ending as `Failed`, `Error` or `Cancel` resolve to a `ControllerCommandError`
+/// carrying the `ControllerError` reported by OpenZWave. Dropping the future stops
+/// tracking the command but doesn't cancel it.
+///
+/// It's also a `Stream` of every state reported until the command ends.
+pub struct ControllerCommand {
+    home_id: u32,
+    watcher: Arc<CommandWatcher>,
+    states: mpsc::UnboundedReceiver<ControllerState>,
+    receiver: oneshot::Receiver<Result<ControllerState>>,
+    _guard: WatcherGuard,
+}
+
+impl ControllerCommand {
+    pub(crate) fn start<F: FnOnce(&dyn ZWaveBackend) -> bool>(
+        home_id: u32,
+        command_type: ControllerCommandType,
+        command: F,
+    ) -> Result<ControllerCommand> {
+        let backend = backend::get(home_id);
+        let (sender, receiver) = oneshot::channel();
+        let (states_sender, states) = mpsc::unbounded();
+        let watcher = Arc::new(CommandWatcher {
+            home_id,
+            command_type,
+            state: Mutex::new(ControllerState::Normal),
+            node_id: Mutex::new(None),
+            states: Mutex::new(Some(states_sender)),
+            sender: Mutex::new(Some(sender)),
+        });
+
+        // Listen before starting: the first states may be notified before the call returns.
+        let guard = WatcherGuard::register(backend.clone(), watcher.clone())?;
+        if !command(&*backend) {
+            return Err(Error::APIError(
+                "The controller command could not be started, another one may be running",
+            ));
+        }
+
+        Ok(ControllerCommand {
+            home_id,
+            watcher,
+            states,
+            receiver,
+            _guard: guard,
+        })
+    }
+
+    pub fn command_type(&self) -> ControllerCommandType {
+        self.watcher.command_type
+    }
+
+    /// Last state reported for the command, e.g. `Waiting` while a user action is needed.
+    pub fn state(&self) -> ControllerState {
+        *self.watcher.state.lock().unwrap()
+    }
+
+    /// The first node added to or removed from the network once the command was in progress.
+    pub fn node_id(&self) -> Option<u8> {
+        *self.watcher.node_id.lock().unwrap()
+    }
+
+    pub fn cancel(&self) {
+        backend::get(self.home_id).cancel_controller_command(self.home_id);
+    }
+}
+
+impl Future for ControllerCommand {
+    type Output = Result<ControllerState>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map(|outcome| match outcome {
+            Ok(outcome) => outcome,
+            Err(_) => Err(Error::APIError("The controller command watcher went away")),
+        })
+    }
+}
+
+impl Stream for ControllerCommand {
+    type Item = ControllerState;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ControllerState>> {
+        Pin::new(&mut self.states).poll_next(cx)
+    }
+}
+
+/// An inclusion or exclusion started by `Controller::include` or `Controller::exclude`.
+///
+/// Resolves with the included or excluded `Node`. Streams the states of the underlying
+/// `ControllerCommand` meanwhile. The command is cancelled when the timeout expires, which
+/// fails with `ControllerCommandTimeout`, or when this is dropped before completion.
+pub struct NodeCommand {
+    command: ControllerCommand,
+    timeout: Duration,
+    deadline: Option<Pin<Box<Sleep>>>, //< started on the first poll, which needs a tokio runtime
+    done: bool,
+}
+
+impl NodeCommand {
+    pub(crate) fn new(command: ControllerCommand, timeout: Duration) -> NodeCommand {
+        NodeCommand {
+            command,
+            timeout,
+            deadline: None,
+            done: false,
+        }
+    }
+
+    pub fn state(&self) -> ControllerState {
+        self.command.state()
+    }
+
+    pub fn cancel(&self) {
+        self.command.cancel()
+    }
+}
+
+impl Future for NodeCommand {
+    type Output = Result<Node>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Node>> {
+        if self.done {
+            return Poll::Pending;
+        }
+
+        if let Poll::Ready(outcome) = Pin::new(&mut self.command).poll(cx) {
+            self.done = true;
+            let home_id = self.command.home_id;
+            return Poll::Ready(outcome.and_then(|state| match self.command.node_id() {
+                Some(node_id) => Ok(Node::from_id(home_id, node_id)),
+                // e.g. excluding a device from another network
+                None => Err(Error::ControllerCommandError(state, ControllerError::NodeNotFound)),
+            }));
+        }
+
+        let timeout = self.timeout;
+        let deadline = self
+            .deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+        if deadline.as_mut().poll(cx).is_ready() {
+            self.done = true;
+            let state = self.command.state();
+            self.command.cancel();
+            return Poll::Ready(Err(Error::ControllerCommandTimeout(state)));
+        }
+        Poll::Pending
+    }
+}
+
+impl Stream for NodeCommand {
+    type Item = ControllerState;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ControllerState>> {
+        Pin::new(&mut self.command).poll_next(cx)
+    }
+}
+
+impl Drop for NodeCommand {
+    fn drop(&mut self) {
+        if !self.done {
+            self.command.cancel();
+        }
+    }
+}
@@ -6,18 +6,46 @@ pub enum Error {
     OptionsAreLocked(&'static str),
     OptionsAreNotLocked(&'static str),
     InvalidParameter(&'static str, &'static str),
+    InvalidOption(&'static str, &'static str), //< option name, reason
+    ConfigFileError(String),
     APIError(&'static str),
     GetError(GetSetError),
-    SetError(GetSetError)
+    SetError(GetSetError),
+    ConfirmationError(ConfirmationError),
+    ControllerCommandError(ControllerState, ControllerError), //< the final state and its reason
+    ControllerCommandTimeout(ControllerState), //< the last state before the command was cancelled
 }
 
 #[derive(Debug)]
 pub enum GetSetError {
     APIError(&'static str),
     WrongType,
-    InvalidString
+    InvalidString,
+    OutOfRange { min: i32, max: i32 }, //< outside of the value's range, bounds included
+    NotAListItem,
+    ReadOnly,
+    ParseError, //< the string doesn't read as the value's type
 }
 
+#[derive(Debug)]
+pub enum ConfirmationError {
+    Timeout,     //< no confirmation arrived in time
+    NodeTimeout, //< OpenZWave gave up waiting for the node
+    NodeDead,
+    Rejected,    //< the device reported back without applying the change
+}
+
+/// Why a string or a notification could not be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    Format,                     //< not laid out as expected, e.g. missing parts or not a number
+    InvalidField(&'static str), //< the named part isn't valid for it
+    OutOfRange,                 //< a number with more digits than can be kept
+    Missing(&'static str),      //< a notification lacks the named part its type carries
+    Unknown,                    //< an unknown notification type or controller state
+}
+
+use ffi::notification::{ControllerError, ControllerState};
 use std::fmt;
 use std::error;
 impl fmt::Display for Error {
@@ -29,6 +57,21 @@ impl fmt::Display for Error {
             Error::GetError(ref specific_error) | Error::SetError(ref specific_error) => {
                 format!("{:?}: {:?}", self, specific_error)
             },
+            Error::InvalidOption(ref option, ref reason) => {
+                format!("InvalidOption Error: {} {}", option, reason)
+            },
+            Error::ConfigFileError(ref str) => {
+                format!("ConfigFileError: {}", str)
+            },
+            Error::ConfirmationError(ref specific_error) => {
+                format!("ConfirmationError: the value change was not confirmed: {:?}", specific_error)
+            },
+            Error::ControllerCommandError(ref state, ref error) => {
+                format!("ControllerCommandError: the command ended as {} with error {}", state, error)
+            },
+            Error::ControllerCommandTimeout(ref state) => {
+                format!("ControllerCommandTimeout: the command was cancelled while {}", state)
+            },
             Error::OptionsAreLocked(ref method) => {
                 format!("OptionsAreLocked Error: {:?} when calling method {}",
                         self,
@@ -60,13 +103,32 @@ impl error::Error for Error {
             Error::OptionsAreLocked(_) => "The underlying C++ Options object is locked",
             Error::OptionsAreNotLocked(_) => "The underlying C++ Options object is not locked",
             Error::InvalidParameter(_, _) => "One parameter is invalid",
+            Error::InvalidOption(_, _) => "One OpenZWave option is invalid",
+            Error::ConfigFileError(_) => "The options config file could not be loaded",
             Error::APIError(_) => "OpenZWave C++ library Error",
             Error::GetError(_) => "Error getting a value",
-            Error::SetError(_) => "Error setting a value"
+            Error::SetError(_) => "Error setting a value",
+            Error::ConfirmationError(_) => "A value change was not confirmed by the device",
+            Error::ControllerCommandError(_, _) => "A controller command did not complete",
+            Error::ControllerCommandTimeout(_) => "A controller command did not complete in time"
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Format => write!(formatter, "not in the expected format"),
+            ParseError::InvalidField(field) => write!(formatter, "invalid {}", field),
+            ParseError::OutOfRange => write!(formatter, "out of range"),
+            ParseError::Missing(field) => write!(formatter, "missing {}", field),
+            ParseError::Unknown => write!(formatter, "unknown"),
         }
     }
 }
 
+impl error::Error for ParseError {}
+
 use std::ffi::NulError;
 impl From<NulError> for Error {
     fn from(_: NulError) -> Error {
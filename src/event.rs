@@ -0,0 +1,256 @@
+use crate::error::ParseError;
+use crate::node::Node;
+use crate::notification::{
+    ControllerCommandType, ControllerError, ControllerState, Event, Notification,
+    NotificationCode, NotificationType, NotificationValue, UserAlert,
+};
+use crate::value_classes::value_id::ValueID;
+use std::convert::TryFrom;
+
+/// A notification as one variant per `NotificationType`, holding only what that type carries.
+#[derive(Debug, Clone)]
+pub enum ZWaveEvent {
+    ValueAdded { value: ValueID },
+    ValueRemoved { value: ValueID },
+    ValueChanged { value: ValueID },
+    ValueRefreshed { value: ValueID },
+    Group { home_id: u32, node_id: u8, group_idx: u8 },
+    NodeNew { home_id: u32, node_id: u8 },
+    NodeAdded { home_id: u32, node_id: u8 },
+    NodeRemoved { home_id: u32, node_id: u8 },
+    NodeProtocolInfo { home_id: u32, node_id: u8 },
+    NodeNaming { home_id: u32, node_id: u8 },
+    /// A Basic Set sent by a device that has no value for it, `level` being what it set
+    NodeEvent { home_id: u32, node_id: u8, level: u8 },
+    PollingDisabled { home_id: u32, node_id: u8 },
+    PollingEnabled { home_id: u32, node_id: u8 },
+    SceneEvent { home_id: u32, node_id: u8, scene_id: u8 },
+    CreateButton { home_id: u32, node_id: u8, button_id: u8 },
+    DeleteButton { home_id: u32, node_id: u8, button_id: u8 },
+    ButtonOn { home_id: u32, node_id: u8, button_id: u8 },
+    ButtonOff { home_id: u32, node_id: u8, button_id: u8 },
+    DriverReady { home_id: u32, node_id: u8 },
+    DriverFailed { home_id: u32 },
+    DriverReset { home_id: u32 },
+    DriverRemoved { home_id: u32 },
+    EssentialNodeQueriesComplete { home_id: u32, node_id: u8 },
+    NodeQueriesComplete { home_id: u32, node_id: u8 },
+    AwakeNodesQueried { home_id: u32 },
+    AllNodesQueriedSomeDead { home_id: u32 },
+    AllNodesQueried { home_id: u32 },
+    Notification { home_id: u32, node_id: u8, code: NotificationCode },
+    ControllerCommand {
+        home_id: u32,
+        command: ControllerCommandType,
+        state: ControllerState,
+        error: ControllerError,
+    },
+    NodeReset { home_id: u32, node_id: u8 },
+    /// `node_id` is 0 when the alert isn't about a node
+    UserAlert { home_id: u32, node_id: u8, alert: UserAlert },
+    ManufacturerSpecificDBReady { home_id: u32 },
+}
+
+impl ZWaveEvent {
+    pub fn home_id(&self) -> u32 {
+        match *self {
+            ZWaveEvent::ValueAdded { ref value }
+            | ZWaveEvent::ValueRemoved { ref value }
+            | ZWaveEvent::ValueChanged { ref value }
+            | ZWaveEvent::ValueRefreshed { ref value } => value.get_home_id(),
+            ZWaveEvent::Group { home_id, .. }
+            | ZWaveEvent::NodeNew { home_id, .. }
+            | ZWaveEvent::NodeAdded { home_id, .. }
+            | ZWaveEvent::NodeRemoved { home_id, .. }
+            | ZWaveEvent::NodeProtocolInfo { home_id, .. }
+            | ZWaveEvent::NodeNaming { home_id, .. }
+            | ZWaveEvent::NodeEvent { home_id, .. }
+            | ZWaveEvent::PollingDisabled { home_id, .. }
+            | ZWaveEvent::PollingEnabled { home_id, .. }
+            | ZWaveEvent::SceneEvent { home_id, .. }
+            | ZWaveEvent::CreateButton { home_id, .. }
+            | ZWaveEvent::DeleteButton { home_id, .. }
+            | ZWaveEvent::ButtonOn { home_id, .. }
+            | ZWaveEvent::ButtonOff { home_id, .. }
+            | ZWaveEvent::DriverReady { home_id, .. }
+            | ZWaveEvent::DriverFailed { home_id }
+            | ZWaveEvent::DriverReset { home_id }
+            | ZWaveEvent::DriverRemoved { home_id }
+            | ZWaveEvent::EssentialNodeQueriesComplete { home_id, .. }
+            | ZWaveEvent::NodeQueriesComplete { home_id, .. }
+            | ZWaveEvent::AwakeNodesQueried { home_id }
+            | ZWaveEvent::AllNodesQueriedSomeDead { home_id }
+            | ZWaveEvent::AllNodesQueried { home_id }
+            | ZWaveEvent::Notification { home_id, .. }
+            | ZWaveEvent::ControllerCommand { home_id, .. }
+            | ZWaveEvent::NodeReset { home_id, .. }
+            | ZWaveEvent::UserAlert { home_id, .. }
+            | ZWaveEvent::ManufacturerSpecificDBReady { home_id } => home_id,
+        }
+    }
+
+    /// The node the event is about, if any. For `DriverReady` it's the controller's.
+    pub fn node_id(&self) -> Option<u8> {
+        match *self {
+            ZWaveEvent::ValueAdded { ref value }
+            | ZWaveEvent::ValueRemoved { ref value }
+            | ZWaveEvent::ValueChanged { ref value }
+            | ZWaveEvent::ValueRefreshed { ref value } => Some(value.get_node_id()),
+            ZWaveEvent::Group { node_id, .. }
+            | ZWaveEvent::NodeNew { node_id, .. }
+            | ZWaveEvent::NodeAdded { node_id, .. }
+            | ZWaveEvent::NodeRemoved { node_id, .. }
+            | ZWaveEvent::NodeProtocolInfo { node_id, .. }
+            | ZWaveEvent::NodeNaming { node_id, .. }
+            | ZWaveEvent::NodeEvent { node_id, .. }
+            | ZWaveEvent::PollingDisabled { node_id, .. }
+            | ZWaveEvent::PollingEnabled { node_id, .. }
+            | ZWaveEvent::SceneEvent { node_id, .. }
+            | ZWaveEvent::CreateButton { node_id, .. }
+            | ZWaveEvent::DeleteButton { node_id, .. }
+            | ZWaveEvent::ButtonOn { node_id, .. }
+            | ZWaveEvent::ButtonOff { node_id, .. }
+            | ZWaveEvent::DriverReady { node_id, .. }
+            | ZWaveEvent::EssentialNodeQueriesComplete { node_id, .. }
+            | ZWaveEvent::NodeQueriesComplete { node_id, .. }
+            | ZWaveEvent::Notification { node_id, .. }
+            | ZWaveEvent::NodeReset { node_id, .. } => Some(node_id),
+            ZWaveEvent::UserAlert { node_id, .. } if node_id != 0 => Some(node_id),
+            ZWaveEvent::UserAlert { .. }
+            | ZWaveEvent::DriverFailed { .. }
+            | ZWaveEvent::DriverReset { .. }
+            | ZWaveEvent::DriverRemoved { .. }
+            | ZWaveEvent::AwakeNodesQueried { .. }
+            | ZWaveEvent::AllNodesQueriedSomeDead { .. }
+            | ZWaveEvent::AllNodesQueried { .. }
+            | ZWaveEvent::ControllerCommand { .. }
+            | ZWaveEvent::ManufacturerSpecificDBReady { .. } => None,
+        }
+    }
+
+    pub fn node(&self) -> Option<Node> {
+        self.node_id()
+            .map(|node_id| Node::from_id(self.home_id(), node_id))
+    }
+}
+
+/// Fails for `Unknown` notifications and ones missing what their type should carry.
+impl TryFrom<&Notification> for ZWaveEvent {
+    type Error = ParseError;
+
+    fn try_from(notification: &Notification) -> Result<Self, Self::Error> {
+        let home_id = notification.home_id;
+        let node_id = notification.node_id;
+        let value = || notification.value_id.clone().ok_or(ParseError::Missing("value_id"));
+        let byte = |expected: fn(&NotificationValue) -> Option<u8>| {
+            notification.value.as_ref().and_then(expected).ok_or(ParseError::Missing("value"))
+        };
+
+        let event = match notification.notification_type {
+            NotificationType::ValueAdded => ZWaveEvent::ValueAdded { value: value()? },
+            NotificationType::ValueRemoved => ZWaveEvent::ValueRemoved { value: value()? },
+            NotificationType::ValueChanged => ZWaveEvent::ValueChanged { value: value()? },
+            NotificationType::ValueRefreshed => ZWaveEvent::ValueRefreshed { value: value()? },
+            NotificationType::Group => ZWaveEvent::Group {
+                home_id,
+                node_id,
+                group_idx: byte(|value| match *value {
+                    NotificationValue::Group(group_idx) => Some(group_idx),
+                    _ => None,
+                })?,
+            },
+            NotificationType::NodeNew => ZWaveEvent::NodeNew { home_id, node_id },
+            NotificationType::NodeAdded => ZWaveEvent::NodeAdded { home_id, node_id },
+            NotificationType::NodeRemoved => ZWaveEvent::NodeRemoved { home_id, node_id },
+            NotificationType::NodeProtocolInfo => ZWaveEvent::NodeProtocolInfo { home_id, node_id },
+            NotificationType::NodeNaming => ZWaveEvent::NodeNaming { home_id, node_id },
+            NotificationType::NodeEvent => match notification.event {
+                Some(Event::Node(level)) => ZWaveEvent::NodeEvent { home_id, node_id, level },
+                _ => return Err(ParseError::Missing("event")),
+            },
+            NotificationType::PollingDisabled => ZWaveEvent::PollingDisabled { home_id, node_id },
+            NotificationType::PollingEnabled => ZWaveEvent::PollingEnabled { home_id, node_id },
+            NotificationType::SceneEvent => ZWaveEvent::SceneEvent {
+                home_id,
+                node_id,
+                scene_id: byte(|value| match *value {
+                    NotificationValue::Scene(scene_id) => Some(scene_id),
+                    _ => None,
+                })?,
+            },
+            NotificationType::CreateButton
+            | NotificationType::DeleteButton
+            | NotificationType::ButtonOn
+            | NotificationType::ButtonOff => {
+                let button_id = byte(|value| match *value {
+                    NotificationValue::Button(button_id) => Some(button_id),
+                    _ => None,
+                })?;
+                match notification.notification_type {
+                    NotificationType::CreateButton => {
+                        ZWaveEvent::CreateButton { home_id, node_id, button_id }
+                    }
+                    NotificationType::DeleteButton => {
+                        ZWaveEvent::DeleteButton { home_id, node_id, button_id }
+                    }
+                    NotificationType::ButtonOn => {
+                        ZWaveEvent::ButtonOn { home_id, node_id, button_id }
+                    }
+                    _ => ZWaveEvent::ButtonOff { home_id, node_id, button_id },
+                }
+            }
+            NotificationType::DriverReady => ZWaveEvent::DriverReady { home_id, node_id },
+            NotificationType::DriverFailed => ZWaveEvent::DriverFailed { home_id },
+            NotificationType::DriverReset => ZWaveEvent::DriverReset { home_id },
+            NotificationType::DriverRemoved => ZWaveEvent::DriverRemoved { home_id },
+            NotificationType::EssentialNodeQueriesComplete => {
+                ZWaveEvent::EssentialNodeQueriesComplete { home_id, node_id }
+            }
+            NotificationType::NodeQueriesComplete => {
+                ZWaveEvent::NodeQueriesComplete { home_id, node_id }
+            }
+            NotificationType::AwakeNodesQueried => ZWaveEvent::AwakeNodesQueried { home_id },
+            NotificationType::AllNodesQueriedSomeDead => {
+                ZWaveEvent::AllNodesQueriedSomeDead { home_id }
+            }
+            NotificationType::AllNodesQueried => ZWaveEvent::AllNodesQueried { home_id },
+            NotificationType::Notification => match notification.value {
+                Some(NotificationValue::Report(code)) => {
+                    ZWaveEvent::Notification { home_id, node_id, code }
+                }
+                _ => return Err(ParseError::Missing("value")),
+            },
+            NotificationType::ControllerCommand => match notification.event {
+                Some(Event::Controller(Some(state))) => ZWaveEvent::ControllerCommand {
+                    home_id,
+                    command: notification.command.unwrap_or(ControllerCommandType::None),
+                    state,
+                    error: match notification.value {
+                        Some(NotificationValue::ControllerError(error)) => error,
+                        _ => ControllerError::None,
+                    },
+                },
+                // a state this version of the library doesn't know
+                Some(Event::Controller(None)) => return Err(ParseError::Unknown),
+                _ => return Err(ParseError::Missing("event")),
+            },
+            NotificationType::NodeReset => ZWaveEvent::NodeReset { home_id, node_id },
+            NotificationType::UserAlerts => match notification.user_alert() {
+                Some(alert) => ZWaveEvent::UserAlert { home_id, node_id, alert },
+                None => return Err(ParseError::Missing("value")),
+            },
+            NotificationType::ManufacturerSpecificDBReady => {
+                ZWaveEvent::ManufacturerSpecificDBReady { home_id }
+            }
+            NotificationType::Unknown => return Err(ParseError::Unknown),
+        };
+        Ok(event)
+    }
+}
+
+impl Notification {
+    /// The notification as a `ZWaveEvent`, see its `TryFrom` implementation.
+    pub fn to_event(&self) -> Option<ZWaveEvent> {
+        ZWaveEvent::try_from(self).ok()
+    }
+}
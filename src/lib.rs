@@ -1,14 +1,29 @@
+extern crate futures;
 extern crate itertools;
 extern crate libc;
 #[macro_use]
 extern crate openzwave_sys as ffi;
 
+pub mod association;
+pub mod backend;
 pub mod controller;
+pub mod controller_command;
 pub mod error;
+pub mod event;
+pub mod logging;
 pub mod manager;
+pub mod network_state;
 pub mod node;
+pub mod node_config;
 pub mod notification;
+pub mod notification_stream;
 pub mod options;
+pub mod panic_safety;
+#[cfg(feature = "serde_serialization")]
+pub mod recording;
+pub mod router;
+pub mod statistics;
+pub mod topology;
 pub mod value_classes;
 
 pub use error::{Error, Result};
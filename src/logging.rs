@@ -0,0 +1,40 @@
+use ffi::log as extern_log;
+use libc::c_char;
+use std::ffi::CStr;
+use std::panic;
+
+/// Target of the records forwarded from OpenZWave's own log.
+pub const TARGET: &str = "openzwave::ozw";
+
+fn level_from_u8(level: u8) -> log::Level {
+    match level {
+        1 => log::Level::Error,
+        2 => log::Level::Warn,
+        3 => log::Level::Info,
+        4 => log::Level::Debug,
+        _ => log::Level::Trace,
+    }
+}
+
+extern "C" fn forward(level: u8, node_id: u8, message: *const c_char) {
+    // Called from OpenZWave's threads: a panicking logger must not unwind into C++.
+    let _ = panic::catch_unwind(|| {
+        let level = level_from_u8(level);
+        if !log::log_enabled!(target: TARGET, level) {
+            return;
+        }
+
+        let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+        let message = message.trim_end();
+        if node_id == 0 {
+            log::log!(target: TARGET, level, "{}", message);
+        } else {
+            log::log!(target: TARGET, level, "Node{:03}, {}", node_id, message);
+        }
+    });
+}
+
+// Must be called once OpenZWave's Log exists, i.e. after the C++ Manager is created.
+pub(crate) fn install() -> bool {
+    unsafe { extern_log::log_set_rust_logger(forward) }
+}
@@ -1,37 +1,78 @@
+use crate::backend::{self, ZWaveBackend};
 use crate::error::{Error, Result};
-use crate::notification::{ExternNotification, Notification};
+use crate::logging;
+use crate::notification::Notification;
+use crate::notification_stream::{NotificationStream, OverflowPolicy};
 use crate::options::Options;
+use crate::panic_safety::{PanicPolicy, PanicSafeWatcher, WatcherPanic};
 use crate::value_classes::value_id::ValueID;
 use ffi::manager as extern_manager;
-use ffi::utils as extern_utils;
 use ffi::utils::res_to_result;
-use libc::c_void;
-use std::ffi::CString;
+use std::ptr;
+use std::sync::Arc;
 
 pub struct Manager {
     pub ptr: *mut extern_manager::Manager,
+    backend: Arc<dyn ZWaveBackend>,
     #[allow(dead_code)]
-    options: Options, //< this is a false positive
-    watchers: Vec<Option<Box<WatcherWrapper>>>,
+    options: Option<Options>, //< this is a false positive
+    panic_policy: Arc<PanicPolicy>,
 }
 
 unsafe impl Send for Manager {}
 unsafe impl Sync for Manager {}
 
-pub trait NotificationWatcher: Sync {
+pub trait NotificationWatcher: Send + Sync {
     fn on_notification(&self, notification: &Notification);
 }
 
-struct WatcherWrapper {
-    watcher: Box<dyn NotificationWatcher>,
+impl<F: Fn(&Notification) + Send + Sync> NotificationWatcher for F {
+    fn on_notification(&self, notification: &Notification) {
+        self(notification)
+    }
+}
+
+/// Keeps a watcher registered. The watcher is removed when the guard is dropped.
+#[must_use = "the watcher is removed as soon as the guard is dropped"]
+pub struct WatcherGuard {
+    backend: Arc<dyn ZWaveBackend>,
+    watcher: Option<Arc<dyn NotificationWatcher>>,
+}
+
+impl WatcherGuard {
+    pub(crate) fn register(
+        backend: Arc<dyn ZWaveBackend>,
+        watcher: Arc<dyn NotificationWatcher>,
+    ) -> Result<WatcherGuard> {
+        if backend.add_watcher(watcher.clone()) {
+            Ok(WatcherGuard {
+                backend,
+                watcher: Some(watcher),
+            })
+        } else {
+            Err(Error::APIError(
+                "Could not add a watcher: it's already added",
+            ))
+        }
+    }
+
+    /// Removes the watcher now, reporting failures instead of ignoring them like `drop`.
+    pub fn remove(mut self) -> Result<()> {
+        match self.watcher.take() {
+            Some(watcher) => res_to_result(self.backend.remove_watcher(&watcher)).or(Err(
+                Error::APIError("Could not remove a watcher as it was not added or already removed"),
+            )),
+            None => Ok(()),
+        }
+    }
 }
 
-// watcher is actually a Box<WatcherWrapper>
-extern "C" fn watcher_cb(notification: *const ExternNotification, watcher: *const c_void) {
-    let watcher_wrapper: &WatcherWrapper = unsafe { &*(watcher as *const WatcherWrapper) };
-    let rust_notification = Notification::new(notification);
-    // log::info!("watcher_cb -> {:?}", rust_notification.notification_type);
-    watcher_wrapper.watcher.on_notification(&rust_notification);
+impl Drop for WatcherGuard {
+    fn drop(&mut self) {
+        if let Some(watcher) = self.watcher.take() {
+            self.backend.remove_watcher(&watcher);
+        }
+    }
 }
 
 impl Manager {
@@ -41,262 +82,205 @@ impl Manager {
         if external_manager.is_null() {
             Err(Error::OptionsAreNotLocked("Manager::create"))
         } else {
-            Ok(Manager {
+            let log_to_rust = options.is_logging_to_rust();
+            let manager = Manager {
                 ptr: external_manager,
-                options: options,
-                watchers: Vec::with_capacity(1),
-            })
+                backend: backend::openzwave::shared(),
+                options: Some(options),
+                panic_policy: Arc::new(PanicPolicy::new()),
+            };
+            if log_to_rust && !logging::install() {
+                return Err(Error::InitError("Could not route OpenZWave's log to the log crate"));
+            }
+            Ok(manager)
         }
     }
 
+    /// A manager that does not touch OpenZWave at all, e.g. on top of a `SimulatedNetwork`.
+    pub fn with_backend(backend: Arc<dyn ZWaveBackend>) -> Manager {
+        Manager {
+            ptr: ptr::null_mut(),
+            backend,
+            options: None,
+            panic_policy: Arc::new(PanicPolicy::new()),
+        }
+    }
+
+    pub fn backend(&self) -> &Arc<dyn ZWaveBackend> {
+        &self.backend
+    }
+
+    pub(crate) fn panic_policy(&self) -> &Arc<PanicPolicy> {
+        &self.panic_policy
+    }
+
     pub fn get_node_manufacturer_id(&self, home_id: u32, node_id: u8) -> String {
-        let raw = unsafe {
-            extern_manager::get_node_manufacturer_id(
-                self.ptr,
-                home_id,
-                node_id,
-                extern_utils::rust_string_creator,
-            )
-        };
-        extern_utils::recover_string(raw)
+        self.backend.get_node_manufacturer_id(home_id, node_id)
     }
 
     pub fn get_node_manufacturer_name(&self, home_id: u32, node_id: u8) -> String {
-        let raw = unsafe {
-            extern_manager::get_node_manufacturer_name(
-                self.ptr,
-                home_id,
-                node_id,
-                extern_utils::rust_string_creator,
-            )
-        };
-        extern_utils::recover_string(raw)
+        self.backend.get_node_manufacturer_name(home_id, node_id)
     }
 
     pub fn get_node_product_id(&self, home_id: u32, node_id: u8) -> String {
-        let raw = unsafe {
-            extern_manager::get_node_product_id(
-                self.ptr,
-                home_id,
-                node_id,
-                extern_utils::rust_string_creator,
-            )
-        };
-        extern_utils::recover_string(raw)
+        self.backend.get_node_product_id(home_id, node_id)
     }
 
     pub fn get_node_product_name(&self, home_id: u32, node_id: u8) -> String {
-        let raw = unsafe {
-            extern_manager::get_node_product_name(
-                self.ptr,
-                home_id,
-                node_id,
-                extern_utils::rust_string_creator,
-            )
-        };
-        extern_utils::recover_string(raw)
+        self.backend.get_node_product_name(home_id, node_id)
     }
 
     pub fn set_value_byte(&self, vid: &ValueID, value: u8) -> bool {
-        unsafe { extern_manager::manager_set_value_byte(self.ptr, vid.vid(), value) }
+        self.backend.set_value_byte(vid.vid(), value)
     }
 
     pub fn request_node_state(&self, home_id: u32, node_id: u8) -> bool {
-        unsafe { extern_manager::manager_request_node_state(self.ptr, home_id, node_id) }
+        self.backend.request_node_state(home_id, node_id)
     }
 
     pub fn request_all_config_params(&self, home_id: u32, node_id: u8) {
-        unsafe { extern_manager::manager_request_all_config_params(self.ptr, home_id, node_id) }
+        self.backend.request_all_config_params(home_id, node_id)
     }
 
     pub fn reset_controller(&self, home_id: u32) {
-        unsafe {
-            extern_manager::reset_controller(self.ptr, home_id);
-        }
+        self.backend.reset_controller(home_id);
     }
 
     pub fn soft_reset_controller(&self, home_id: u32) {
-        unsafe {
-            extern_manager::soft_reset_controller(self.ptr, home_id);
-        }
+        self.backend.soft_reset_controller(home_id);
     }
 
     pub fn cancel_controller_command(&self, home_id: u32) {
-        unsafe {
-            extern_manager::cancel_controller_command(self.ptr, home_id);
-        }
+        self.backend.cancel_controller_command(home_id);
     }
 
     pub fn add_node(&self, home_id: u32, secure: bool) -> Result<()> {
-        res_to_result(unsafe { extern_manager::manager_add_node(self.ptr, home_id, secure) })
+        res_to_result(self.backend.add_node(home_id, secure))
             .or(Err(Error::InvalidParameter("home_id", "Manager::add_node")))
     }
 
     pub fn remove_node(&self, home_id: u32) -> Result<()> {
-        res_to_result(unsafe { extern_manager::manager_remove_node(self.ptr, home_id) }).or(Err(
+        res_to_result(self.backend.remove_node(home_id)).or(Err(
             Error::InvalidParameter("home_id", "Manager::remove_node"),
         ))
     }
 
     pub fn test_network(&self, home_id: u32, count: u32) {
-        unsafe {
-            extern_manager::test_network(self.ptr, home_id, count);
-        }
+        self.backend.test_network(home_id, count);
     }
 
     pub fn test_network_node(&self, home_id: u32, node_id: u8, count: u32) {
-        unsafe {
-            extern_manager::test_network_node(self.ptr, home_id, node_id, count);
-        }
+        self.backend.test_network_node(home_id, node_id, count);
     }
 
     pub fn heal_network(&self, home_id: u32, do_rr: bool) {
-        unsafe {
-            extern_manager::heal_network(self.ptr, home_id, do_rr);
-        }
+        self.backend.heal_network(home_id, do_rr);
     }
 
     pub fn heal_network_node(&self, home_id: u32, node_id: u8, do_rr: bool) {
-        unsafe {
-            extern_manager::heal_network_node(self.ptr, home_id, node_id, do_rr);
-        }
+        self.backend.heal_network_node(home_id, node_id, do_rr);
     }
 
-    pub fn add_watcher<T: 'static + NotificationWatcher>(&mut self, watcher: T) -> Result<usize> {
-        let watcher_wrapper = Box::new(WatcherWrapper {
-            watcher: Box::new(watcher),
-        });
+    pub fn add_watcher<T: 'static + NotificationWatcher>(&self, watcher: T) -> Result<WatcherGuard> {
+        let watcher = PanicSafeWatcher::new(watcher, self.panic_policy.clone());
+        WatcherGuard::register(self.backend.clone(), Arc::new(watcher))
+    }
 
-        let watcher_ptr: *const c_void = &*watcher_wrapper as *const _ as *const c_void;
-        let api_res =
-            unsafe { extern_manager::manager_add_watcher(self.ptr, watcher_cb, watcher_ptr) };
+    /// Called for every panic swallowed from a watcher, instead of logging it.
+    pub fn set_watcher_panic_hook<F: 'static + Fn(&WatcherPanic) + Send + Sync>(&self, hook: F) {
+        self.panic_policy.set_hook(Some(Box::new(hook)));
+    }
 
-        if api_res {
-            let position = self.watchers.len();
-            self.watchers.push(Some(watcher_wrapper));
-            Ok(position)
-        } else {
-            Err(Error::APIError(
-                "Could not add a watcher: it's already added",
-            ))
-        }
+    /// Goes back to reporting watcher panics with `log::error!`.
+    pub fn clear_watcher_panic_hook(&self) {
+        self.panic_policy.set_hook(None);
     }
 
-    pub fn remove_watcher(&mut self, position: usize) -> Result<()> {
-        let wrapper = self.watchers[position].take();
+    /// Stop notifying a watcher once it panicked `panics` times. `None` never quarantines.
+    pub fn set_watcher_quarantine_after(&self, panics: Option<usize>) {
+        self.panic_policy.set_quarantine_after(panics);
+    }
 
-        if let Some(mut wrapper) = wrapper {
-            let result = self.remove_watcher_impl(&mut wrapper);
-            if result.is_err() {
-                // put the watcher back to the vec
-                self.watchers[position] = Some(wrapper);
-            }
-            result
-        } else {
-            Err(Error::APIError("Could not find the watcher to remove"))
-        }
+    /// Number of watcher panics swallowed so far.
+    pub fn swallowed_watcher_panics(&self) -> u64 {
+        self.panic_policy.swallowed()
     }
 
-    fn remove_watcher_impl(&self, wrapper: &mut WatcherWrapper) -> Result<()> {
-        let watcher_ptr: *mut c_void = wrapper as *mut _ as *mut c_void;
-        res_to_result(unsafe {
-            extern_manager::manager_remove_watcher(self.ptr, watcher_cb, watcher_ptr)
-        })
-        .or(Err(Error::APIError(
-            "Could not remove a watcher as it was not added or already removed",
-        )))
+    /// Notifications as an async stream, buffered up to `capacity` entries.
+    pub fn notifications(
+        &self,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<NotificationStream> {
+        NotificationStream::register(self.backend.clone(), capacity, policy)
     }
 
     pub fn add_driver(&mut self, device: &str) -> Result<()> {
-        let device = CString::new(device).unwrap();
-        res_to_result(unsafe {
-            extern_manager::manager_add_driver(
-                self.ptr,
-                device.as_ptr(),
-                &extern_manager::ControllerInterface::Serial,
-            )
-        })
+        res_to_result(self.backend.add_driver(device, extern_manager::ControllerInterface::Serial))
         .or(Err(Error::APIError(
             "Could not add the driver as it is already added",
         )))
     }
 
     pub fn add_usb_driver(&mut self) -> Result<()> {
-        let device = CString::new("HID Controller").unwrap();
-        res_to_result(unsafe {
-            extern_manager::manager_add_driver(
-                self.ptr,
-                device.as_ptr(),
-                &extern_manager::ControllerInterface::Hid,
-            )
-        })
+        res_to_result(self.backend.add_driver("HID Controller", extern_manager::ControllerInterface::Hid))
         .or(Err(Error::APIError(
             "Could not add the driver as it is already added",
         )))
     }
 
     pub fn remove_driver(&mut self, device: &str) -> Result<()> {
-        let device = CString::new(device).unwrap();
-        res_to_result(unsafe { extern_manager::manager_remove_driver(self.ptr, device.as_ptr()) })
+        res_to_result(self.backend.remove_driver(device))
             .or(Err(Error::APIError(
                 "Could not remove the driver as it was not added or already removed",
             )))
     }
 
     pub fn remove_usb_driver(&mut self) -> Result<()> {
-        let device = CString::new("HID Controller").unwrap();
-        res_to_result(unsafe { extern_manager::manager_remove_driver(self.ptr, device.as_ptr()) })
+        res_to_result(self.backend.remove_driver("HID Controller"))
             .or(Err(Error::APIError(
                 "Could not remove the driver as it was not added or already removed",
             )))
     }
 
     pub fn get_poll_interval(&self) -> i32 {
-        unsafe { extern_manager::manager_get_poll_interval(self.ptr) }
+        self.backend.get_poll_interval()
     }
 
     pub fn set_poll_interval(&self, interval_ms: i32, is_between_each_poll: bool) {
-        unsafe {
-            extern_manager::manager_set_poll_interval(self.ptr, interval_ms, is_between_each_poll)
-        }
+        self.backend.set_poll_interval(interval_ms, is_between_each_poll)
     }
 
     pub fn enable_poll_with_intensity(&self, vid: &ValueID, intensity: u8) -> bool {
-        unsafe {
-            extern_manager::manager_enable_poll_with_intensity(self.ptr, vid.vid(), intensity)
-        }
+        self.backend.enable_poll_with_intensity(vid.vid(), intensity)
     }
 
     pub fn enable_poll(&self, vid: &ValueID) -> bool {
-        unsafe { extern_manager::manager_enable_poll(self.ptr, vid.vid()) }
+        self.backend.enable_poll(vid.vid())
     }
 
     pub fn disable_poll(&self, vid: &ValueID) -> bool {
-        unsafe { extern_manager::manager_disable_poll(self.ptr, vid.vid()) }
+        self.backend.disable_poll(vid.vid())
     }
 
     pub fn is_polled(&self, vid: &ValueID) -> bool {
-        unsafe { extern_manager::manager_is_polled(self.ptr, vid.vid()) }
+        self.backend.is_polled(vid.vid())
     }
 
     pub fn set_poll_intensity(&self, vid: &ValueID, intensity: u8) {
-        unsafe { extern_manager::manager_set_poll_intensity(self.ptr, vid.vid(), intensity) }
+        self.backend.set_poll_intensity(vid.vid(), intensity)
     }
 
     pub fn get_poll_intensity(&self, vid: &ValueID) -> u8 {
-        unsafe { extern_manager::manager_get_poll_intensity(self.ptr, vid.vid()) }
+        self.backend.get_poll_intensity(vid.vid())
     }
 }
 
 impl Drop for Manager {
     fn drop(&mut self) {
-        let watchers: Vec<_> = self.watchers.drain(..).collect();
-        for watcher in watchers {
-            if let Some(mut watcher) = watcher {
-                self.remove_watcher_impl(&mut watcher).unwrap();
-            }
+        // Watchers still held by a WatcherGuard go away with the C++ manager.
+        if !self.ptr.is_null() {
+            unsafe { extern_manager::manager_destroy() }
         }
-
-        unsafe { extern_manager::manager_destroy() }
     }
 }
@@ -0,0 +1,327 @@
+use crate::error::Result;
+use crate::event::ZWaveEvent;
+use crate::manager::{Manager, NotificationWatcher, WatcherGuard};
+use crate::notification::Notification;
+use crate::value_classes::value_id::{ValueID, ValueIdKey};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+
+/// Last known state of a node, with its values keyed by their packed id.
+#[derive(Debug, Clone)]
+pub struct NodeState {
+    pub home_id: u32,
+    pub node_id: u8,
+    pub query_stage: String,
+    pub values: BTreeMap<u64, ValueID>,
+}
+
+impl NodeState {
+    fn new(home_id: u32, node_id: u8, query_stage: String) -> NodeState {
+        NodeState {
+            home_id,
+            node_id,
+            query_stage,
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+/// A modification of the `NetworkState`, delivered to subscribers once it's applied.
+#[derive(Debug, Clone)]
+pub enum StateChange {
+    ControllerAdded(u32),
+    ControllerRemoved(u32),
+    NodeAdded { home_id: u32, node_id: u8 },
+    NodeRemoved { home_id: u32, node_id: u8 },
+    QueryStageChanged { home_id: u32, node_id: u8, query_stage: String },
+    ValueAdded(ValueID),
+    ValueChanged(ValueID),
+    ValueRemoved(ValueID),
+}
+
+type Listener = Arc<dyn Fn(&StateChange) + Send + Sync>;
+
+type Controllers = BTreeMap<u32, BTreeMap<u8, NodeState>>;
+
+#[derive(Default)]
+struct Inner {
+    controllers: RwLock<Controllers>,
+    listeners: Mutex<Vec<(u64, Listener)>>,
+    next_listener: AtomicU64,
+}
+
+fn ensure_controller<'a>(
+    controllers: &'a mut Controllers,
+    home_id: u32,
+    changes: &mut Vec<StateChange>,
+) -> &'a mut BTreeMap<u8, NodeState> {
+    controllers.entry(home_id).or_insert_with(|| {
+        changes.push(StateChange::ControllerAdded(home_id));
+        BTreeMap::new()
+    })
+}
+
+fn ensure_node<'a>(
+    controllers: &'a mut Controllers,
+    home_id: u32,
+    node_id: u8,
+    query_stage: &Option<String>,
+    changes: &mut Vec<StateChange>,
+) -> &'a mut NodeState {
+    ensure_controller(controllers, home_id, changes)
+        .entry(node_id)
+        .or_insert_with(|| {
+            changes.push(StateChange::NodeAdded { home_id, node_id });
+            NodeState::new(home_id, node_id, query_stage.clone().unwrap_or_default())
+        })
+}
+
+impl Inner {
+    fn apply(&self, notification: &Notification) -> Vec<StateChange> {
+        let event = match notification.to_event() {
+            Some(event) => event,
+            None => return Vec::new(),
+        };
+        let home_id = event.home_id();
+        let mut changes = Vec::new();
+
+        // Asked before locking, OpenZWave may take a while to answer.
+        let query_stage = match event {
+            ZWaveEvent::NodeNew { .. }
+            | ZWaveEvent::NodeAdded { .. }
+            | ZWaveEvent::ValueAdded { .. }
+            | ZWaveEvent::ValueChanged { .. }
+            | ZWaveEvent::ValueRefreshed { .. }
+            | ZWaveEvent::NodeProtocolInfo { .. }
+            | ZWaveEvent::NodeNaming { .. }
+            | ZWaveEvent::EssentialNodeQueriesComplete { .. }
+            | ZWaveEvent::NodeQueriesComplete { .. } => {
+                event.node().map(|node| node.get_query_stage())
+            }
+            _ => None,
+        };
+        // So is the value, read before it's stored: the backend may have moved on when it's
+        // queried.
+        if let ZWaveEvent::ValueAdded { ref value }
+        | ZWaveEvent::ValueChanged { ref value }
+        | ZWaveEvent::ValueRefreshed { ref value } = event
+        {
+            value.snapshot();
+        }
+
+        let mut controllers = self.controllers.write().unwrap();
+        match event {
+            ZWaveEvent::DriverReady { .. } => {
+                ensure_controller(&mut controllers, home_id, &mut changes);
+            }
+            ZWaveEvent::DriverReset { .. } => {
+                if let Some(nodes) = controllers.get_mut(&home_id) {
+                    for (node_id, node) in std::mem::take(nodes) {
+                        changes.extend(node.values.into_values().map(StateChange::ValueRemoved));
+                        changes.push(StateChange::NodeRemoved { home_id, node_id });
+                    }
+                }
+            }
+            ZWaveEvent::DriverRemoved { .. } | ZWaveEvent::DriverFailed { .. } => {
+                changes.extend(
+                    controllers
+                        .remove(&home_id)
+                        .map(|_| StateChange::ControllerRemoved(home_id)),
+                );
+            }
+            ZWaveEvent::NodeNew { node_id, .. } | ZWaveEvent::NodeAdded { node_id, .. } => {
+                ensure_node(&mut controllers, home_id, node_id, &query_stage, &mut changes);
+            }
+            ZWaveEvent::NodeRemoved { node_id, .. } => {
+                let removed = controllers
+                    .get_mut(&home_id)
+                    .and_then(|nodes| nodes.remove(&node_id));
+                if let Some(node) = removed {
+                    changes.extend(node.values.into_values().map(StateChange::ValueRemoved));
+                    changes.push(StateChange::NodeRemoved { home_id, node_id });
+                }
+            }
+            ZWaveEvent::ValueAdded { ref value }
+            | ZWaveEvent::ValueChanged { ref value }
+            | ZWaveEvent::ValueRefreshed { ref value } => {
+                let node_id = value.get_node_id();
+                let node = ensure_node(&mut controllers, home_id, node_id, &query_stage, &mut changes);
+                let known = node.values.insert(value.id(), value.clone()).is_some();
+                if !known {
+                    changes.push(StateChange::ValueAdded(value.clone()));
+                } else if !matches!(event, ZWaveEvent::ValueAdded { .. }) {
+                    changes.push(StateChange::ValueChanged(value.clone()));
+                }
+            }
+            ZWaveEvent::ValueRemoved { value } => {
+                let removed = controllers
+                    .get_mut(&home_id)
+                    .and_then(|nodes| nodes.get_mut(&value.get_node_id()))
+                    .and_then(|node| node.values.remove(&value.id()));
+                if let Some(value_id) = removed {
+                    changes.push(StateChange::ValueRemoved(value_id));
+                }
+            }
+            ZWaveEvent::NodeProtocolInfo { node_id, .. }
+            | ZWaveEvent::NodeNaming { node_id, .. }
+            | ZWaveEvent::EssentialNodeQueriesComplete { node_id, .. }
+            | ZWaveEvent::NodeQueriesComplete { node_id, .. } => {
+                let node = ensure_node(&mut controllers, home_id, node_id, &query_stage, &mut changes);
+                if let Some(query_stage) = query_stage {
+                    if node.query_stage != query_stage {
+                        node.query_stage = query_stage.clone();
+                        changes.push(StateChange::QueryStageChanged {
+                            home_id,
+                            node_id,
+                            query_stage,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        changes
+    }
+
+    fn publish(&self, changes: &[StateChange]) {
+        if changes.is_empty() {
+            return;
+        }
+        // Cloned out so listeners can query the state or subscribe.
+        let listeners: Vec<Listener> = self
+            .listeners
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for change in changes {
+            for listener in &listeners {
+                listener(change);
+            }
+        }
+    }
+}
+
+struct StateWatcher {
+    inner: Arc<Inner>,
+}
+
+impl NotificationWatcher for StateWatcher {
+    fn on_notification(&self, notification: &Notification) {
+        let changes = self.inner.apply(notification);
+        self.inner.publish(&changes);
+    }
+}
+
+/// Keeps a `NetworkState` subscriber registered until it's dropped.
+#[must_use = "the subscriber is removed as soon as the subscription is dropped"]
+pub struct Subscription {
+    inner: Weak<Inner>,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.listeners.lock().unwrap().retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// Controllers, nodes and values of the network, maintained from the notifications.
+///
+/// Only what is notified after its creation is known, so create it before adding drivers.
+pub struct NetworkState {
+    inner: Arc<Inner>,
+    _guard: WatcherGuard,
+}
+
+impl NetworkState {
+    pub fn new(manager: &Manager) -> Result<NetworkState> {
+        let inner = Arc::new(Inner::default());
+        let guard = manager.add_watcher(StateWatcher {
+            inner: inner.clone(),
+        })?;
+        Ok(NetworkState {
+            inner,
+            _guard: guard,
+        })
+    }
+
+    pub fn controllers(&self) -> Vec<u32> {
+        self.inner.controllers.read().unwrap().keys().cloned().collect()
+    }
+
+    pub fn nodes(&self, home_id: u32) -> Vec<u8> {
+        self.inner
+            .controllers
+            .read()
+            .unwrap()
+            .get(&home_id)
+            .map_or_else(Vec::new, |nodes| nodes.keys().cloned().collect())
+    }
+
+    pub fn node(&self, home_id: u32, node_id: u8) -> Option<NodeState> {
+        self.with_node(home_id, node_id, |node| node.clone())
+    }
+
+    pub fn query_stage(&self, home_id: u32, node_id: u8) -> Option<String> {
+        self.with_node(home_id, node_id, |node| node.query_stage.clone())
+    }
+
+    pub fn values(&self, home_id: u32, node_id: u8) -> Vec<ValueID> {
+        self.with_node(home_id, node_id, |node| node.values.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn value(
+        &self,
+        home_id: u32,
+        node_id: u8,
+        command_class_id: u8,
+        instance: u8,
+        index: u16,
+    ) -> Option<ValueID> {
+        let key = ValueIdKey::new(home_id, node_id, command_class_id)
+            .instance(instance)
+            .index(index);
+        self.value_by_key(&key)
+    }
+
+    /// The value a key stands for, whether the key knows the genre and type or not.
+    pub fn value_by_key(&self, key: &ValueIdKey) -> Option<ValueID> {
+        self.with_node(key.home_id, key.node_id, |node| {
+            node.values
+                .values()
+                .find(|value_id| value_id.key() == *key)
+                .cloned()
+        })
+        .flatten()
+    }
+
+    /// Calls `listener` after every change, from the thread delivering the notifications.
+    pub fn subscribe<F: 'static + Fn(&StateChange) + Send + Sync>(&self, listener: F) -> Subscription {
+        let id = self.inner.next_listener.fetch_add(1, Ordering::Relaxed);
+        self.inner
+            .listeners
+            .lock()
+            .unwrap()
+            .push((id, Arc::new(listener)));
+        Subscription {
+            inner: Arc::downgrade(&self.inner),
+            id,
+        }
+    }
+
+    fn with_node<R, F: FnOnce(&NodeState) -> R>(&self, home_id: u32, node_id: u8, f: F) -> Option<R> {
+        self.inner
+            .controllers
+            .read()
+            .unwrap()
+            .get(&home_id)
+            .and_then(|nodes| nodes.get(&node_id))
+            .map(f)
+    }
+}
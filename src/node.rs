@@ -1,9 +1,11 @@
-use std::{ fmt, ptr };
+use std::fmt;
+use std::sync::Arc;
 
-use ffi::manager as extern_manager;
-use ffi::utils::{ rust_string_creator, rust_vec_creator, recover_string, recover_vec };
-use libc::c_char;
+use crate::association::Associations;
+use crate::backend::{ self, ZWaveBackend };
 use crate::controller::Controller;
+use crate::node_config::NodeConfig;
+use crate::statistics::NodeStatistics;
 use itertools::free::join;
 
 // Mapping comes from https://github.com/OpenZWave/open-zwave-control-panel/blob/master/zwavelib.cpp
@@ -37,10 +39,7 @@ pub struct Node {
 macro_rules! node_getters {
     ( $($impl_name: ident as $name: ident -> $t: ty),+ ) => {
         $(pub fn $name(&self) -> $t {
-            let manager_ptr = unsafe { extern_manager::get() };
-            unsafe {
-                extern_manager::$impl_name(manager_ptr, self.home_id, self.node_id)
-            }
+            self.backend().$impl_name(self.home_id, self.node_id)
         })*
     }
 }
@@ -48,11 +47,7 @@ macro_rules! node_getters {
 macro_rules! node_string_getters {
     ( $($impl_name: ident as $name: ident),+ ) => {
         $(pub fn $name(&self) -> String {
-            let manager_ptr = unsafe { extern_manager::get() };
-            let result = unsafe {
-                extern_manager::$impl_name(manager_ptr, self.home_id, self.node_id, rust_string_creator)
-            };
-            recover_string(result)
+            self.backend().$impl_name(self.home_id, self.node_id)
         })*
     }
 }
@@ -62,6 +57,10 @@ impl Node {
         Node { home_id: home_id, node_id: node_id }
     }
 
+    fn backend(&self) -> Arc<dyn ZWaveBackend> {
+        backend::get(self.home_id)
+    }
+
     node_getters! {
         is_node_listening_device as is_listening_device -> bool,
         is_node_frequent_listening_device as is_frequent_listening_device -> bool,
@@ -110,21 +109,11 @@ impl Node {
     }
 
     pub fn get_basic(&self) -> Option<NodeBasic> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        NodeBasic::from_u8(unsafe { extern_manager::get_node_basic(manager_ptr, self.home_id, self.node_id) })
+        NodeBasic::from_u8(self.backend().get_node_basic(self.home_id, self.node_id))
     }
 
     pub fn get_neighbors(&self) -> Option<Vec<Node>> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let result_ptr = unsafe {
-            extern_manager::get_node_neighbors(manager_ptr, self.home_id, self.node_id, rust_vec_creator::<u8>)
-        } as *mut Vec<u8>;
-
-        if result_ptr.is_null() {
-            return None;
-        }
-
-        let vec_neighbors_id = recover_vec(result_ptr);
+        let vec_neighbors_id = self.backend().get_node_neighbors(self.home_id, self.node_id)?;
         let vec_neighbors = vec_neighbors_id.into_iter()
             .map(|id| Node { home_id: self.home_id, node_id: id })
             .collect();
@@ -132,25 +121,19 @@ impl Node {
     }
 
     pub fn get_class_information(&self, command_class_id: u8) -> Option<(String, u8)> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let mut class_name: *mut c_char = ptr::null_mut();
-        let mut class_version: u8 = 0;
-
-        let has_class = unsafe {
-            extern_manager::get_node_class_information(
-                manager_ptr, self.home_id, self.node_id,
-                command_class_id, &mut class_name, &mut class_version,
-                rust_string_creator
-            )
-        };
+        self.backend().get_node_class_information(self.home_id, self.node_id, command_class_id)
+    }
 
-        if !has_class {
-            return None;
-        }
+    pub fn get_statistics(&self) -> NodeStatistics {
+        self.backend().get_node_statistics(self.home_id, self.node_id)
+    }
 
-        let class_name = recover_string(class_name);
+    pub fn config(&self) -> NodeConfig {
+        NodeConfig::new(*self)
+    }
 
-        Some((class_name, class_version))
+    pub fn associations(&self) -> Associations {
+        Associations::new(*self)
     }
 
     pub fn simple_debug(&self) -> String {
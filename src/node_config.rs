@@ -0,0 +1,96 @@
+use crate::backend;
+use crate::error::{Error, GetSetError, Result};
+use crate::network_state::NetworkState;
+use crate::node::Node;
+use crate::value_classes::confirm::{confirm, reported_value};
+use crate::value_classes::value_id::{CommandClass, ValueGenre, ValueID};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::time::Duration;
+
+/// Configuration parameters of a node, see `Node::config`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct NodeConfig {
+    node: Node,
+}
+
+// The parameter number of a Configuration command class value
+fn config_param(value_id: &ValueID) -> Option<u8> {
+    if value_id.get_genre() != Some(ValueGenre::Config)
+        || value_id.get_command_class() != Some(CommandClass::Configuration)
+    {
+        return None;
+    }
+    value_id.get_index().try_into().ok()
+}
+
+impl NodeConfig {
+    pub(crate) fn new(node: Node) -> NodeConfig {
+        NodeConfig { node }
+    }
+
+    /// The known parameters of the node keyed by their number.
+    ///
+    /// OpenZWave can't list values, they're taken from a `NetworkState`.
+    pub fn params(&self, state: &NetworkState) -> BTreeMap<u8, ValueID> {
+        state
+            .values(self.node.get_home_id(), self.node.get_id())
+            .into_iter()
+            .filter_map(|value_id| config_param(&value_id).map(|param| (param, value_id)))
+            .collect()
+    }
+
+    pub fn param(&self, state: &NetworkState, param: u8) -> Option<ValueID> {
+        self.params(state).remove(&param)
+    }
+
+    /// Asks the device for the current value of `param`, which is then notified.
+    pub fn request(&self, param: u8) {
+        backend::get(self.node.get_home_id()).request_config_param(
+            self.node.get_home_id(),
+            self.node.get_id(),
+            param,
+        )
+    }
+
+    pub fn request_all(&self) {
+        backend::get(self.node.get_home_id())
+            .request_all_config_params(self.node.get_home_id(), self.node.get_id())
+    }
+
+    /// Sets `param` to `value`, sent on `size` bytes (1, 2 or 4), and waits until the device
+    /// reports the parameter back.
+    ///
+    /// `value` may be given signed or unsigned, devices differ on how they read it. Resolves
+    /// with the reported value, see `ValueID::set_and_confirm` for the failures.
+    pub async fn set(&self, param: u8, value: i32, size: u8, timeout: Duration) -> Result<ValueID> {
+        let (min, max) = match size {
+            1 => (i8::MIN as i32, u8::MAX as i32),
+            2 => (i16::MIN as i32, u16::MAX as i32),
+            4 => (i32::MIN, i32::MAX),
+            _ => return Err(Error::InvalidParameter("size", "NodeConfig::set")),
+        };
+        if value < min || value > max {
+            return Err(Error::InvalidParameter("value", "NodeConfig::set"));
+        }
+
+        let home_id = self.node.get_home_id();
+        let node_id = self.node.get_id();
+        confirm(
+            home_id,
+            node_id,
+            reported_value(move |value_id| {
+                value_id.get_node_id() == node_id && config_param(value_id) == Some(param)
+            }),
+            || {
+                if backend::get(home_id).set_config_param(home_id, node_id, param, value, size) {
+                    Ok(())
+                } else {
+                    Err(Error::SetError(GetSetError::APIError("SetConfigParam")))
+                }
+            },
+            timeout,
+        )
+        .await
+    }
+}
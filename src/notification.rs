@@ -1,7 +1,9 @@
-pub use ffi::notification::{ ControllerState, ControllerError, NotificationType, NotificationCode, Notification as ExternNotification };
+pub use ffi::notification::{ ControllerState, ControllerError, ControllerCommandType, NotificationType, NotificationCode, UserAlertType, Notification as ExternNotification };
 use ffi::notification as extern_notification;
+use ffi::utils::{ recover_string, rust_string_creator };
 use crate::value_classes::value_id::ValueID;
 use crate::controller::Controller;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone)]
 pub enum NotificationValue {
@@ -9,7 +11,43 @@ pub enum NotificationValue {
     Button(u8),
     Scene(u8),
     State(ControllerState),
+    ControllerError(ControllerError),
     Report(NotificationCode),
+    UserAlert(UserAlert),
+}
+
+/// What a `UserAlerts` notification warns about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub enum UserAlert {
+    ConfigOutOfDate,
+    MFSOutOfDate,
+    ConfigFileDownloadFailed,
+    DNSError,
+    NodeReloadRequired,
+    UnsupportedController,
+    /// The device is busy and asks to retry after that many seconds
+    ApplicationStatusRetry(u8),
+    ApplicationStatusQueued,
+    ApplicationStatusRejected,
+}
+
+impl UserAlert {
+    /// `retry` is only used for `ApplicationStatusRetry`.
+    pub fn from_type(alert_type: UserAlertType, retry: u8) -> Option<UserAlert> {
+        match alert_type {
+            UserAlertType::None => None,
+            UserAlertType::ConfigOutOfDate => Some(UserAlert::ConfigOutOfDate),
+            UserAlertType::MFSOutOfDate => Some(UserAlert::MFSOutOfDate),
+            UserAlertType::ConfigFileDownloadFailed => Some(UserAlert::ConfigFileDownloadFailed),
+            UserAlertType::DNSError => Some(UserAlert::DNSError),
+            UserAlertType::NodeReloadRequired => Some(UserAlert::NodeReloadRequired),
+            UserAlertType::UnsupportedController => Some(UserAlert::UnsupportedController),
+            UserAlertType::ApplicationStatusRetry => Some(UserAlert::ApplicationStatusRetry(retry)),
+            UserAlertType::ApplicationStatusQueued => Some(UserAlert::ApplicationStatusQueued),
+            UserAlertType::ApplicationStatusRejected => Some(UserAlert::ApplicationStatusRejected),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -27,15 +65,39 @@ pub struct Notification {
     pub value_id: Option<ValueID>,
     pub value: Option<NotificationValue>,
     pub event: Option<Event>,
+    /// The controller command a `ControllerCommand` notification is about
+    pub command: Option<ControllerCommandType>,
+    /// The raw byte payload: group index, button or scene id, notification code or error
+    pub byte: u8,
+    /// OpenZWave's own rendering of the notification, empty unless
+    /// `set_notification_descriptions` is on
+    pub description: String,
 }
 
+static DESCRIPTIONS: AtomicBool = AtomicBool::new(false);
+
+/// Fills `Notification::description`, at the cost of one more call into OpenZWave and a
+/// string for every notification, wanted or not. Off by default.
+pub fn set_notification_descriptions(enabled: bool) {
+    DESCRIPTIONS.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn descriptions_enabled() -> bool {
+    DESCRIPTIONS.load(Ordering::Relaxed)
+}
+
+// Node notifications carry a ValueID holding only the node id, while real values always
+// have a command class. m_id1 can't be used for this since it's 0 for every index 0 value.
 #[inline(always)]
 fn is_valid_value_id(value: u64) -> bool {
-    value >> 32 > 0
+    value & 0x003fc000 != 0
 }
 
 impl Notification {
-    pub fn new(ptr: *const ExternNotification) -> Self {
+    /// # Safety
+    ///
+    /// `ptr` must point to the OpenZWave notification being delivered to a watcher.
+    pub unsafe fn new(ptr: *const ExternNotification) -> Self {
 
         let home_id = unsafe { extern_notification::notification_get_home_id(ptr) };
         let node_id = unsafe { extern_notification::notification_get_node_id(ptr) };
@@ -52,10 +114,12 @@ impl Notification {
                 0 => None,
                 _ => unsafe {
                         let ozw_vid = extern_notification::notification_get_value_id(ptr);
-                            if is_valid_value_id(ozw_vid) && notification_type != NotificationType::ValueRemoved {
-                                Some(ValueID::from_packed_id(home_id, ozw_vid))
-                            } else {
+                            if !is_valid_value_id(ozw_vid) {
                                 None
+                            } else if notification_type == NotificationType::ValueRemoved {
+                                Some(ValueID::removed(home_id, ozw_vid))
+                            } else {
+                                Some(ValueID::from_packed_id(home_id, ozw_vid))
                             }
                     }
             },
@@ -63,20 +127,21 @@ impl Notification {
                 NotificationType::Group => Some(NotificationValue::Group(unsafe { extern_notification::notification_get_group_idx(ptr) })),
                 NotificationType::CreateButton | NotificationType::DeleteButton | NotificationType::ButtonOn | NotificationType::ButtonOff => Some(NotificationValue::Button(unsafe { extern_notification::notification_get_button_id(ptr) })),
                 NotificationType::SceneEvent => Some(NotificationValue::Scene(unsafe { extern_notification::notification_get_scene_id(ptr) })),
-                // TODO: This is wrong? m_command instead of m_byte used in openzwave-sys/open-zwave/cpp/src/Notification.h
+                // The state is the event, m_notification holds the ControllerError
                 NotificationType::ControllerCommand => {
-                    if let Some(state) = ControllerState::from_u8(unsafe { extern_notification::notification_get_notification(ptr) }) {
-                        Some(NotificationValue::State(state))
-                    } else {
-                        None
-                    }
+                    ControllerError::from_u8(unsafe { extern_notification::notification_get_notification(ptr) })
+                        .map(NotificationValue::ControllerError)
                 },
                 NotificationType::Notification => {
-                    if let Some(code) = NotificationCode::from_u8(unsafe { extern_notification::notification_get_notification(ptr) }) {
-                        Some(NotificationValue::Report(code))
-                    } else {
-                        None
-                    }
+                    NotificationCode::from_u8(unsafe { extern_notification::notification_get_notification(ptr) })
+                        .map(NotificationValue::Report)
+                },
+                NotificationType::UserAlerts => {
+                    let alert_type = unsafe { extern_notification::notification_get_user_alert_type(ptr) };
+                    let retry = unsafe { extern_notification::notification_get_retry(ptr) };
+                    UserAlertType::from_u8(alert_type)
+                        .and_then(|alert_type| UserAlert::from_type(alert_type, retry))
+                        .map(NotificationValue::UserAlert)
                 },
                 _ => None,
             },
@@ -97,6 +162,38 @@ impl Notification {
                 },
                 _ => None
             },
+            command: match notification_type {
+                NotificationType::ControllerCommand => {
+                    ControllerCommandType::from_u8(unsafe { extern_notification::notification_get_command(ptr) })
+                },
+                _ => None,
+            },
+            byte: unsafe { extern_notification::notification_get_byte(ptr) },
+            description: if descriptions_enabled() {
+                recover_string(unsafe { extern_notification::notification_get_as_string(ptr, rust_string_creator) as *mut _ })
+            } else {
+                String::new()
+            },
+        }
+    }
+
+    /// The command, state and error of a `ControllerCommand` notification.
+    pub fn controller_command(&self) -> Option<(ControllerCommandType, ControllerState, ControllerError)> {
+        let state = match self.event {
+            Some(Event::Controller(Some(state))) => state,
+            _ => return None,
+        };
+        let error = match self.value {
+            Some(NotificationValue::ControllerError(error)) => error,
+            _ => ControllerError::None,
+        };
+        self.command.map(|command| (command, state, error))
+    }
+
+    pub fn user_alert(&self) -> Option<UserAlert> {
+        match self.value {
+            Some(NotificationValue::UserAlert(alert)) => Some(alert),
+            _ => None,
         }
     }
 
@@ -0,0 +1,155 @@
+use crate::backend::ZWaveBackend;
+use crate::error::Result;
+use crate::manager::{NotificationWatcher, WatcherGuard};
+use crate::notification::Notification;
+use futures::stream::Stream;
+use futures::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// What happens when a notification arrives while the stream buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Hold OpenZWave's driver thread until the consumer catches up.
+    /// Never use this when the stream is consumed on the thread emitting notifications.
+    Block,
+    /// Discard the oldest buffered notification to make room.
+    DropOldest,
+    /// Discard the incoming notification.
+    DropNewest,
+}
+
+struct Buffer {
+    queue: VecDeque<Notification>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+struct Shared {
+    buffer: Mutex<Buffer>,
+    not_full: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+    lagged: AtomicU64,
+}
+
+struct StreamWatcher {
+    shared: Arc<Shared>,
+}
+
+impl NotificationWatcher for StreamWatcher {
+    fn on_notification(&self, notification: &Notification) {
+        let shared = &self.shared;
+        let mut buffer = shared.buffer.lock().unwrap();
+
+        while buffer.queue.len() >= shared.capacity && !buffer.closed {
+            match shared.policy {
+                OverflowPolicy::Block => buffer = shared.not_full.wait(buffer).unwrap(),
+                OverflowPolicy::DropOldest => {
+                    buffer.queue.pop_front();
+                    shared.lagged.fetch_add(1, Ordering::Relaxed);
+                }
+                OverflowPolicy::DropNewest => {
+                    shared.lagged.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+        if buffer.closed {
+            return;
+        }
+
+        // Queued as notified, not as the backend holds it when the consumer gets to it. Only
+        // read now, dropped notifications not being worth a call into OpenZWave.
+        let notification = notification.clone();
+        if let Some(ref value_id) = notification.value_id {
+            value_id.snapshot();
+        }
+        buffer.queue.push_back(notification);
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Notifications of one `Manager`, as an async `Stream`.
+///
+/// Created by `Manager::notifications`. The underlying watcher is removed when the stream
+/// is dropped.
+pub struct NotificationStream {
+    shared: Arc<Shared>,
+    _guard: WatcherGuard,
+}
+
+impl NotificationStream {
+    pub(crate) fn register(
+        backend: Arc<dyn ZWaveBackend>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<NotificationStream> {
+        let shared = Arc::new(Shared {
+            buffer: Mutex::new(Buffer {
+                queue: VecDeque::with_capacity(capacity),
+                waker: None,
+                closed: false,
+            }),
+            not_full: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            lagged: AtomicU64::new(0),
+        });
+        let watcher = Arc::new(StreamWatcher {
+            shared: shared.clone(),
+        });
+
+        Ok(NotificationStream {
+            shared,
+            _guard: WatcherGuard::register(backend, watcher)?,
+        })
+    }
+
+    /// Number of notifications dropped so far because the buffer was full.
+    pub fn lagged(&self) -> u64 {
+        self.shared.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Number of notifications waiting to be consumed.
+    pub fn len(&self) -> usize {
+        self.shared.buffer.lock().unwrap().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Stream for NotificationStream {
+    type Item = Notification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Notification>> {
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        match buffer.queue.pop_front() {
+            Some(notification) => {
+                self.shared.not_full.notify_one();
+                Poll::Ready(Some(notification))
+            }
+            None if buffer.closed => Poll::Ready(None),
+            None => {
+                buffer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for NotificationStream {
+    fn drop(&mut self) {
+        // Release a driver thread blocked in `on_notification` before the guard unregisters.
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        buffer.closed = true;
+        buffer.queue.clear();
+        self.shared.not_full.notify_all();
+    }
+}
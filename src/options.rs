@@ -3,8 +3,11 @@ use ffi::get_default_config_path;
 use std::ffi::CString;
 use ffi::utils::res_to_result;
 use crate::error::{ Result, Error };
+#[cfg(feature = "config_file")]
+use std::path::Path;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "config_file", derive(serde::Deserialize))]
 pub enum LogLevel {
     None = 0,
     All = 1,
@@ -27,7 +30,8 @@ impl From<LogLevel> for i32 {
 
 #[derive(Debug)]
 pub struct Options {
-    ptr: *mut extern_options::Options
+    ptr: *mut extern_options::Options,
+    log_to_rust: bool,
 }
 
 unsafe impl Send for Options {}
@@ -49,7 +53,7 @@ impl Options {
         if external_options.is_null() {
             Err(Error::InitError("Could not create Options, see Openzwave logs for more information"))
         } else {
-            Ok(Options { ptr: external_options })
+            Ok(Options { ptr: external_options, log_to_rust: false })
         }
     }
 
@@ -58,7 +62,7 @@ impl Options {
         if external_options.is_null() {
             None
         } else {
-            Some(Options { ptr: external_options })
+            Some(Options { ptr: external_options, log_to_rust: false })
         }
     }
 
@@ -84,6 +88,28 @@ impl Options {
         ).or(Err(Error::OptionsAreLocked("Options::add_option_string")))
     }
 
+    /// Sends OpenZWave's log through the `log` crate, with the target `logging::TARGET`,
+    /// instead of its own file and console output. Only the messages up to SaveLogLevel
+    /// are sent.
+    ///
+    /// The log file isn't written, or even created, so the Logging and AppendLogFile options
+    /// are overridden.
+    pub fn log_to_rust(&mut self, enabled: bool) -> Result<()> {
+        if enabled {
+            // Turned on again once the Rust logger replaces the file one
+            self.add_option_bool("Logging", false)?;
+            // Otherwise the file is truncated as the Manager is created
+            self.add_option_bool("AppendLogFile", true)?;
+            self.add_option_bool("ConsoleOutput", false)?;
+        }
+        self.log_to_rust = enabled;
+        Ok(())
+    }
+
+    pub fn is_logging_to_rust(&self) -> bool {
+        self.log_to_rust
+    }
+
     pub fn lock(&mut self) -> Result<()> {
         res_to_result(unsafe { extern_options::options_lock(self.ptr) })
             .or(Err(Error::OptionsAreLocked("Options::lock")))
@@ -95,3 +121,285 @@ impl Drop for Options {
         res_to_result(unsafe { extern_options::options_destroy() }).unwrap();
     }
 }
+
+/// Which command classes are sent encrypted to secure nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "config_file", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config_file", serde(rename_all = "UPPERCASE"))]
+pub enum SecurityStrategy {
+    Essential,
+    Supported,
+    Custom, //< the ones listed in CustomSecuredCC
+}
+
+/// When to reload a node whose device config file was updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "config_file", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config_file", serde(rename_all = "UPPERCASE"))]
+pub enum ReloadAfterUpdate {
+    Never,
+    Immediate,
+    Awake,
+}
+
+trait OptionValue {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()>;
+}
+
+impl OptionValue for bool {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        options.add_option_bool(name, *self)
+    }
+}
+
+impl OptionValue for i32 {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        options.add_option_int(name, *self)
+    }
+}
+
+impl OptionValue for String {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        options.add_option_string(name, self, false)
+    }
+}
+
+impl OptionValue for LogLevel {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        options.add_option_int(name, (*self).into())
+    }
+}
+
+// NetworkKey and CustomSecuredCC are both lists of bytes: "0x01, 0x02, ..."
+impl OptionValue for Vec<u8> {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        let bytes: Vec<String> = self.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+        options.add_option_string(name, &bytes.join(", "), false)
+    }
+}
+
+impl OptionValue for [u8; 16] {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        self.to_vec().add_to(options, name)
+    }
+}
+
+impl OptionValue for SecurityStrategy {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        let value = match *self {
+            SecurityStrategy::Essential => "ESSENTIAL",
+            SecurityStrategy::Supported => "SUPPORTED",
+            SecurityStrategy::Custom => "CUSTOM",
+        };
+        options.add_option_string(name, value, false)
+    }
+}
+
+impl OptionValue for ReloadAfterUpdate {
+    fn add_to(&self, options: &mut Options, name: &str) -> Result<()> {
+        let value = match *self {
+            ReloadAfterUpdate::Never => "NEVER",
+            ReloadAfterUpdate::Immediate => "IMMEDIATE",
+            ReloadAfterUpdate::Awake => "AWAKE",
+        };
+        options.add_option_string(name, value, false)
+    }
+}
+
+// Declares the builder fields with their OpenZWave option name, which is also their name
+// in config files.
+macro_rules! options_builder {
+    ( $( $(#[$doc:meta])* $field: ident: $t: ty => $name: literal ),+ ) => {
+        /// Typed alternative to `Options::add_option_*`, validated before anything reaches
+        /// OpenZWave.
+        #[derive(Debug, Clone, Default, PartialEq)]
+        #[cfg_attr(feature = "config_file", derive(serde::Deserialize))]
+        #[cfg_attr(feature = "config_file", serde(deny_unknown_fields))]
+        pub struct OptionsBuilder {
+            #[cfg_attr(feature = "config_file", serde(rename = "ConfigPath"))]
+            config_path: Option<String>,
+            #[cfg_attr(feature = "config_file", serde(rename = "UserPath"))]
+            user_path: Option<String>,
+            #[cfg_attr(feature = "config_file", serde(rename = "CommandLine"))]
+            command_line: Option<String>,
+            #[cfg_attr(feature = "config_file", serde(rename = "LogToRust"))]
+            log_to_rust: Option<bool>,
+            $(
+                #[cfg_attr(feature = "config_file", serde(rename = $name))]
+                $field: Option<$t>,
+            )+
+        }
+
+        impl OptionsBuilder {
+            $(
+                $(#[$doc])*
+                pub fn $field<V: Into<$t>>(mut self, value: V) -> Self {
+                    self.$field = Some(value.into());
+                    self
+                }
+            )+
+
+            fn add_options(&self, options: &mut Options) -> Result<()> {
+                $(
+                    if let Some(ref value) = self.$field {
+                        value.add_to(options, $name)?;
+                    }
+                )+
+                Ok(())
+            }
+        }
+    };
+}
+
+options_builder! {
+    /// Enables OpenZWave's own log.
+    logging: bool => "Logging",
+    log_file_name: String => "LogFileName",
+    append_log_file: bool => "AppendLogFile",
+    console_output: bool => "ConsoleOutput",
+    save_log_level: LogLevel => "SaveLogLevel",
+    /// Messages kept in memory, dumped to the log when DumpTriggerLevel is reached.
+    queue_log_level: LogLevel => "QueueLogLevel",
+    dump_trigger_level: LogLevel => "DumpTriggerLevel",
+    /// Associates the controller with group 1 of every device.
+    associate: bool => "Associate",
+    exclude: String => "Exclude",
+    include: String => "Include",
+    notify_transactions: bool => "NotifyTransactions",
+    save_configuration: bool => "SaveConfiguration",
+    driver_max_attempts: i32 => "DriverMaxAttempts",
+    /// In milliseconds.
+    poll_interval: i32 => "PollInterval",
+    /// PollInterval is the time between two polls instead of the time to poll every value.
+    interval_between_polls: bool => "IntervalBetweenPolls",
+    suppress_value_refresh: bool => "SuppressValueRefresh",
+    perform_return_routes: bool => "PerformReturnRoutes",
+    network_key: [u8; 16] => "NetworkKey",
+    refresh_all_user_codes: bool => "RefreshAllUserCodes",
+    /// In milliseconds.
+    retry_timeout: i32 => "RetryTimeout",
+    enable_sis: bool => "EnableSIS",
+    assume_awake: bool => "AssumeAwake",
+    notify_on_driver_unload: bool => "NotifyOnDriverUnload",
+    security_strategy: SecurityStrategy => "SecurityStrategy",
+    custom_secured_cc: Vec<u8> => "CustomSecuredCC",
+    enforce_secure_reception: bool => "EnforceSecureReception",
+    auto_update_config_file: bool => "AutoUpdateConfigFile",
+    reload_after_update: ReloadAfterUpdate => "ReloadAfterUpdate",
+    language: String => "Language",
+    include_instance_labels: bool => "IncludeInstanceLabels"
+}
+
+fn check_string(name: &'static str, value: &Option<String>) -> Result<()> {
+    match *value {
+        Some(ref value) if value.contains('\0') => Err(Error::InvalidOption(name, "contains a NUL byte")),
+        _ => Ok(()),
+    }
+}
+
+impl OptionsBuilder {
+    pub fn new() -> OptionsBuilder {
+        OptionsBuilder::default()
+    }
+
+    /// Where OpenZWave's device database is. Defaults to the one shipped with openzwave-sys.
+    pub fn config_path<V: Into<String>>(mut self, value: V) -> Self {
+        self.config_path = Some(value.into());
+        self
+    }
+
+    /// Where OpenZWave writes the network cache and its log.
+    pub fn user_path<V: Into<String>>(mut self, value: V) -> Self {
+        self.user_path = Some(value.into());
+        self
+    }
+
+    pub fn command_line<V: Into<String>>(mut self, value: V) -> Self {
+        self.command_line = Some(value.into());
+        self
+    }
+
+    /// See `Options::log_to_rust`.
+    pub fn log_to_rust(mut self, value: bool) -> Self {
+        self.log_to_rust = Some(value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        check_string("ConfigPath", &self.config_path)?;
+        check_string("UserPath", &self.user_path)?;
+        check_string("CommandLine", &self.command_line)?;
+        check_string("LogFileName", &self.log_file_name)?;
+        check_string("Exclude", &self.exclude)?;
+        check_string("Include", &self.include)?;
+        check_string("Language", &self.language)?;
+
+        if self.log_file_name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(Error::InvalidOption("LogFileName", "is empty"));
+        }
+        if self.driver_max_attempts.is_some_and(|attempts| attempts < 0) {
+            return Err(Error::InvalidOption("DriverMaxAttempts", "is negative"));
+        }
+        if self.poll_interval.is_some_and(|interval| interval < 0) {
+            return Err(Error::InvalidOption("PollInterval", "is negative"));
+        }
+        if self.retry_timeout.is_some_and(|timeout| timeout <= 0) {
+            return Err(Error::InvalidOption("RetryTimeout", "must be positive"));
+        }
+        if let (Some(save), Some(queue)) = (self.save_log_level, self.queue_log_level) {
+            if queue < save {
+                return Err(Error::InvalidOption("QueueLogLevel", "is less verbose than SaveLogLevel"));
+            }
+        }
+        if let (Some(queue), Some(dump)) = (self.queue_log_level, self.dump_trigger_level) {
+            if dump > queue {
+                return Err(Error::InvalidOption("DumpTriggerLevel", "is more verbose than QueueLogLevel"));
+            }
+        }
+        if self.custom_secured_cc.is_some() && self.security_strategy != Some(SecurityStrategy::Custom) {
+            return Err(Error::InvalidOption("CustomSecuredCC", "requires the CUSTOM SecurityStrategy"));
+        }
+        Ok(())
+    }
+
+    /// Validates then creates the Options. They get locked by `Manager::create`.
+    pub fn build(self) -> Result<Options> {
+        self.validate()?;
+        let mut options = Options::create(
+            self.config_path.as_deref().unwrap_or(""),
+            self.user_path.as_deref().unwrap_or(""),
+            self.command_line.as_deref().unwrap_or(""),
+        )?;
+        self.add_options(&mut options)?;
+        if let Some(log_to_rust) = self.log_to_rust {
+            options.log_to_rust(log_to_rust)?;
+        }
+        Ok(options)
+    }
+
+    #[cfg(feature = "config_file")]
+    pub fn from_toml_str(config: &str) -> Result<OptionsBuilder> {
+        toml::from_str(config).map_err(|e| Error::ConfigFileError(e.to_string()))
+    }
+
+    #[cfg(feature = "config_file")]
+    pub fn from_json_str(config: &str) -> Result<OptionsBuilder> {
+        serde_json::from_str(config).map_err(|e| Error::ConfigFileError(e.to_string()))
+    }
+
+    /// Loads a `.toml` or `.json` file using the OpenZWave option names, e.g. `PollInterval`.
+    #[cfg(feature = "config_file")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<OptionsBuilder> {
+        let path = path.as_ref();
+        let config = std::fs::read_to_string(path)
+            .map_err(|e| Error::ConfigFileError(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => OptionsBuilder::from_toml_str(&config),
+            Some("json") => OptionsBuilder::from_json_str(&config),
+            _ => Err(Error::ConfigFileError(format!(
+                "{}: unknown format, expected a .toml or .json file",
+                path.display()
+            ))),
+        }
+    }
+}
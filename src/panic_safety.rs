@@ -0,0 +1,127 @@
+use crate::manager::NotificationWatcher;
+use crate::notification::{Notification, NotificationType};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+pub const DEFAULT_QUARANTINE_AFTER: usize = 5;
+
+/// Describes a panic raised by a watcher and swallowed before reaching OpenZWave.
+#[derive(Debug, Clone)]
+pub struct WatcherPanic {
+    pub message: String,
+    pub notification_type: NotificationType,
+    pub home_id: u32,
+    pub node_id: u8,
+    /// How many times this watcher has panicked so far.
+    pub panics: usize,
+    /// The watcher won't receive any further notification.
+    pub quarantined: bool,
+}
+
+type PanicHook = Box<dyn Fn(&WatcherPanic) + Send + Sync>;
+
+pub(crate) struct PanicPolicy {
+    hook: RwLock<Option<PanicHook>>,
+    quarantine_after: AtomicUsize, //< 0 means never
+    swallowed: AtomicU64,
+}
+
+impl PanicPolicy {
+    pub fn new() -> Self {
+        PanicPolicy {
+            hook: RwLock::new(None),
+            quarantine_after: AtomicUsize::new(DEFAULT_QUARANTINE_AFTER),
+            swallowed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set_hook(&self, hook: Option<PanicHook>) {
+        *self.hook.write().unwrap() = hook;
+    }
+
+    pub fn set_quarantine_after(&self, panics: Option<usize>) {
+        self.quarantine_after
+            .store(panics.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    pub fn swallowed(&self) -> u64 {
+        self.swallowed.load(Ordering::Relaxed)
+    }
+
+    fn report(&self, report: &WatcherPanic) {
+        self.swallowed.fetch_add(1, Ordering::Relaxed);
+        match *self.hook.read().unwrap() {
+            Some(ref hook) => hook(report),
+            None => log::error!(
+                "Notification watcher panicked on {:?} (home {:08x}, node {}): {}{}",
+                report.notification_type,
+                report.home_id,
+                report.node_id,
+                report.message,
+                if report.quarantined { ", watcher quarantined" } else { "" }
+            ),
+        }
+    }
+}
+
+/// Wraps the watchers registered through a `Manager` so their panics never unwind
+/// into OpenZWave's driver thread.
+pub(crate) struct PanicSafeWatcher<T: NotificationWatcher> {
+    watcher: T,
+    policy: Arc<PanicPolicy>,
+    panics: AtomicUsize,
+    quarantined: AtomicBool,
+}
+
+impl<T: NotificationWatcher> PanicSafeWatcher<T> {
+    pub fn new(watcher: T, policy: Arc<PanicPolicy>) -> Self {
+        PanicSafeWatcher {
+            watcher,
+            policy,
+            panics: AtomicUsize::new(0),
+            quarantined: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<T: NotificationWatcher> NotificationWatcher for PanicSafeWatcher<T> {
+    fn on_notification(&self, notification: &Notification) {
+        if self.quarantined.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.watcher.on_notification(notification)
+        }));
+
+        if let Err(payload) = result {
+            let panics = self.panics.fetch_add(1, Ordering::Relaxed) + 1;
+            let limit = self.policy.quarantine_after.load(Ordering::Relaxed);
+            let quarantined = limit > 0 && panics >= limit;
+            if quarantined {
+                self.quarantined.store(true, Ordering::Relaxed);
+            }
+
+            self.policy.report(&WatcherPanic {
+                message: panic_message(&*payload),
+                notification_type: notification.notification_type,
+                home_id: notification.home_id,
+                node_id: notification.node_id,
+                panics,
+                quarantined,
+            });
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic payload")
+    }
+}
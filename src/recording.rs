@@ -0,0 +1,335 @@
+use crate::error::{Error, Result};
+use crate::manager::NotificationWatcher;
+use crate::node::Node;
+use crate::notification::{
+    ControllerCommandType, ControllerError, ControllerState, Event, Notification,
+    NotificationCode, NotificationType, NotificationValue, UserAlert,
+};
+use crate::value_classes::value_id::{ValueContent, ValueID, ValueIdKey};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The `value` of a notification, OpenZWave enums being written by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedPayload {
+    Group(u8),
+    Button(u8),
+    Scene(u8),
+    State(String),
+    ControllerError(String),
+    Report(String),
+    UserAlert(UserAlert),
+}
+
+/// The `event` of a notification, a state name or a node event level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedEvent {
+    Controller(Option<String>),
+    Node(u8),
+}
+
+/// A value as it was when notified. Genre, type and the rest of the key come from `id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedValue {
+    pub id: u64,
+    /// `ValueIdKey` string, for the readers of the recording
+    #[serde(default)]
+    pub key: String,
+    pub label: String,
+    pub units: String,
+    pub content: serde_json::Value,
+}
+
+/// What was known of the node when the notification was recorded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedNode {
+    pub name: String,
+    pub location: String,
+    pub manufacturer_name: String,
+    pub product_name: String,
+    pub query_stage: String,
+    pub failed: bool,
+    pub awake: bool,
+}
+
+/// One line of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedNotification {
+    /// Since the recording started
+    pub elapsed_ms: u64,
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    pub home_id: u32,
+    pub node_id: u8,
+    #[serde(default)]
+    pub byte: u8,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<RecordedPayload>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<RecordedEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<RecordedValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<RecordedNode>,
+}
+
+fn name<T: fmt::Debug>(variant: T) -> String {
+    format!("{:?}", variant)
+}
+
+// The variant a `name` was written from
+fn from_name<T: fmt::Debug>(name: &str, from_u8: fn(u8) -> Option<T>) -> Option<T> {
+    (0..=u8::MAX)
+        .filter_map(from_u8)
+        .find(|variant| format!("{:?}", variant) == name)
+}
+
+impl RecordedNotification {
+    /// Records `notification`, reading the content of its value and its node from the network.
+    pub fn capture(notification: &Notification, elapsed: Duration) -> RecordedNotification {
+        let node = match notification.node_id {
+            0 => None,
+            node_id => {
+                let node = Node::from_id(notification.home_id, node_id);
+                Some(RecordedNode {
+                    name: node.get_name(),
+                    location: node.get_location(),
+                    manufacturer_name: node.get_manufacturer_name(),
+                    product_name: node.get_product_name(),
+                    query_stage: node.get_query_stage(),
+                    failed: node.is_failed(),
+                    awake: node.is_awake(),
+                })
+            }
+        };
+        RecordedNotification {
+            elapsed_ms: elapsed.as_millis() as u64,
+            notification_type: name(notification.notification_type),
+            home_id: notification.home_id,
+            node_id: notification.node_id,
+            byte: notification.byte,
+            description: notification.description.clone(),
+            command: notification.command.map(name),
+            payload: notification.value.as_ref().map(|value| match *value {
+                NotificationValue::Group(group_idx) => RecordedPayload::Group(group_idx),
+                NotificationValue::Button(button_id) => RecordedPayload::Button(button_id),
+                NotificationValue::Scene(scene_id) => RecordedPayload::Scene(scene_id),
+                NotificationValue::State(state) => RecordedPayload::State(name(state)),
+                NotificationValue::ControllerError(error) => {
+                    RecordedPayload::ControllerError(name(error))
+                }
+                NotificationValue::Report(code) => RecordedPayload::Report(name(code)),
+                NotificationValue::UserAlert(alert) => RecordedPayload::UserAlert(alert),
+            }),
+            event: notification.event.as_ref().map(|event| match *event {
+                Event::Controller(state) => RecordedEvent::Controller(state.map(name)),
+                Event::Node(level) => RecordedEvent::Node(level),
+            }),
+            value: notification.value_id.as_ref().map(|value_id| RecordedValue {
+                id: value_id.id(),
+                key: value_id.key().to_string(),
+                label: value_id.label().to_string(),
+                units: value_id.units().to_string(),
+                content: serde_json::to_value(value_id.value()).unwrap_or_default(),
+            }),
+            node,
+        }
+    }
+
+    /// The notification as it was delivered, without OpenZWave: only the label, units and
+    /// content of its value are known. None if the record names unknown enum variants.
+    pub fn to_notification(&self) -> Option<Notification> {
+        let value_id = match self.value {
+            Some(ref value) => {
+                let value_type = ValueIdKey::from_packed_id(self.home_id, value.id).value_type;
+                Some(ValueID::with_content(
+                    self.home_id,
+                    value.id,
+                    value.label.clone(),
+                    value.units.clone(),
+                    ValueContent::from_json(value_type, value.content.clone())?,
+                ))
+            }
+            None => None,
+        };
+        let value = match self.payload {
+            Some(RecordedPayload::Group(group_idx)) => Some(NotificationValue::Group(group_idx)),
+            Some(RecordedPayload::Button(button_id)) => Some(NotificationValue::Button(button_id)),
+            Some(RecordedPayload::Scene(scene_id)) => Some(NotificationValue::Scene(scene_id)),
+            Some(RecordedPayload::State(ref state)) => Some(NotificationValue::State(from_name(
+                state,
+                ControllerState::from_u8,
+            )?)),
+            Some(RecordedPayload::ControllerError(ref error)) => Some(
+                NotificationValue::ControllerError(from_name(error, ControllerError::from_u8)?),
+            ),
+            Some(RecordedPayload::Report(ref code)) => {
+                Some(NotificationValue::Report(from_name(code, NotificationCode::from_u8)?))
+            }
+            Some(RecordedPayload::UserAlert(alert)) => Some(NotificationValue::UserAlert(alert)),
+            None => None,
+        };
+        let event = match self.event {
+            Some(RecordedEvent::Controller(Some(ref state))) => Some(Event::Controller(Some(
+                from_name(state, ControllerState::from_u8)?,
+            ))),
+            Some(RecordedEvent::Controller(None)) => Some(Event::Controller(None)),
+            Some(RecordedEvent::Node(level)) => Some(Event::Node(level)),
+            None => None,
+        };
+        let command = match self.command {
+            Some(ref command) => Some(from_name(command, ControllerCommandType::from_u8)?),
+            None => None,
+        };
+
+        Some(Notification {
+            notification_type: from_name(&self.notification_type, NotificationType::from_u8)?,
+            home_id: self.home_id,
+            node_id: self.node_id,
+            value_id,
+            value,
+            event,
+            command,
+            byte: self.byte,
+            description: self.description.clone(),
+        })
+    }
+}
+
+/// A watcher writing every notification as a line of JSON.
+///
+/// Each line is flushed at once, so a recording is complete up to a crash.
+pub struct Recorder {
+    writer: Mutex<Box<dyn Write + Send>>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn new<W: 'static + Write + Send>(writer: W) -> Recorder {
+        Recorder {
+            writer: Mutex::new(Box::new(writer)),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Recorder> {
+        Ok(Recorder::new(BufWriter::new(File::create(path)?)))
+    }
+
+    fn write(&self, record: &RecordedNotification) -> io::Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        serde_json::to_writer(&mut *writer, record)?;
+        writer.write_all(b"\n")?;
+        writer.flush()
+    }
+}
+
+impl NotificationWatcher for Recorder {
+    fn on_notification(&self, notification: &Notification) {
+        let record = RecordedNotification::capture(notification, self.start.elapsed());
+        if let Err(error) = self.write(&record) {
+            log::error!("Could not record a notification: {}", error);
+        }
+    }
+}
+
+/// How fast a `Replay` delivers its notifications.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// One after the other, without waiting
+    Instant,
+    /// With the delays they were recorded with
+    RealTime,
+    /// With the recorded delays divided by the factor, finite and above 0. Replays stretched
+    /// beyond what a `Duration` holds are refused.
+    Accelerated(f64),
+}
+
+/// A recording read back, to deliver to any watcher.
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    records: Vec<RecordedNotification>,
+}
+
+impl Replay {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Replay> {
+        Replay::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads JSON lines, skipping blank ones. Fails on the first line that isn't a
+    /// notification.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Replay> {
+        let mut records = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |reason: String| {
+                let message = format!("line {}: {}", number + 1, reason);
+                io::Error::new(io::ErrorKind::InvalidData, message)
+            };
+            let record: RecordedNotification =
+                serde_json::from_str(&line).map_err(|error| invalid(error.to_string()))?;
+            if record.to_notification().is_none() {
+                return Err(invalid(String::from("not a notification OpenZWave can send")));
+            }
+            records.push(record);
+        }
+        Ok(Replay { records })
+    }
+
+    pub fn records(&self) -> &[RecordedNotification] {
+        &self.records
+    }
+
+    pub fn notifications(&self) -> impl Iterator<Item = Notification> + '_ {
+        self.records.iter().filter_map(RecordedNotification::to_notification)
+    }
+
+    /// Delivers every notification to `watcher`, blocking until the last one.
+    pub fn replay<W: NotificationWatcher + ?Sized>(
+        &self,
+        watcher: &W,
+        speed: ReplaySpeed,
+    ) -> Result<()> {
+        // When to deliver a record, None if the factor puts it out of reach
+        let due = |elapsed_ms: u64| match speed {
+            ReplaySpeed::Instant => Some(Duration::ZERO),
+            ReplaySpeed::RealTime => Some(Duration::from_millis(elapsed_ms)),
+            ReplaySpeed::Accelerated(factor) if factor.is_finite() && factor > 0.0 => {
+                Duration::try_from_secs_f64(elapsed_ms as f64 / 1000.0 / factor).ok()
+            }
+            ReplaySpeed::Accelerated(_) => None,
+        };
+        // Checked before delivering anything, against the latest record
+        let last = self.records.iter().map(|record| record.elapsed_ms).max();
+        if due(last.unwrap_or(0)).is_none() {
+            return Err(Error::InvalidParameter("speed", "Replay::replay"));
+        }
+
+        let start = Instant::now();
+        for record in &self.records {
+            if speed != ReplaySpeed::Instant {
+                let due = due(record.elapsed_ms).unwrap_or_default();
+                thread::sleep(due.saturating_sub(start.elapsed()));
+            }
+            if let Some(notification) = record.to_notification() {
+                watcher.on_notification(&notification);
+            }
+        }
+        Ok(())
+    }
+}
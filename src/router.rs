@@ -0,0 +1,198 @@
+use crate::error::{Error, Result};
+use crate::manager::{Manager, NotificationWatcher, WatcherGuard};
+use crate::notification::{Notification, NotificationType};
+use crate::panic_safety::{PanicPolicy, PanicSafeWatcher};
+use crate::value_classes::value_id::{CommandClass, ValueGenre};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+
+/// Which notifications a handler wants, every criterion set having to match.
+///
+/// Command class, genre, instance and label criteria only match notifications carrying a
+/// value. All but the label are decoded from the packed value id; the label is read from
+/// OpenZWave last, and only when everything else matched.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationFilter {
+    home_id: Option<u32>,
+    node_ids: BTreeSet<u8>,
+    command_classes: BTreeSet<u8>,
+    genres: Vec<ValueGenre>,
+    notification_types: Vec<NotificationType>,
+    instance: Option<u8>,
+    label: Option<Regex>,
+}
+
+impl NotificationFilter {
+    /// A filter letting everything through.
+    pub fn new() -> Self {
+        NotificationFilter::default()
+    }
+
+    pub fn home_id(mut self, home_id: u32) -> Self {
+        self.home_id = Some(home_id);
+        self
+    }
+
+    /// Adds `node_id` to the accepted nodes.
+    pub fn node(mut self, node_id: u8) -> Self {
+        self.node_ids.insert(node_id);
+        self
+    }
+
+    pub fn nodes<I: IntoIterator<Item = u8>>(mut self, node_ids: I) -> Self {
+        self.node_ids.extend(node_ids);
+        self
+    }
+
+    /// Adds `command_class` to the accepted command classes.
+    pub fn command_class(mut self, command_class: CommandClass) -> Self {
+        self.command_classes.insert(command_class as u8);
+        self
+    }
+
+    /// Adds `genre` to the accepted genres.
+    pub fn genre(mut self, genre: ValueGenre) -> Self {
+        self.genres.push(genre);
+        self
+    }
+
+    /// Adds `notification_type` to the accepted types.
+    pub fn notification_type(mut self, notification_type: NotificationType) -> Self {
+        self.notification_types.push(notification_type);
+        self
+    }
+
+    pub fn instance(mut self, instance: u8) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Values whose label matches `pattern` somewhere, anchor it to match the whole label.
+    pub fn label(mut self, pattern: &str) -> Result<Self> {
+        let label = Regex::new(pattern)
+            .map_err(|_| Error::InvalidParameter("pattern", "NotificationFilter::label"))?;
+        self.label = Some(label);
+        Ok(self)
+    }
+
+    pub fn matches(&self, notification: &Notification) -> bool {
+        if self.home_id.is_some_and(|home_id| home_id != notification.home_id)
+            || !self.node_ids.is_empty() && !self.node_ids.contains(&notification.node_id)
+            || !self.notification_types.is_empty()
+                && !self.notification_types.contains(&notification.notification_type)
+        {
+            return false;
+        }
+        if self.command_classes.is_empty()
+            && self.genres.is_empty()
+            && self.instance.is_none()
+            && self.label.is_none()
+        {
+            return true;
+        }
+
+        let value_id = match notification.value_id {
+            Some(ref value_id) => value_id,
+            None => return false,
+        };
+        let key = value_id.key();
+        (self.command_classes.is_empty() || self.command_classes.contains(&key.command_class))
+            && (self.genres.is_empty()
+                || key.genre.is_some_and(|genre| self.genres.contains(&genre)))
+            && self.instance.is_none_or(|instance| instance == key.instance)
+            && self.label.as_ref().is_none_or(|label| label.is_match(value_id.label()))
+    }
+}
+
+type Routes = RwLock<Vec<(u64, NotificationFilter, Arc<dyn NotificationWatcher>)>>;
+
+#[derive(Default)]
+struct Inner {
+    routes: Routes,
+    next_route: AtomicU64,
+}
+
+struct Dispatcher {
+    inner: Arc<Inner>,
+}
+
+impl NotificationWatcher for Dispatcher {
+    fn on_notification(&self, notification: &Notification) {
+        // Cloned out so handlers can add or drop routes.
+        let handlers: Vec<Arc<dyn NotificationWatcher>> = self
+            .inner
+            .routes
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, filter, _)| filter.matches(notification))
+            .map(|(_, _, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            handler.on_notification(notification);
+        }
+    }
+}
+
+/// Keeps a `Router` handler registered until it's dropped.
+#[must_use = "the handler is removed as soon as the route is dropped"]
+pub struct Route {
+    inner: Weak<Inner>,
+    id: u64,
+}
+
+impl Drop for Route {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.routes.write().unwrap().retain(|(id, _, _)| *id != self.id);
+        }
+    }
+}
+
+/// Dispatches the notifications to handlers through a single watcher, each handler getting
+/// the ones its filter matches, in the order the handlers were routed.
+///
+/// Handler panics are swallowed and reported like the manager's watchers ones, each handler
+/// being quarantined on its own.
+pub struct Router {
+    inner: Arc<Inner>,
+    panic_policy: Arc<PanicPolicy>,
+    _guard: WatcherGuard,
+}
+
+impl Router {
+    pub fn new(manager: &Manager) -> Result<Router> {
+        let inner = Arc::new(Inner::default());
+        let guard = WatcherGuard::register(
+            manager.backend().clone(),
+            Arc::new(Dispatcher {
+                inner: inner.clone(),
+            }),
+        )?;
+        Ok(Router {
+            inner,
+            panic_policy: manager.panic_policy().clone(),
+            _guard: guard,
+        })
+    }
+
+    pub fn route<T: 'static + NotificationWatcher>(
+        &self,
+        filter: NotificationFilter,
+        handler: T,
+    ) -> Route {
+        let id = self.inner.next_route.fetch_add(1, Ordering::Relaxed);
+        let handler = PanicSafeWatcher::new(handler, self.panic_policy.clone());
+        self.inner
+            .routes
+            .write()
+            .unwrap()
+            .push((id, filter, Arc::new(handler)));
+        Route {
+            inner: Arc::downgrade(&self.inner),
+            id,
+        }
+    }
+}
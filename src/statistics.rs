@@ -0,0 +1,124 @@
+use ffi::manager as extern_manager;
+use ffi::utils::recover_string;
+
+/// Counters kept by the driver of a controller since it was started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize))]
+pub struct DriverStatistics {
+    /// Start of frame bytes read
+    pub sof_count: u32,
+    /// Unsolicited messages received while waiting for an ACK
+    pub ack_waiting: u32,
+    /// Times a read was aborted because of a timeout
+    pub read_aborts: u32,
+    pub bad_checksum: u32,
+    pub read_count: u32,
+    pub write_count: u32,
+    pub can_count: u32,
+    pub nak_count: u32,
+    pub ack_count: u32,
+    /// Out of frame bytes read
+    pub oof_count: u32,
+    /// Messages dropped and not delivered
+    pub dropped: u32,
+    /// Messages retransmitted
+    pub retries: u32,
+    /// Unexpected callbacks
+    pub callbacks: u32,
+    pub bad_routes: u32,
+    pub no_ack: u32,
+    pub net_busy: u32,
+    pub not_idle: u32,
+    pub tx_verified: u32,
+    pub non_delivery: u32,
+    pub routed_busy: u32,
+    pub broadcast_read_count: u32,
+    pub broadcast_write_count: u32,
+}
+
+impl From<extern_manager::DriverStatistics> for DriverStatistics {
+    fn from(stats: extern_manager::DriverStatistics) -> Self {
+        DriverStatistics {
+            sof_count: stats.sof_count,
+            ack_waiting: stats.ack_waiting,
+            read_aborts: stats.read_aborts,
+            bad_checksum: stats.bad_checksum,
+            read_count: stats.read_count,
+            write_count: stats.write_count,
+            can_count: stats.can_count,
+            nak_count: stats.nak_count,
+            ack_count: stats.ack_count,
+            oof_count: stats.oof_count,
+            dropped: stats.dropped,
+            retries: stats.retries,
+            callbacks: stats.callbacks,
+            bad_routes: stats.bad_routes,
+            no_ack: stats.no_ack,
+            net_busy: stats.net_busy,
+            not_idle: stats.not_idle,
+            tx_verified: stats.tx_verified,
+            non_delivery: stats.non_delivery,
+            routed_busy: stats.routed_busy,
+            broadcast_read_count: stats.broadcast_read_count,
+            broadcast_write_count: stats.broadcast_write_count,
+        }
+    }
+}
+
+/// Traffic and link quality of a node, as seen by the controller.
+///
+/// OpenZWave doesn't keep the last message sent to a node, only the last one received.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize))]
+pub struct NodeStatistics {
+    pub sent_count: u32,
+    pub sent_failed: u32,
+    pub retries: u32,
+    pub received_count: u32,
+    pub received_dups: u32,
+    pub received_unsolicited: u32,
+    /// Formatted by OpenZWave, empty until a message was sent
+    pub sent_timestamp: String,
+    /// Formatted by OpenZWave, empty until a message was received
+    pub received_timestamp: String,
+    /// Round trip times, in milliseconds
+    pub last_request_rtt: u32,
+    pub average_request_rtt: u32,
+    pub last_response_rtt: u32,
+    pub average_response_rtt: u32,
+    /// Signal quality reported by the node, 0 when unknown
+    pub quality: u8,
+    /// Raw bytes of the last message received from the node, from its start of frame
+    pub last_received_message: Vec<u8>,
+}
+
+impl From<extern_manager::NodeStatistics> for NodeStatistics {
+    fn from(stats: extern_manager::NodeStatistics) -> Self {
+        NodeStatistics {
+            sent_count: stats.sent_count,
+            sent_failed: stats.sent_failed,
+            retries: stats.retries,
+            received_count: stats.received_count,
+            received_dups: stats.received_dups,
+            received_unsolicited: stats.received_unsolicited,
+            sent_timestamp: recover_string(stats.sent_timestamp),
+            received_timestamp: recover_string(stats.received_timestamp),
+            last_request_rtt: stats.last_request_rtt,
+            average_request_rtt: stats.average_request_rtt,
+            last_response_rtt: stats.last_response_rtt,
+            average_response_rtt: stats.average_response_rtt,
+            quality: stats.quality,
+            last_received_message: message_bytes(&stats.last_received_message),
+        }
+    }
+}
+
+// A serial frame is SOF, length, then `length` bytes; the buffer is zeroed when nothing was received
+fn message_bytes(buffer: &[u8]) -> Vec<u8> {
+    const SOF: u8 = 0x01;
+    if buffer.len() < 2 || buffer[0] != SOF {
+        return Vec::new();
+    }
+    let length = (buffer[1] as usize + 2).min(buffer.len());
+    buffer[..length].to_vec()
+}
@@ -0,0 +1,261 @@
+use crate::controller::Controller;
+use crate::node::{Node, NodeBasic};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+const MAX_NODE_ID: u8 = 232;
+
+/// A node of the mesh, with what matters for routing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyNode {
+    pub node_id: u8,
+    pub name: String,
+    pub basic: Option<NodeBasic>,
+    pub listening: bool,
+    pub routing: bool,
+    pub failed: bool,
+    pub neighbors: Vec<u8>,
+}
+
+impl TopologyNode {
+    fn from_node(node: &Node) -> TopologyNode {
+        let name = match node.get_name() {
+            ref name if !name.is_empty() => name.clone(),
+            _ => node.get_product_name(),
+        };
+        TopologyNode {
+            node_id: node.get_id(),
+            name,
+            basic: node.get_basic(),
+            listening: node.is_listening_device(),
+            routing: node.is_routing_device(),
+            failed: node.is_failed(),
+            neighbors: node
+                .get_neighbors()
+                .unwrap_or_default()
+                .iter()
+                .map(Node::get_id)
+                .collect(),
+        }
+    }
+
+    /// Only always listening, routing and healthy nodes forward frames for others.
+    pub fn can_relay(&self) -> bool {
+        self.listening && self.routing && !self.failed
+    }
+}
+
+/// The routing graph of a controller's network, built from each node's neighbour list.
+///
+/// An edge is kept when either end reports the other as a neighbour.
+#[derive(Debug, Clone)]
+pub struct Topology {
+    home_id: u32,
+    controller_node_id: u8,
+    nodes: BTreeMap<u8, TopologyNode>,
+    edges: BTreeSet<(u8, u8)>,
+}
+
+impl Topology {
+    /// Builds the graph of every node known to the controller.
+    ///
+    /// OpenZWave has no node list, so every possible node id is probed.
+    pub fn build(controller: &Controller) -> Topology {
+        let home_id = controller.get_home_id();
+        let node_ids = (1..=MAX_NODE_ID)
+            .filter(|&node_id| Node::from_id(home_id, node_id).get_basic().is_some());
+        Topology::from_nodes(controller, node_ids)
+    }
+
+    /// Builds the graph of the given nodes, e.g. the ones known to a `NetworkState`.
+    pub fn from_nodes<I: IntoIterator<Item = u8>>(controller: &Controller, node_ids: I) -> Topology {
+        let home_id = controller.get_home_id();
+        let nodes: BTreeMap<u8, TopologyNode> = node_ids
+            .into_iter()
+            .map(|node_id| (node_id, TopologyNode::from_node(&Node::from_id(home_id, node_id))))
+            .collect();
+
+        let edges = nodes
+            .values()
+            .flat_map(|node| {
+                let node_id = node.node_id;
+                let nodes = &nodes;
+                node.neighbors
+                    .iter()
+                    .filter(move |neighbor| nodes.contains_key(neighbor) && **neighbor != node_id)
+                    .map(move |&neighbor| (node_id.min(neighbor), node_id.max(neighbor)))
+            })
+            .collect();
+
+        Topology {
+            home_id,
+            controller_node_id: controller.get_controller_node_id(),
+            nodes,
+            edges,
+        }
+    }
+
+    pub fn get_home_id(&self) -> u32 {
+        self.home_id
+    }
+
+    pub fn get_controller_node_id(&self) -> u8 {
+        self.controller_node_id
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &TopologyNode> {
+        self.nodes.values()
+    }
+
+    pub fn node(&self, node_id: u8) -> Option<&TopologyNode> {
+        self.nodes.get(&node_id)
+    }
+
+    /// Links between two nodes, the lowest id first.
+    pub fn edges(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.edges.iter().cloned()
+    }
+
+    /// Nodes the controller can't reach, directly or through relaying nodes.
+    pub fn unreachable(&self) -> Vec<u8> {
+        let reachable = self.reachable(None);
+        self.nodes
+            .keys()
+            .filter(|node_id| !reachable.contains(node_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Relaying nodes whose failure would cut other nodes off the controller.
+    pub fn single_points_of_failure(&self) -> Vec<u8> {
+        let reachable = self.reachable(None);
+        self.nodes
+            .values()
+            .filter(|node| node.node_id != self.controller_node_id && node.can_relay())
+            .filter(|node| reachable.contains(&node.node_id))
+            .filter(|node| self.reachable(Some(node.node_id)).len() + 1 < reachable.len())
+            .map(|node| node.node_id)
+            .collect()
+    }
+
+    fn neighbors(&self, node_id: u8) -> impl Iterator<Item = u8> + '_ {
+        self.edges.iter().filter_map(move |&(a, b)| match node_id {
+            _ if a == node_id => Some(b),
+            _ if b == node_id => Some(a),
+            _ => None,
+        })
+    }
+
+    // Breadth first search from the controller, optionally as if `without` had failed
+    fn reachable(&self, without: Option<u8>) -> BTreeSet<u8> {
+        let mut reached = BTreeSet::new();
+        if !self.nodes.contains_key(&self.controller_node_id) {
+            return reached;
+        }
+        let mut queue = VecDeque::new();
+        reached.insert(self.controller_node_id);
+        queue.push_back(self.controller_node_id);
+
+        while let Some(node_id) = queue.pop_front() {
+            let relays = node_id == self.controller_node_id
+                || self.nodes.get(&node_id).is_some_and(TopologyNode::can_relay);
+            if !relays {
+                continue;
+            }
+            for neighbor in self.neighbors(node_id) {
+                if Some(neighbor) != without && reached.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Graphviz rendering: failed nodes in red, unreachable ones filled in pink and single
+    /// points of failure in gold. Non listening nodes are dashed.
+    pub fn to_dot(&self) -> String {
+        let unreachable = self.unreachable();
+        let single_points = self.single_points_of_failure();
+        let mut dot = String::new();
+
+        writeln!(dot, "graph \"zwave_{:08x}\" {{", self.home_id).unwrap();
+        for node in self.nodes.values() {
+            let mut attributes = vec![format!(
+                "label=\"{}\"",
+                escape(&format!(
+                    "{}: {}\n{}",
+                    node.node_id,
+                    node.name,
+                    node.basic.map_or(String::from("unknown"), |basic| basic.to_string())
+                ))
+            )];
+            if node.node_id == self.controller_node_id {
+                attributes.push(String::from("shape=doublecircle"));
+            }
+            if !node.listening {
+                attributes.push(String::from("style=\"dashed,filled\""));
+            } else {
+                attributes.push(String::from("style=filled"));
+            }
+            if node.failed {
+                attributes.push(String::from("color=red"));
+            }
+            if unreachable.contains(&node.node_id) {
+                attributes.push(String::from("fillcolor=pink"));
+            } else if single_points.contains(&node.node_id) {
+                attributes.push(String::from("fillcolor=gold"));
+            } else {
+                attributes.push(String::from("fillcolor=white"));
+            }
+            writeln!(dot, "  {} [{}];", node.node_id, attributes.join(", ")).unwrap();
+        }
+        for (a, b) in &self.edges {
+            writeln!(dot, "  {} -- {};", a, b).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// A `{ "nodes": [...], "edges": [...] }` document, with the analysis results.
+    #[cfg(feature = "serde_serialization")]
+    pub fn to_json(&self) -> serde_json::Value {
+        let unreachable = self.unreachable();
+        let single_points = self.single_points_of_failure();
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .values()
+            .map(|node| {
+                serde_json::json!({
+                    "id": node.node_id,
+                    "name": node.name,
+                    "basic": node.basic.map(|basic| basic.to_string()),
+                    "controller": node.node_id == self.controller_node_id,
+                    "listening": node.listening,
+                    "routing": node.routing,
+                    "failed": node.failed,
+                    "reachable": !unreachable.contains(&node.node_id),
+                    "single_point_of_failure": single_points.contains(&node.node_id),
+                })
+            })
+            .collect();
+        let edges: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .map(|(a, b)| serde_json::json!({ "source": a, "target": b }))
+            .collect();
+
+        serde_json::json!({
+            "home_id": self.home_id,
+            "controller": self.controller_node_id,
+            "nodes": nodes,
+            "edges": edges,
+        })
+    }
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,144 @@
+use super::value_id::{ValueContent, ValueID};
+use crate::backend;
+use crate::error::{ConfirmationError, Error, GetSetError, Result as ZWaveResult};
+use crate::manager::{NotificationWatcher, WatcherGuard};
+use crate::notification::{Notification, NotificationCode, NotificationType, NotificationValue};
+use futures::channel::oneshot;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Matcher<T> = Box<dyn Fn(&Notification) -> Option<T> + Send + Sync>;
+
+struct Confirmation<T> {
+    home_id: u32,
+    node_id: u8,
+    matches: Matcher<T>,
+    sender: Mutex<Option<oneshot::Sender<ZWaveResult<T>>>>,
+}
+
+impl<T: Send> NotificationWatcher for Confirmation<T> {
+    fn on_notification(&self, notification: &Notification) {
+        if notification.home_id != self.home_id {
+            return;
+        }
+
+        let outcome = match notification.notification_type {
+            NotificationType::Notification if notification.node_id == self.node_id => {
+                match notification.value {
+                    Some(NotificationValue::Report(NotificationCode::Timeout)) => {
+                        Err(Error::ConfirmationError(ConfirmationError::NodeTimeout))
+                    }
+                    Some(NotificationValue::Report(NotificationCode::Dead)) => {
+                        Err(Error::ConfirmationError(ConfirmationError::NodeDead))
+                    }
+                    _ => return,
+                }
+            }
+            _ => match (self.matches)(notification) {
+                Some(confirmed) => Ok(confirmed),
+                None => return,
+            },
+        };
+
+        if let Some(sender) = self.sender.lock().unwrap().take() {
+            let _ = sender.send(outcome);
+        }
+    }
+}
+
+/// Runs `write` and waits for the first notification `matches` turns into a confirmation.
+///
+/// Fails if OpenZWave reports the node as timed out or dead, or if nothing arrives within
+/// `timeout`. Must be awaited within a tokio runtime.
+pub(crate) async fn confirm<T, M, W>(
+    home_id: u32,
+    node_id: u8,
+    matches: M,
+    write: W,
+    timeout: Duration,
+) -> ZWaveResult<T>
+where
+    T: 'static + Send,
+    M: 'static + Fn(&Notification) -> Option<T> + Send + Sync,
+    W: FnOnce() -> ZWaveResult<()>,
+{
+    let (sender, receiver) = oneshot::channel();
+    let watcher = Arc::new(Confirmation {
+        home_id,
+        node_id,
+        matches: Box::new(matches),
+        sender: Mutex::new(Some(sender)),
+    });
+
+    // Listen before writing: the confirmation may arrive before the write returns.
+    let _guard = WatcherGuard::register(backend::get(home_id), watcher)?;
+
+    write()?;
+
+    match tokio::time::timeout(timeout, receiver).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(_)) => Err(Error::SetError(GetSetError::APIError("set_and_confirm"))),
+        Err(_) => Err(Error::ConfirmationError(ConfirmationError::Timeout)),
+    }
+}
+
+/// Confirms with the first value reported for the node that `matches` accepts.
+pub(crate) fn reported_value<M>(matches: M) -> impl Fn(&Notification) -> Option<ValueID>
+where
+    M: Fn(&ValueID) -> bool,
+{
+    move |notification| match notification.notification_type {
+        NotificationType::ValueAdded | NotificationType::ValueChanged | NotificationType::ValueRefreshed => {
+            notification.value_id.as_ref().filter(|value_id| matches(value_id)).cloned()
+        }
+        _ => None,
+    }
+}
+
+impl ValueID {
+    /// Writes `content` like `ValueID::set` and waits until the device reports the value back.
+    ///
+    /// Resolves with the updated `ValueID` on the first matching `ValueChanged` or
+    /// `ValueRefreshed` notification, or for a schedule or a bitset the first one reporting
+    /// all of it. Fails if OpenZWave reports the node as timed out or dead, or if nothing
+    /// arrives within `timeout`. Must be awaited within a tokio runtime.
+    pub async fn set_and_confirm(
+        &self,
+        content: ValueContent,
+        timeout: Duration,
+    ) -> ZWaveResult<ValueID> {
+        let id = self.id();
+        // Schedules and bitsets are written piece by piece, wait for the last one
+        let expected = match content {
+            ValueContent::Schedule(_) | ValueContent::BitSet(_) => Some(content.clone()),
+            _ => None,
+        };
+        confirm(
+            self.get_home_id(),
+            self.get_node_id(),
+            reported_value(move |value_id| {
+                value_id.id() == id
+                    && expected
+                        .as_ref()
+                        .is_none_or(|expected| reports_all(expected, value_id.value()))
+            }),
+            || self.set(content),
+            timeout,
+        )
+        .await
+    }
+}
+
+// Whether `reported` holds all of `expected`
+fn reports_all(expected: &ValueContent, reported: &ValueContent) -> bool {
+    match (expected, reported) {
+        (ValueContent::Schedule(expected), ValueContent::Schedule(reported)) => {
+            expected == reported
+        }
+        (ValueContent::BitSet(expected), ValueContent::BitSet(reported)) => expected
+            .bits
+            .iter()
+            .all(|bit| reported.get(bit.position) == Some(bit.value)),
+        _ => false,
+    }
+}
@@ -0,0 +1,336 @@
+use crate::error::ParseError;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A fixed-point number, `mantissa / 10^precision`, as OpenZWave formats decimal values.
+///
+/// Values compare by what they stand for: `1.5` equals `1.50`. Arithmetic keeps every
+/// digit it has room for, up to `MAX_PRECISION`, rounding the others. The operators saturate
+/// when the integer part is out of range, the `checked_` methods tell it instead.
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalValue {
+    mantissa: i64,
+    precision: u8,
+}
+
+impl DecimalValue {
+    /// The most digits after the point a `DecimalValue` can have.
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// None if `precision` is beyond `MAX_PRECISION`.
+    pub fn new(mantissa: i64, precision: u8) -> Option<Self> {
+        if precision > Self::MAX_PRECISION {
+            return None;
+        }
+        Some(DecimalValue { mantissa, precision })
+    }
+
+    /// Rounds `value` to `precision` digits, which may lose what `value` can't represent.
+    pub fn from_f32(value: f32, precision: u8) -> Option<Self> {
+        Self::from_f64(value as f64, precision)
+    }
+
+    /// None if `value` isn't finite or doesn't fit with `precision` digits.
+    pub fn from_f64(value: f64, precision: u8) -> Option<Self> {
+        if precision > Self::MAX_PRECISION {
+            return None;
+        }
+        let mantissa = (value * 10_f64.powi(precision as i32)).round();
+        if !(i64::MIN as f64..i64::MAX as f64).contains(&mantissa) {
+            return None;
+        }
+        Some(DecimalValue {
+            mantissa: mantissa as i64,
+            precision,
+        })
+    }
+
+    pub fn mantissa(&self) -> i64 {
+        self.mantissa
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10_f64.powi(self.precision as i32)
+    }
+
+    /// The same number with `precision` digits, rounded half away from zero when there are less.
+    /// None if `precision` is beyond `MAX_PRECISION` or the number doesn't fit with it.
+    pub fn rescale(&self, precision: u8) -> Option<Self> {
+        if precision > Self::MAX_PRECISION {
+            return None;
+        }
+        let mantissa = if precision >= self.precision {
+            self.scaled(precision)
+        } else {
+            round(self.mantissa as i128, (self.precision - precision) as u32)
+        };
+        Some(DecimalValue {
+            mantissa: mantissa.try_into().ok()?,
+            precision,
+        })
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (sum, precision) = self.sum(other, 1);
+        DecimalValue::fit(sum, precision)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (difference, precision) = self.sum(other, -1);
+        DecimalValue::fit(difference, precision)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (product, precision) = self.product(other);
+        DecimalValue::fit(product, precision)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(DecimalValue {
+            mantissa: self.mantissa.checked_neg()?,
+            precision: self.precision,
+        })
+    }
+
+    // The mantissa at a larger precision, which can't overflow as precisions are at most 18
+    fn scaled(&self, precision: u8) -> i128 {
+        self.mantissa as i128 * 10_i128.pow((precision - self.precision) as u32)
+    }
+
+    // The exact `self + sign * other`, as a mantissa and its precision
+    fn sum(self, other: Self, sign: i128) -> (i128, u32) {
+        let precision = self.precision.max(other.precision);
+        (self.scaled(precision) + sign * other.scaled(precision), precision as u32)
+    }
+
+    fn product(self, other: Self) -> (i128, u32) {
+        (
+            self.mantissa as i128 * other.mantissa as i128,
+            self.precision as u32 + other.precision as u32,
+        )
+    }
+
+    // Rounds off the digits beyond `MAX_PRECISION` and the ones an i64 has no room for,
+    // None if even the integer part doesn't fit
+    fn fit(mut mantissa: i128, mut precision: u32) -> Option<Self> {
+        if precision > Self::MAX_PRECISION as u32 {
+            mantissa = round(mantissa, precision - Self::MAX_PRECISION as u32);
+            precision = Self::MAX_PRECISION as u32;
+        }
+        loop {
+            if let Ok(fitting) = mantissa.try_into() {
+                return Some(DecimalValue {
+                    mantissa: fitting,
+                    precision: precision as u8,
+                });
+            }
+            if precision == 0 {
+                return None;
+            }
+            mantissa = round(mantissa, 1);
+            precision -= 1;
+        }
+    }
+
+    // What a result out of range saturates to
+    fn saturated(mantissa: i128, precision: u32) -> Self {
+        DecimalValue {
+            mantissa: if mantissa < 0 { i64::MIN } else { i64::MAX },
+            precision: precision.min(Self::MAX_PRECISION as u32) as u8,
+        }
+    }
+}
+
+// `mantissa` without its last `digits`, rounded half away from zero
+fn round(mantissa: i128, digits: u32) -> i128 {
+    // dividing by 10^38 already leaves nothing
+    let divisor = match 10_i128.checked_pow(digits) {
+        Some(divisor) => divisor,
+        None => return 0,
+    };
+    let rounded = (mantissa.unsigned_abs() + divisor as u128 / 2) / divisor as u128;
+    if mantissa < 0 {
+        -(rounded as i128)
+    } else {
+        rounded as i128
+    }
+}
+
+impl fmt::Display for DecimalValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let precision = self.precision as usize;
+        if precision == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        // at least one digit before the point
+        let digits = format!("{:0>1$}", digits, precision + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+/// Reads plain decimal numbers like `-12345.678`, keeping all their digits.
+impl FromStr for DecimalValue {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (negative, unsigned) = match value.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let all_digits = |part: &str| part.bytes().all(|digit| digit.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !all_digits(integer)
+            || !all_digits(fraction)
+        {
+            return Err(ParseError::Format);
+        }
+        if fraction.len() > DecimalValue::MAX_PRECISION as usize {
+            return Err(ParseError::OutOfRange);
+        }
+        // only digits are left, so it can only fail on overflow. Signed, i64::MIN having no
+        // positive counterpart.
+        let sign = if negative { "-" } else { "" };
+        let mantissa: i64 = format!("{}{}{}", sign, integer, fraction)
+            .parse()
+            .map_err(|_| ParseError::OutOfRange)?;
+        Ok(DecimalValue {
+            mantissa,
+            precision: fraction.len() as u8,
+        })
+    }
+}
+
+impl PartialEq for DecimalValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DecimalValue {}
+
+impl PartialOrd for DecimalValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecimalValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let precision = self.precision.max(other.precision);
+        self.scaled(precision).cmp(&other.scaled(precision))
+    }
+}
+
+impl Add for DecimalValue {
+    type Output = DecimalValue;
+
+    fn add(self, other: Self) -> Self::Output {
+        let (sum, precision) = self.sum(other, 1);
+        DecimalValue::fit(sum, precision).unwrap_or_else(|| DecimalValue::saturated(sum, precision))
+    }
+}
+
+impl Sub for DecimalValue {
+    type Output = DecimalValue;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let (difference, precision) = self.sum(other, -1);
+        DecimalValue::fit(difference, precision)
+            .unwrap_or_else(|| DecimalValue::saturated(difference, precision))
+    }
+}
+
+impl Mul for DecimalValue {
+    type Output = DecimalValue;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let (product, precision) = self.product(other);
+        DecimalValue::fit(product, precision)
+            .unwrap_or_else(|| DecimalValue::saturated(product, precision))
+    }
+}
+
+impl Neg for DecimalValue {
+    type Output = DecimalValue;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().unwrap_or(DecimalValue {
+            mantissa: i64::MAX,
+            precision: self.precision,
+        })
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+impl serde::Serialize for DecimalValue {
+    // As a string, a JSON number would lose trailing zeros and digits
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+impl<'de> serde::Deserialize<'de> for DecimalValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct DecimalVisitor;
+
+        impl serde::de::Visitor<'_> for DecimalVisitor {
+            type Value = DecimalValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a decimal number or a string holding one")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<DecimalValue, E> {
+                value
+                    .parse()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<DecimalValue, E> {
+                Ok(DecimalValue {
+                    mantissa: value,
+                    precision: 0,
+                })
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<DecimalValue, E> {
+                let mantissa = value
+                    .try_into()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))?;
+                Ok(DecimalValue {
+                    mantissa,
+                    precision: 0,
+                })
+            }
+
+            // The shortest representation reading back as the same float
+            fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<DecimalValue, E> {
+                self.visit_str(&value.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::backend;
+use ffi::value_classes::value_id::ValueID as ExternValueID;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// What a `ValueID` reads from OpenZWave besides its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ValueMetadata {
+    pub label: String,
+    pub units: String,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CACHE: RwLock<BTreeMap<(u32, u64), Arc<ValueMetadata>>> = RwLock::new(BTreeMap::new());
+
+/// Keeps the label and units of every value once read, rather than asking OpenZWave again
+/// for each notification. An entry is dropped when its value is removed, or relabeled through
+/// `ValueID::set_label` and `set_units`; disabling the cache empties it.
+pub fn set_metadata_cache(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        CACHE.write().unwrap().clear();
+    }
+}
+
+fn cache_key(vid: &ExternValueID) -> (u32, u64) {
+    (vid.home_id, (vid.id1 as u64) << 32 | vid.id as u64)
+}
+
+pub(crate) fn get(vid: &ExternValueID) -> Arc<ValueMetadata> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return read(vid);
+    }
+    if let Some(metadata) = CACHE.read().unwrap().get(&cache_key(vid)) {
+        return metadata.clone();
+    }
+    let metadata = read(vid);
+    CACHE.write().unwrap().insert(cache_key(vid), metadata.clone());
+    metadata
+}
+
+pub(crate) fn forget(vid: &ExternValueID) {
+    if ENABLED.load(Ordering::Relaxed) {
+        CACHE.write().unwrap().remove(&cache_key(vid));
+    }
+}
+
+fn read(vid: &ExternValueID) -> Arc<ValueMetadata> {
+    let backend = backend::get(vid.home_id);
+    Arc::new(ValueMetadata {
+        label: backend.get_value_label(vid),
+        units: backend.get_value_units(vid),
+    })
+}
@@ -1 +1,6 @@
+mod decimal;
+mod metadata;
 pub mod value_id;
+mod value_id_key;
+pub(crate) mod confirm;
+pub(crate) mod validation;
@@ -0,0 +1,175 @@
+use super::value_id::{
+    BitSetValue, Schedule, SwitchPoint, ValueContent, ValueID, ValueType,
+};
+use crate::error::{Error, GetSetError, Result as ZWaveResult};
+
+impl ValueID {
+    /// Writes `content` with the setter matching its type.
+    ///
+    /// The content must have the type of the value. Numbers must be within
+    /// `get_min()..=get_max()` when the value has a range, and list selections among the items
+    /// of the list. Read only values are refused.
+    pub fn set(&self, content: ValueContent) -> ZWaveResult<()> {
+        if self.is_read_only() {
+            return Err(Error::SetError(GetSetError::ReadOnly));
+        }
+        if content.value_type() != self.get_type() {
+            return Err(Error::SetError(GetSetError::WrongType));
+        }
+        self.check_range(&content)?;
+
+        match content {
+            ValueContent::Bool(value) | ValueContent::Button(value) => self.set_bool(value),
+            ValueContent::Byte(value) => self.set_byte(value),
+            ValueContent::Decimal(value) => self.set_decimal(value),
+            ValueContent::Int(value) => self.set_int(value),
+            ValueContent::Short(value) => self.set_short(value),
+            ValueContent::String(value) => self.set_string(&value),
+            ValueContent::List(list) => {
+                if !self.as_list()?.values()?.contains(&list.selected_value) {
+                    return Err(Error::SetError(GetSetError::NotAListItem));
+                }
+                self.set_list_selection_value(list.selected_value)
+            }
+            ValueContent::Schedule(schedule) => self.set_schedule(&schedule),
+            ValueContent::Raw(bytes) => self.set_raw(&bytes),
+            ValueContent::BitSet(bitset) => self.set_bitset(&bitset),
+            ValueContent::Unknown => Err(Error::SetError(GetSetError::WrongType)),
+        }
+    }
+
+    /// Parses `value` according to the type of the value, then writes it like `set`.
+    ///
+    /// Lists take the label of an item, raw values hexadecimal bytes, schedules comma separated
+    /// `HH:MM setback` switch points and bitsets the comma separated positions of the set bits.
+    pub fn set_from_str(&self, value: &str) -> ZWaveResult<()> {
+        let content = self.parse(value.trim())?;
+        self.set(content)
+    }
+
+    fn parse(&self, value: &str) -> ZWaveResult<ValueContent> {
+        let parse_error = || Error::SetError(GetSetError::ParseError);
+        match self.get_type() {
+            ValueType::Bool => parse_bool(value).map(ValueContent::Bool).ok_or_else(parse_error),
+            ValueType::Button => {
+                parse_bool(value).map(ValueContent::Button).ok_or_else(parse_error)
+            }
+            ValueType::Byte => parse_integer(value, u8::MIN as i32, u8::MAX as i32)
+                .map(|val| ValueContent::Byte(val as u8)),
+            ValueType::Short => parse_integer(value, i16::MIN as i32, i16::MAX as i32)
+                .map(|val| ValueContent::Short(val as i16)),
+            ValueType::Int => parse_integer(value, i32::MIN, i32::MAX).map(ValueContent::Int),
+            ValueType::Decimal => {
+                value.parse().map(ValueContent::Decimal).map_err(|_| parse_error())
+            }
+            ValueType::String => {
+                if value.contains('\0') {
+                    return Err(Error::SetError(GetSetError::InvalidString));
+                }
+                Ok(ValueContent::String(value.to_string()))
+            }
+            ValueType::List => {
+                let list = self.as_list()?.content()?;
+                let item = list
+                    .item_by_label(value)
+                    .ok_or(Error::SetError(GetSetError::NotAListItem))?;
+                list.with_selection(item.value)
+                    .map(ValueContent::List)
+                    .ok_or(Error::SetError(GetSetError::NotAListItem))
+            }
+            ValueType::Schedule => {
+                parse_schedule(value).map(ValueContent::Schedule).ok_or_else(parse_error)
+            }
+            ValueType::Raw => parse_hex(value).map(ValueContent::Raw).ok_or_else(parse_error),
+            ValueType::BitSet => self.parse_bitset(value).map(ValueContent::BitSet),
+            ValueType::Unknown => Err(Error::SetError(GetSetError::WrongType)),
+        }
+    }
+
+    // The current bitset with only the listed positions set
+    fn parse_bitset(&self, value: &str) -> ZWaveResult<BitSetValue> {
+        let mut bitset = self.as_bitset().map_err(|_| Error::SetError(GetSetError::WrongType))?;
+        let positions = value
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(str::trim)
+            .filter(|position| !position.is_empty())
+            .map(|position| position.parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| Error::SetError(GetSetError::ParseError))?;
+        if positions.iter().any(|position| bitset.bit(*position).is_none()) {
+            return Err(Error::InvalidParameter("position", "set_from_str"));
+        }
+        for bit in bitset.bits.iter_mut() {
+            bit.value = positions.contains(&bit.position);
+        }
+        Ok(bitset)
+    }
+
+    fn check_range(&self, content: &ValueContent) -> ZWaveResult<()> {
+        let value = match content {
+            ValueContent::Byte(val) => *val as f64,
+            ValueContent::Short(val) => *val as f64,
+            ValueContent::Int(val) => *val as f64,
+            ValueContent::Decimal(val) => val.to_f64(),
+            _ => return Ok(()),
+        };
+        let (min, max) = (self.get_min(), self.get_max());
+        // OpenZWave leaves both at 0 for values without a range
+        if min < max && (value < min as f64 || value > max as f64) {
+            return Err(Error::SetError(GetSetError::OutOfRange { min, max }));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// Numbers that don't fit the type are out of its range rather than unreadable
+fn parse_integer(value: &str, min: i32, max: i32) -> ZWaveResult<i32> {
+    let val: i64 = value
+        .parse()
+        .map_err(|_| Error::SetError(GetSetError::ParseError))?;
+    if val < min as i64 || val > max as i64 {
+        return Err(Error::SetError(GetSetError::OutOfRange { min, max }));
+    }
+    Ok(val as i32)
+}
+
+// "06:30 -20, 22:00 0", like ValueContent::Schedule is displayed
+fn parse_schedule(value: &str) -> Option<Schedule> {
+    let switch_points = value
+        .trim_matches('"')
+        .split(',')
+        .map(str::trim)
+        .filter(|point| !point.is_empty())
+        .map(|point| {
+            let (time, setback) = point.split_once(' ')?;
+            let (hours, minutes) = time.split_once(':')?;
+            Some(SwitchPoint::new(
+                hours.parse().ok()?,
+                minutes.parse().ok()?,
+                setback.trim().parse().ok()?,
+            ))
+        })
+        .collect::<Option<Vec<SwitchPoint>>>()?;
+    Some(Schedule::new(switch_points))
+}
+
+pub(crate) fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    let digits = value.trim_start_matches("0x");
+    if !digits.len().is_multiple_of(2) || !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
@@ -1,56 +1,235 @@
+use crate::backend;
 use crate::error::{Error, GetSetError, Result as ZWaveResult};
-use ffi::manager as extern_manager;
 use ffi::utils::res_to_result;
 use ffi::value_classes::value_id as extern_value_id;
-use libc::{c_char, c_void};
 #[cfg(feature = "serde_serialization")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::convert::TryInto;
-use std::ffi::CString;
+use serde::Serializer;
 use std::fmt;
-use std::ptr;
+use std::sync::{Arc, OnceLock};
 
+pub use super::decimal::DecimalValue;
+pub use super::metadata::set_metadata_cache;
+pub use super::value_id_key::ValueIdKey;
 pub use ffi::value_classes::value_id::{ValueGenre, ValueType};
 pub use extern_value_id::ValueID as ExternValueID;
 
-// Helper to have a correct representation with a fixed precision
-#[derive(Debug, Clone)]
-pub struct DecimalValue {
-    pub value: f32,
-    pub precision: u8,
+/// One of the choices of a list value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListItem {
+    pub label: String,
+    pub value: i32,
 }
 
-#[cfg(feature = "serde_serialization")]
-#[allow(unused)]
-fn deserialize_decimal_value<'de, D>(deserializer: D) -> Result<DecimalValue, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s: &str = Deserialize::deserialize(deserializer)?;
-    let value: f32 = serde_json::from_str(s).unwrap_or(0_f32);
-
-    // Quick & ugly hack for the precision...
-    let temp = format!("{}", value.fract());
-    let precision = temp.split('.')
-        .collect::<Vec<&str>>()
-        .last()
-        .unwrap_or(&"")
-        .find('0')
-        .unwrap_or(0);
-
-    Ok(DecimalValue::from_f32(value, precision as u8))
+/// The selected item of a list value, with all the allowed items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListValue {
+    pub selected: String,
+    pub selected_value: i32,
+    pub items: Vec<ListItem>,
+}
+
+impl ListValue {
+    /// `selected_value` is the value of the selected item, not its index.
+    pub fn new<S: Into<String>, I: IntoIterator<Item = (S, i32)>>(items: I, selected_value: i32) -> Self {
+        let items: Vec<ListItem> = items
+            .into_iter()
+            .map(|(label, value)| ListItem { label: label.into(), value })
+            .collect();
+        let selected = items
+            .iter()
+            .find(|item| item.value == selected_value)
+            .map_or_else(String::new, |item| item.label.clone());
+        ListValue {
+            selected,
+            selected_value,
+            items,
+        }
+    }
+
+    pub fn item_by_label(&self, label: &str) -> Option<&ListItem> {
+        self.items.iter().find(|item| item.label == label)
+    }
+
+    pub fn item_by_value(&self, value: i32) -> Option<&ListItem> {
+        self.items.iter().find(|item| item.value == value)
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.items.iter().position(|item| item.value == self.selected_value)
+    }
+
+    /// The same list with the item of this value selected, if there is one.
+    pub fn with_selection(&self, value: i32) -> Option<ListValue> {
+        self.item_by_value(value).map(|item| ListValue {
+            selected: item.label.clone(),
+            selected_value: item.value,
+            items: self.items.clone(),
+        })
+    }
+}
+
+/// A change of the setpoint at a time of day.
+///
+/// `setback` is in tenths of degree relative to the comfort setpoint, from -128 to 120.
+/// 121 means frost protection, 122 energy saving and 127 unused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwitchPoint {
+    pub hours: u8,
+    pub minutes: u8,
+    pub setback: i8,
+}
+
+impl SwitchPoint {
+    pub fn new(hours: u8, minutes: u8, setback: i8) -> Self {
+        SwitchPoint {
+            hours,
+            minutes,
+            setback,
+        }
+    }
+}
+
+impl fmt::Display for SwitchPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02} {}", self.hours, self.minutes, self.setback)
+    }
+}
+
+/// The switch points of a ClimateControlSchedule day, ordered by time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schedule {
+    pub switch_points: Vec<SwitchPoint>,
+}
+
+impl Schedule {
+    pub fn new(mut switch_points: Vec<SwitchPoint>) -> Self {
+        switch_points.sort();
+        Schedule { switch_points }
+    }
+}
+
+/// One bit of a bitset value. Positions start at 1, like in OpenZWave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitSetBit {
+    pub position: u8,
+    pub label: String,
+    pub value: bool,
+}
+
+impl BitSetBit {
+    // The bit in a mask, none for positions a u32 has no room for
+    fn flag(&self) -> u32 {
+        (self.position as u32)
+            .checked_sub(1)
+            .and_then(|shift| 1_u32.checked_shl(shift))
+            .unwrap_or(0)
+    }
+}
+
+/// The bits a device uses in a bitset value, ordered by position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitSetValue {
+    /// Size of the bitset in bytes
+    pub size: u8,
+    /// The bits in use, bit 0 standing for position 1
+    pub mask: u32,
+    pub bits: Vec<BitSetBit>,
+}
+
+impl BitSetValue {
+    /// `bits` are `(position, label, value)`, the mask is made of their positions. None if a
+    /// position is outside of the `size` bytes, or beyond the 32 bits a mask holds.
+    pub fn new<S: Into<String>, I: IntoIterator<Item = (u8, S, bool)>>(
+        size: u8,
+        bits: I,
+    ) -> Option<Self> {
+        let last = size.saturating_mul(8).min(32);
+        let mut bits: Vec<BitSetBit> = bits
+            .into_iter()
+            .map(|(position, label, value)| BitSetBit { position, label: label.into(), value })
+            .collect();
+        if bits.iter().any(|bit| bit.position == 0 || bit.position > last) {
+            return None;
+        }
+        bits.sort_by_key(|bit| bit.position);
+        let mask = bits.iter().fold(0, |mask, bit| mask | bit.flag());
+        Some(BitSetValue { size, mask, bits })
+    }
+
+    pub fn bit(&self, position: u8) -> Option<&BitSetBit> {
+        self.bits.iter().find(|bit| bit.position == position)
+    }
+
+    pub fn bit_by_label(&self, label: &str) -> Option<&BitSetBit> {
+        self.bits.iter().find(|bit| bit.label == label)
+    }
+
+    pub fn get(&self, position: u8) -> Option<bool> {
+        self.bit(position).map(|bit| bit.value)
+    }
+
+    /// The bits packed in an integer, position 1 being the lowest bit. Bits at positions an
+    /// integer has no room for are left out.
+    pub fn to_u32(&self) -> u32 {
+        self.bits
+            .iter()
+            .filter(|bit| bit.value)
+            .fold(0, |value, bit| value | bit.flag())
+    }
+
+    /// The same bitset with the bit at `position` changed, if the device uses it.
+    pub fn with_bit(&self, position: u8, value: bool) -> Option<BitSetValue> {
+        self.bit(position)?;
+        let mut bitset = self.clone();
+        for bit in bitset.bits.iter_mut().filter(|bit| bit.position == position) {
+            bit.value = value;
+        }
+        Some(bitset)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
 }
 
 #[cfg(feature = "serde_serialization")]
-impl Serialize for DecimalValue {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_f32(self.to_f32())
+impl ValueContent {
+    /// Reads back a serialized content of a value of type `value_type`, since the JSON
+    /// doesn't tell a byte from an int.
+    pub fn from_json(value_type: ValueType, json: serde_json::Value) -> Option<ValueContent> {
+        use serde_json::from_value;
+        let content = match value_type {
+            ValueType::Bool => ValueContent::Bool(from_value(json).ok()?),
+            ValueType::Byte => ValueContent::Byte(from_value(json).ok()?),
+            ValueType::Decimal => ValueContent::Decimal(from_value(json).ok()?),
+            ValueType::Int => ValueContent::Int(from_value(json).ok()?),
+            ValueType::List => ValueContent::List(from_value(json).ok()?),
+            ValueType::Schedule => ValueContent::Schedule(from_value(json).ok()?),
+            ValueType::Short => ValueContent::Short(from_value(json).ok()?),
+            ValueType::String => ValueContent::String(from_value(json).ok()?),
+            ValueType::Button => ValueContent::Button(from_value(json).ok()?),
+            ValueType::Raw => ValueContent::Raw(parse_hex(json.as_str()?)?),
+            ValueType::BitSet => ValueContent::BitSet(from_value(json).ok()?),
+            ValueType::Unknown => ValueContent::Unknown,
+        };
+        Some(content)
     }
 }
 
+#[cfg(feature = "serde_serialization")]
+fn serialize_hex<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&to_hex(bytes))
+}
+
 // Rustified ValueType
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_serialization", derive(serde::Serialize))]
@@ -58,36 +237,38 @@ impl Serialize for DecimalValue {
 pub enum ValueContent {
     Bool(bool),
     Byte(u8),
-    #[cfg_attr(feature = "serde_serialization", serde(deserialize_with="deserialize_decimal_value"))]
     Decimal(DecimalValue),
     Int(i32),
-    List(String), //< just supported as a string right now
-    Schedule,     //< ? unsupported yet
+    List(ListValue),
+    Schedule(Schedule),
     Short(i16),
     String(String),
     Button(bool),
-    Raw, //< ? Vec<u8>? unsupported yet
+    #[cfg_attr(feature = "serde_serialization", serde(serialize_with = "serialize_hex"))]
+    Raw(Vec<u8>),
+    BitSet(BitSetValue),
     //
     Unknown, //< null
 }
 
-impl ToString for DecimalValue {
-    fn to_string(&self) -> String {
-        format!("{:.1$}", self.value, self.precision as usize)
-    }
-}
-
-impl DecimalValue {
-    pub fn from_f32(value: f32, precision: u8) -> Self {
-        Self {
-            value,
-            precision,
+impl ValueContent {
+    /// The type of the values holding such a content.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Bool(_) => ValueType::Bool,
+            Self::Byte(_) => ValueType::Byte,
+            Self::Decimal(_) => ValueType::Decimal,
+            Self::Int(_) => ValueType::Int,
+            Self::List(_) => ValueType::List,
+            Self::Schedule(_) => ValueType::Schedule,
+            Self::Short(_) => ValueType::Short,
+            Self::String(_) => ValueType::String,
+            Self::Button(_) => ValueType::Button,
+            Self::Raw(_) => ValueType::Raw,
+            Self::BitSet(_) => ValueType::BitSet,
+            Self::Unknown => ValueType::Unknown,
         }
     }
-
-    pub fn to_f32(&self) -> f32 {
-        self.value
-    }
 }
 
 impl fmt::Display for ValueContent {
@@ -97,9 +278,30 @@ impl fmt::Display for ValueContent {
             Self::Byte(val) => write!(f, "{}", val),
             Self::Int(val) => write!(f, "{}", val),
             Self::Short(val) => write!(f, "{}", val),
-            Self::String(val) | Self::List(val) => write!(f, "\"{}\"", val),
-            Self::Decimal(val) => write!(f, "{}", val.to_string()),
-            Self::Unknown | Self::Schedule | Self::Raw => write!(f, "null"),
+            Self::String(val) => write!(f, "\"{}\"", val),
+            Self::List(val) => write!(f, "\"{}\"", val.selected),
+            Self::Decimal(val) => write!(f, "{}", val),
+            Self::Schedule(val) => write!(
+                f,
+                "\"{}\"",
+                val.switch_points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Raw(val) => write!(f, "\"{}\"", to_hex(val)),
+            Self::BitSet(val) => write!(
+                f,
+                "[{}]",
+                val.bits
+                    .iter()
+                    .filter(|bit| bit.value)
+                    .map(|bit| bit.position.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Unknown => write!(f, "null"),
         }
     }
 }
@@ -203,9 +405,9 @@ impl fmt::Display for CommandClass {
 
 use crate::controller::Controller;
 use crate::node::Node;
-use ffi::utils::{
-    recover_string, recover_vec, rust_string_creator, rust_string_vec_creator, rust_vec_creator,
-};
+use super::metadata::{self, ValueMetadata};
+#[cfg(feature = "serde_serialization")]
+use super::validation::parse_hex;
 
 pub struct ValueList {
     id: extern_value_id::ValueID,
@@ -213,78 +415,48 @@ pub struct ValueList {
 
 impl ValueList {
     pub fn selection_as_string(&self) -> ZWaveResult<String> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let mut raw_string: *mut c_char = ptr::null_mut();
-
-        let res = unsafe {
-            extern_manager::get_value_list_selection_as_string(
-                manager_ptr,
-                &self.id,
-                &mut raw_string,
-                rust_string_creator,
-            )
-        };
-
-        if res {
-            Ok(recover_string(raw_string))
-        } else {
-            Err(Error::GetError(GetSetError::APIError(
+        backend::get(self.id.home_id)
+            .get_value_list_selection_as_string(&self.id)
+            .ok_or(Error::GetError(GetSetError::APIError(
                 "ValueList::selection_as_string",
             )))
-        }
     }
 
     pub fn selection_as_int(&self) -> ZWaveResult<i32> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let mut val: i32 = 0;
-        let res = unsafe {
-            extern_manager::get_value_list_selection_as_int(manager_ptr, &self.id, &mut val)
-        };
-        if res {
-            Ok(val)
-        } else {
-            Err(Error::GetError(GetSetError::APIError(
+        backend::get(self.id.home_id)
+            .get_value_list_selection_as_int(&self.id)
+            .ok_or(Error::GetError(GetSetError::APIError(
                 "ValueList::selection_as_int",
             )))
-        }
     }
 
     pub fn items(&self) -> ZWaveResult<Box<Vec<String>>> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let mut c_items: *mut Vec<String> = ptr::null_mut();
-        let c_items_void_ptr = &mut c_items as *mut *mut _ as *mut *mut c_void;
-        let res = unsafe {
-            extern_manager::get_value_list_items(
-                manager_ptr,
-                &self.id,
-                c_items_void_ptr,
-                rust_string_vec_creator,
-            )
-        };
-        if res {
-            Ok(recover_vec(c_items))
-        } else {
-            Err(Error::GetError(GetSetError::APIError("ValueList::items")))
-        }
+        backend::get(self.id.home_id)
+            .get_value_list_items(&self.id)
+            .map(Box::new)
+            .ok_or(Error::GetError(GetSetError::APIError("ValueList::items")))
     }
 
     pub fn values(&self) -> ZWaveResult<Box<Vec<i32>>> {
-        let manager_ptr = unsafe { extern_manager::get() };
-        let mut c_values: *mut Vec<i32> = ptr::null_mut();
-        let c_values_void_ptr = &mut c_values as *mut *mut _ as *mut *mut c_void;
-        let res = unsafe {
-            extern_manager::get_value_list_values(
-                manager_ptr,
-                &self.id,
-                c_values_void_ptr,
-                rust_vec_creator::<i32>,
-            )
-        };
-        if res {
-            Ok(recover_vec(c_values))
-        } else {
-            Err(Error::GetError(GetSetError::APIError("ValueList::values")))
-        }
+        backend::get(self.id.home_id)
+            .get_value_list_values(&self.id)
+            .map(Box::new)
+            .ok_or(Error::GetError(GetSetError::APIError("ValueList::values")))
+    }
+
+    /// The selection and all the items at once.
+    pub fn content(&self) -> ZWaveResult<ListValue> {
+        let items = self.items()?;
+        let values = self.values()?;
+        Ok(ListValue {
+            selected: self.selection_as_string()?,
+            selected_value: self.selection_as_int()?,
+            items: items
+                .into_iter()
+                .zip(*values)
+                .map(|(label, value)| ListItem { label, value })
+                .collect(),
+        })
     }
 }
 
@@ -303,10 +475,10 @@ impl fmt::Debug for ValueList {
 pub struct ValueID {
     vid: extern_value_id::ValueID,
     genre: Option<ValueGenre>,
-    label: String,
     value_type: ValueType,
-    value: ValueContent,
-    units: String,
+    // Both read from OpenZWave on first use only
+    metadata: OnceLock<Arc<ValueMetadata>>,
+    value: OnceLock<ValueContent>,
 }
 
 // FTR: big id is (uint64) (((uint64) m_id1 << 32) | m_id);
@@ -322,11 +494,6 @@ fn get_id1_from_id(id: u64) -> u32 {
     (id >> 32) as u32
 }
 
-fn get_genre(id: u32) -> Option<ValueGenre> {
-    let genre: u8 = ((id & 0x00c00000) >> 22) as u8;
-    genre.try_into().ok()
-}
-
 fn create_vid(home_id: u32, id: u64) -> extern_value_id::ValueID {
     extern_value_id::ValueID {
         home_id,
@@ -335,144 +502,91 @@ fn create_vid(home_id: u32, id: u64) -> extern_value_id::ValueID {
     }
 }
 
-fn get_value_as_string(id: &extern_value_id::ValueID) -> ZWaveResult<String> {
-    // The underlying C++ lib returns a value for any type.
-    let manager_ptr = unsafe { extern_manager::get() };
-    let mut raw_string: *mut c_char = ptr::null_mut();
-
-    let res = unsafe {
-        extern_manager::get_value_as_string(manager_ptr, id, &mut raw_string, rust_string_creator)
-    };
-
-    if res {
-        Ok(recover_string(raw_string))
-    } else {
-        Err(Error::GetError(GetSetError::APIError("as_string")))
-    }
-}
-
-macro_rules! get_low_level_value {
-    ($($typ:ty, $default:expr, $target:ident, $funci:ident, $manager_ptr:ident, $id:ident),+) => {
-        $(
-            {
-                let mut val: $typ = $default;
-                let res = unsafe {
-                    extern_manager::$funci($manager_ptr, $id, &mut val)
-                };
-                if res {
-                    Some(ValueContent::$target(val))
-                } else {
-                    None
-                }
-            }
-        )*
-    };
-}
-
-macro_rules! get_low_level_value_primitive {
-    ($($typ:ty, $default:expr, $funci:ident, $manager_ptr:ident, $id:ident),+) => {
-        $(
-            {
-                let mut val: $typ = $default;
-                // FIXME: Simplyfy as soon as bool.then_some() is no more nightly
-                if unsafe {
-                    extern_manager::$funci($manager_ptr, $id, &mut val)
-                } {
-                    Some(val)
-                } else {
-                    None
-                }
-            }
-        )*
-    };
-}
-
 fn extract_value(id: &extern_value_id::ValueID, value_type: ValueType) -> Option<ValueContent> {
-    let manager_ptr = unsafe { extern_manager::get() };
+    let backend = backend::get(id.home_id);
 
     match value_type {
-        ValueType::Bool => {
-            get_low_level_value!(bool, false, Bool, get_value_as_bool, manager_ptr, id)
-        }
-        ValueType::Byte => {
-            get_low_level_value!(u8, 0, Byte, get_value_as_byte, manager_ptr, id)
-        }
+        ValueType::Bool => backend.get_value_as_bool(id).map(ValueContent::Bool),
+        ValueType::Byte => backend.get_value_as_byte(id).map(ValueContent::Byte),
         ValueType::Decimal => {
-            if let Some(value) =
-                get_low_level_value_primitive!(f32, 0.00, get_value_as_float, manager_ptr, id)
-            {
-                let precision: u8 = get_low_level_value_primitive!(
-                    u8,
-                    2,
-                    get_value_float_precision,
-                    manager_ptr,
-                    id
-                )
-                .unwrap_or(0);
-                return Some(ValueContent::Decimal(DecimalValue::from_f32(
-                    value, precision,
-                )));
+            // The string holds all the digits, the float only about 7 of them
+            if let Some(value) = backend.get_value_as_string(id).and_then(|val| val.parse().ok()) {
+                return Some(ValueContent::Decimal(value));
             }
-            None
-        }
-        ValueType::Int => {
-            get_low_level_value!(i32, 0, Int, get_value_as_int, manager_ptr, id)
-        }
-        ValueType::List => {
-            let nearly_unsupported = ValueList { id: id.clone() };
-            Some(ValueContent::String(
-                nearly_unsupported
-                    .selection_as_string()
-                    .unwrap_or("".into()),
-            ))
-        }
-        ValueType::Short => {
-            get_low_level_value!(i16, 0, Short, get_value_as_short, manager_ptr, id)
+            let value = backend.get_value_as_float(id)?;
+            let precision: u8 = backend.get_value_float_precision(id).unwrap_or(0);
+            DecimalValue::from_f32(value, precision).map(ValueContent::Decimal)
         }
+        ValueType::Int => backend.get_value_as_int(id).map(ValueContent::Int),
+        ValueType::List => ValueList { id: *id }.content().ok().map(ValueContent::List),
+        ValueType::Short => backend.get_value_as_short(id).map(ValueContent::Short),
         ValueType::String => Some(ValueContent::String(
-            get_value_as_string(id).unwrap_or("".into()),
+            backend.get_value_as_string(id).unwrap_or("".into()),
         )),
-        ValueType::Button => {
-            get_low_level_value!(bool, false, Button, get_value_as_bool, manager_ptr, id)
+        ValueType::Button => backend.get_value_as_bool(id).map(ValueContent::Button),
+        ValueType::Schedule => {
+            let switch_points = (0..backend.get_num_switch_points(id))
+                .filter_map(|idx| backend.get_switch_point(id, idx))
+                .collect();
+            Some(ValueContent::Schedule(Schedule::new(switch_points)))
         }
-        // FIXME: List(partially), Schedule and Raw are still unsupported here as I have currently no idea
-        //        of what exactly they should carry and I have no usage for that atm so I won't
-        //        invest time in this for this moment.
-        _ => None,
+        ValueType::Raw => backend.get_value_as_raw(id).map(ValueContent::Raw),
+        ValueType::BitSet => {
+            let size = backend.get_value_bitset_size(id)?;
+            let mask = backend.get_value_bitmask(id)?;
+            let bits = (1..=size.saturating_mul(8).min(32))
+                .filter(|position| mask & 1 << (position - 1) != 0)
+                .filter_map(|position| {
+                    backend.get_value_as_bitset(id, position).map(|value| BitSetBit {
+                        position,
+                        label: backend.get_value_bit_label(id, position),
+                        value,
+                    })
+                })
+                .collect();
+            Some(ValueContent::BitSet(BitSetValue { size, mask, bits }))
+        }
+        ValueType::Unknown => None,
     }
 }
 
 impl ValueID {
+    /// Decodes the id only: label, units and value are read from OpenZWave when first asked
+    /// for, so they are the ones of that moment. Call `snapshot()` early to keep older ones.
     pub fn from_packed_id(home_id: u32, id: u64) -> ValueID {
-        let vid = create_vid(home_id, id);
-
-        let label: String = recover_string(unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_label(manager_ptr, &vid, rust_string_creator)
-        });
-
-        let temp_type: Option<ValueType> = (get_id0_from_id(id) as u8 & 0x0F).try_into().ok();
-        let value_type = match temp_type {
-            Some(value) => value,
-            None => ValueType::Unknown,
-        };
+        let key = ValueIdKey::from_packed_id(home_id, id);
+        ValueID {
+            vid: create_vid(home_id, id),
+            genre: key.genre,
+            value_type: key.value_type,
+            metadata: OnceLock::new(),
+            value: OnceLock::new(),
+        }
+    }
 
-        let units = recover_string(unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_units(manager_ptr, &vid, rust_string_creator)
-        });
+    // The value is already gone from OpenZWave: only what the id itself holds is known.
+    pub(crate) fn removed(home_id: u32, id: u64) -> ValueID {
+        metadata::forget(&create_vid(home_id, id));
+        ValueID::with_content(home_id, id, String::new(), String::new(), ValueContent::Unknown)
+    }
 
-        let value =
-            extract_value(&vid, value_type).unwrap_or(ValueContent::Unknown);
+    // A value known without asking OpenZWave, e.g. replayed from a recording.
+    pub(crate) fn with_content(
+        home_id: u32,
+        id: u64,
+        label: String,
+        units: String,
+        content: ValueContent,
+    ) -> ValueID {
+        let value_id = ValueID::from_packed_id(home_id, id);
+        let _ = value_id.metadata.set(Arc::new(ValueMetadata { label, units }));
+        let _ = value_id.value.set(content);
+        value_id
+    }
 
-        ValueID {
-            vid,
-            genre: get_genre(get_id0_from_id(id)),
-            label,
-            value_type,
-            value,
-            units,
-        }
+    /// The value of a complete key, e.g. decoded from a packed id.
+    pub fn from_key(key: &ValueIdKey) -> Option<ValueID> {
+        key.packed_id().map(|id| ValueID::from_packed_id(key.home_id, id))
     }
 
     /// Return the big ID from the lib
@@ -480,16 +594,33 @@ impl ValueID {
         (self.vid.id1 as u64) << 32 | (self.vid.id as u64)
     }
 
+    pub fn key(&self) -> ValueIdKey {
+        ValueIdKey::from_packed_id(self.vid.home_id, self.id())
+    }
+
     pub fn label(&self) -> &str {
-        &self.label
+        &self.metadata().label
     }
 
     pub fn value(&self) -> &ValueContent {
-        &self.value
+        self.value.get_or_init(|| {
+            extract_value(&self.vid, self.value_type).unwrap_or(ValueContent::Unknown)
+        })
     }
 
     pub fn units(&self) -> &str {
-        &self.units
+        &self.metadata().units
+    }
+
+    /// Reads the label, units and value now, so this ValueID and its clones keep them.
+    pub fn snapshot(&self) -> &ValueID {
+        self.metadata();
+        self.value();
+        self
+    }
+
+    fn metadata(&self) -> &ValueMetadata {
+        self.metadata.get_or_init(|| metadata::get(&self.vid))
     }
 
     // instance methods
@@ -506,7 +637,7 @@ impl ValueID {
     }
 
     pub fn get_node_id(&self) -> u8 {
-        (self.vid.id >> 24) as u8
+        self.key().node_id
     }
 
     pub fn get_genre(&self) -> Option<ValueGenre> {
@@ -514,7 +645,7 @@ impl ValueID {
     }
 
     pub fn get_command_class_id(&self) -> u8 {
-        ((self.vid.id & 0x003fc000) >> 14) as u8
+        self.key().command_class
     }
 
     pub fn get_command_class(&self) -> Option<CommandClass> {
@@ -522,11 +653,11 @@ impl ValueID {
     }
 
     pub fn get_instance(&self) -> u8 {
-        ((self.vid.id & 0xff0) >> 4) as u8
+        self.key().instance
     }
 
-    pub fn get_index(&self) -> u8 {
-        ((self.vid.id & 0xFFFF0000) >> 16) as u8
+    pub fn get_index(&self) -> u16 {
+        self.key().index
     }
 
     pub fn get_type(&self) -> ValueType {
@@ -537,26 +668,16 @@ impl ValueID {
         &self.vid
     }
 
+    fn backend(&self) -> std::sync::Arc<dyn backend::ZWaveBackend> {
+        backend::get(self.vid.home_id)
+    }
+
     pub fn as_raw(&self) -> ZWaveResult<Box<Vec<u8>>> {
         if self.get_type() == ValueType::Raw {
-            let mut raw_ptr: *mut Vec<u8> = ptr::null_mut();
-            let raw_ptr_c_void = &mut raw_ptr as *mut *mut _ as *mut *mut c_void;
-
-            let manager_ptr = unsafe { extern_manager::get() };
-            let res = unsafe {
-                extern_manager::get_value_as_raw(
-                    manager_ptr,
-                    &self.vid,
-                    raw_ptr_c_void,
-                    rust_vec_creator::<u8>,
-                )
-            };
-
-            if res {
-                Ok(recover_vec(raw_ptr))
-            } else {
-                Err(Error::GetError(GetSetError::APIError("as_raw")))
-            }
+            self.backend()
+                .get_value_as_raw(&self.vid)
+                .map(Box::new)
+                .ok_or(Error::GetError(GetSetError::APIError("as_raw")))
         } else {
             Err(Error::GetError(GetSetError::WrongType))
         }
@@ -576,11 +697,8 @@ impl ValueID {
     pub fn set_bool(&self, value: bool) -> ZWaveResult<()> {
         match self.get_type() {
             ValueType::Bool | ValueType::Button => {
-                let manager_ptr = unsafe { extern_manager::get() };
-                res_to_result(unsafe {
-                    extern_manager::set_value_bool(manager_ptr, &self.vid, value)
-                })
-                .or(Err(Error::SetError(GetSetError::APIError("set_bool"))))
+                res_to_result(self.backend().set_value_bool(&self.vid, value))
+                    .or(Err(Error::SetError(GetSetError::APIError("set_bool"))))
             }
             _ => Err(Error::SetError(GetSetError::WrongType)),
         }
@@ -588,11 +706,8 @@ impl ValueID {
 
     pub fn set_byte(&self, value: u8) -> ZWaveResult<()> {
         if self.get_type() == ValueType::Byte {
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_byte(manager_ptr, &self.vid, value)
-            })
-            .or(Err(Error::SetError(GetSetError::APIError("set_byte"))))
+            res_to_result(self.backend().set_value_byte(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError("set_byte"))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
@@ -600,11 +715,18 @@ impl ValueID {
 
     pub fn set_float(&self, value: f32) -> ZWaveResult<()> {
         if self.get_type() == ValueType::Decimal {
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_float(manager_ptr, &self.vid, value)
-            })
-            .or(Err(Error::SetError(GetSetError::APIError("set_float"))))
+            res_to_result(self.backend().set_value_float(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError("set_float"))))
+        } else {
+            Err(Error::SetError(GetSetError::WrongType))
+        }
+    }
+
+    /// Sets a decimal through its string, which unlike a float keeps every digit.
+    pub fn set_decimal(&self, value: DecimalValue) -> ZWaveResult<()> {
+        if self.get_type() == ValueType::Decimal {
+            res_to_result(self.backend().set_value_string(&self.vid, &value.to_string()))
+                .or(Err(Error::SetError(GetSetError::APIError("set_decimal"))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
@@ -612,11 +734,8 @@ impl ValueID {
 
     pub fn set_int(&self, value: i32) -> ZWaveResult<()> {
         if self.get_type() == ValueType::Int {
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_int(manager_ptr, &self.vid, value)
-            })
-            .or(Err(Error::SetError(GetSetError::APIError("set_int"))))
+            res_to_result(self.backend().set_value_int(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError("set_int"))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
@@ -624,11 +743,8 @@ impl ValueID {
 
     pub fn set_short(&self, value: i16) -> ZWaveResult<()> {
         if self.get_type() == ValueType::Short {
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_short(manager_ptr, &self.vid, value)
-            })
-            .or(Err(Error::SetError(GetSetError::APIError("set_short"))))
+            res_to_result(self.backend().set_value_short(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError("set_short"))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
@@ -636,26 +752,17 @@ impl ValueID {
 
     pub fn set_string(&self, value: &str) -> ZWaveResult<()> {
         // The underlying C++ lib accepts strings for all types
-        let manager_ptr = unsafe { extern_manager::get() };
-        let c_string = CString::new(value)?;
-        res_to_result(unsafe {
-            extern_manager::set_value_string(manager_ptr, &self.vid, c_string.as_ptr())
-        })
-        .or(Err(Error::SetError(GetSetError::APIError("set_string"))))
+        if value.contains('\0') {
+            return Err(Error::SetError(GetSetError::InvalidString));
+        }
+        res_to_result(self.backend().set_value_string(&self.vid, value))
+            .or(Err(Error::SetError(GetSetError::APIError("set_string"))))
     }
 
     pub fn set_raw(&self, value: &Vec<u8>) -> ZWaveResult<()> {
         if self.get_type() == ValueType::Raw && value.len() < 256 {
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_raw(
-                    manager_ptr,
-                    &self.vid,
-                    value.as_ptr(),
-                    value.len() as u8,
-                )
-            })
-            .or(Err(Error::SetError(GetSetError::APIError("set_raw"))))
+            res_to_result(self.backend().set_value_raw(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError("set_raw"))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
@@ -663,111 +770,192 @@ impl ValueID {
 
     pub fn set_list_selection_string(&self, value: &str) -> ZWaveResult<()> {
         if self.get_type() == ValueType::List {
-            let c_string = CString::new(value)?;
-            let manager_ptr = unsafe { extern_manager::get() };
-            res_to_result(unsafe {
-                extern_manager::set_value_list_selection_string(
-                    manager_ptr,
-                    &self.vid,
-                    c_string.as_ptr(),
-                )
-            })
-            .or(Err(Error::SetError(GetSetError::APIError(
-                "set_list_selection_string",
-            ))))
+            if value.contains('\0') {
+                return Err(Error::SetError(GetSetError::InvalidString));
+            }
+            res_to_result(self.backend().set_value_list_selection_string(&self.vid, value))
+                .or(Err(Error::SetError(GetSetError::APIError(
+                    "set_list_selection_string",
+                ))))
         } else {
             Err(Error::SetError(GetSetError::WrongType))
         }
     }
 
+    pub fn as_schedule(&self) -> ZWaveResult<Schedule> {
+        if self.get_type() != ValueType::Schedule {
+            return Err(Error::GetError(GetSetError::WrongType));
+        }
+        match extract_value(&self.vid, ValueType::Schedule) {
+            Some(ValueContent::Schedule(schedule)) => Ok(schedule),
+            _ => Err(Error::GetError(GetSetError::APIError("as_schedule"))),
+        }
+    }
+
+    /// Adds a switch point, or replaces the one at the same time.
+    pub fn set_switch_point(&self, point: SwitchPoint) -> ZWaveResult<()> {
+        self.check_schedule_time(point.hours, point.minutes, "set_switch_point")?;
+        res_to_result(self.backend().set_switch_point(&self.vid, point))
+            .or(Err(Error::SetError(GetSetError::APIError("set_switch_point"))))
+    }
+
+    pub fn remove_switch_point(&self, hours: u8, minutes: u8) -> ZWaveResult<()> {
+        self.check_schedule_time(hours, minutes, "remove_switch_point")?;
+        res_to_result(self.backend().remove_switch_point(&self.vid, hours, minutes))
+            .or(Err(Error::SetError(GetSetError::APIError("remove_switch_point"))))
+    }
+
+    pub fn clear_switch_points(&self) -> ZWaveResult<()> {
+        if self.get_type() != ValueType::Schedule {
+            return Err(Error::SetError(GetSetError::WrongType));
+        }
+        self.backend().clear_switch_points(&self.vid);
+        Ok(())
+    }
+
+    /// Replaces all the switch points.
+    pub fn set_schedule(&self, schedule: &Schedule) -> ZWaveResult<()> {
+        for point in &schedule.switch_points {
+            self.check_schedule_time(point.hours, point.minutes, "set_schedule")?;
+        }
+        self.clear_switch_points()?;
+        schedule
+            .switch_points
+            .iter()
+            .try_for_each(|point| self.set_switch_point(*point))
+    }
+
+    fn check_schedule_time(&self, hours: u8, minutes: u8, method: &'static str) -> ZWaveResult<()> {
+        if self.get_type() != ValueType::Schedule {
+            Err(Error::SetError(GetSetError::WrongType))
+        } else if hours > 23 {
+            Err(Error::InvalidParameter("hours", method))
+        } else if minutes > 59 {
+            Err(Error::InvalidParameter("minutes", method))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn as_bitset(&self) -> ZWaveResult<BitSetValue> {
+        if self.get_type() != ValueType::BitSet {
+            return Err(Error::GetError(GetSetError::WrongType));
+        }
+        match extract_value(&self.vid, ValueType::BitSet) {
+            Some(ValueContent::BitSet(bitset)) => Ok(bitset),
+            _ => Err(Error::GetError(GetSetError::APIError("as_bitset"))),
+        }
+    }
+
+    pub fn get_bit(&self, position: u8) -> ZWaveResult<bool> {
+        self.as_bitset()?
+            .get(position)
+            .ok_or(Error::InvalidParameter("position", "get_bit"))
+    }
+
+    /// Changes one bit, only the ones in the mask of the bitset can be set.
+    pub fn set_bit(&self, position: u8, value: bool) -> ZWaveResult<()> {
+        let bitset = self.as_bitset().map_err(|_| Error::SetError(GetSetError::WrongType))?;
+        if bitset.bit(position).is_none() {
+            return Err(Error::InvalidParameter("position", "set_bit"));
+        }
+        res_to_result(self.backend().set_value_bitset(&self.vid, position, value))
+            .or(Err(Error::SetError(GetSetError::APIError("set_bit"))))
+    }
+
+    /// Sets every bit of `bitset`, one at a time.
+    pub fn set_bitset(&self, bitset: &BitSetValue) -> ZWaveResult<()> {
+        let current = self.as_bitset().map_err(|_| Error::SetError(GetSetError::WrongType))?;
+        if bitset.bits.iter().any(|bit| current.bit(bit.position).is_none()) {
+            return Err(Error::InvalidParameter("bitset", "set_bitset"));
+        }
+        bitset
+            .bits
+            .iter()
+            .try_for_each(|bit| self.set_bit(bit.position, bit.value))
+    }
+
+    /// Selects the item having this value.
+    pub fn set_list_selection_value(&self, value: i32) -> ZWaveResult<()> {
+        let list = self.as_list().map_err(|_| Error::SetError(GetSetError::WrongType))?;
+        if !list.values()?.contains(&value) {
+            return Err(Error::InvalidParameter("value", "set_list_selection_value"));
+        }
+        res_to_result(self.backend().set_value_list_selection_int(&self.vid, value))
+            .or(Err(Error::SetError(GetSetError::APIError(
+                "set_list_selection_value",
+            ))))
+    }
+
+    /// Selects the item at this position in `ValueList::items`.
+    pub fn set_list_selection_index(&self, index: usize) -> ZWaveResult<()> {
+        let list = self.as_list().map_err(|_| Error::SetError(GetSetError::WrongType))?;
+        match list.values()?.get(index) {
+            Some(value) => self.set_list_selection_value(*value),
+            None => Err(Error::InvalidParameter("index", "set_list_selection_index")),
+        }
+    }
+
     pub fn get_label(&self) -> String {
-        recover_string(unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_label(manager_ptr, &self.vid, rust_string_creator)
-        })
+        self.backend().get_value_label(&self.vid)
     }
 
     pub fn set_label(&self, str: &str) -> ZWaveResult<()> {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            let c_string = CString::new(str)?.as_ptr();
-            extern_manager::set_value_label(manager_ptr, &self.vid, c_string);
-            Ok(())
+        if str.contains('\0') {
+            return Err(Error::SetError(GetSetError::InvalidString));
         }
+        self.backend().set_value_label(&self.vid, str);
+        metadata::forget(&self.vid);
+        Ok(())
     }
 
     pub fn get_units(&self) -> String {
-        recover_string(unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_units(manager_ptr, &self.vid, rust_string_creator)
-        })
+        self.backend().get_value_units(&self.vid)
     }
 
     pub fn set_units(&self, str: &str) -> ZWaveResult<()> {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            let c_string = CString::new(str)?.as_ptr();
-            extern_manager::set_value_units(manager_ptr, &self.vid, c_string);
-            Ok(())
+        if str.contains('\0') {
+            return Err(Error::SetError(GetSetError::InvalidString));
         }
+        self.backend().set_value_units(&self.vid, str);
+        metadata::forget(&self.vid);
+        Ok(())
     }
 
     pub fn get_help(&self) -> String {
-        recover_string(unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_help(manager_ptr, &self.vid, rust_string_creator)
-        })
+        self.backend().get_value_help(&self.vid)
     }
 
     pub fn set_help(&self, str: &str) -> ZWaveResult<()> {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            let c_string = CString::new(str)?.as_ptr();
-            extern_manager::set_value_help(manager_ptr, &self.vid, c_string);
-            Ok(())
+        if str.contains('\0') {
+            return Err(Error::SetError(GetSetError::InvalidString));
         }
+        self.backend().set_value_help(&self.vid, str);
+        Ok(())
     }
 
     pub fn get_min(&self) -> i32 {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_min(manager_ptr, &self.vid)
-        }
+        self.backend().get_value_min(&self.vid)
     }
 
     pub fn get_max(&self) -> i32 {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::get_value_max(manager_ptr, &self.vid)
-        }
+        self.backend().get_value_max(&self.vid)
     }
 
     pub fn is_read_only(&self) -> bool {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::is_value_read_only(manager_ptr, &self.vid)
-        }
+        self.backend().is_value_read_only(&self.vid)
     }
 
     pub fn is_write_only(&self) -> bool {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::is_value_write_only(manager_ptr, &self.vid)
-        }
+        self.backend().is_value_write_only(&self.vid)
     }
 
     pub fn is_set(&self) -> bool {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::is_value_set(manager_ptr, &self.vid)
-        }
+        self.backend().is_value_set(&self.vid)
     }
 
     pub fn is_polled(&self) -> bool {
-        unsafe {
-            let manager_ptr = extern_manager::get();
-            extern_manager::is_value_polled(manager_ptr, &self.vid)
-        }
+        self.backend().is_value_polled(&self.vid)
     }
 }
 
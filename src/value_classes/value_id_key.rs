@@ -0,0 +1,185 @@
+use super::value_id::{ValueGenre, ValueType};
+use crate::error::ParseError;
+use std::cmp::Ordering;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// The parts of a value id, as OpenZWave 1.6 packs them in its two words:
+///
+/// - `id`: node (8 bits), genre (2), command class (8), unused (2), instance (8), type (4)
+/// - `id1`: index (16 bits), unused (16)
+///
+/// A value is identified by its home id, node, command class, instance and index; keys
+/// compare, hash and print with those only, as `0xabcd1234:5:0x25:1:0`. Genre and type come
+/// along when the key is decoded from a packed id, but are unknown in keys read from strings.
+#[derive(Debug, Clone, Copy)]
+pub struct ValueIdKey {
+    pub home_id: u32,
+    pub node_id: u8,
+    pub genre: Option<ValueGenre>,
+    pub command_class: u8,
+    pub instance: u8,
+    pub index: u16,
+    pub value_type: ValueType,
+}
+
+impl ValueIdKey {
+    /// A key for the first instance and index 0, to complete with the other setters.
+    pub fn new(home_id: u32, node_id: u8, command_class: u8) -> Self {
+        ValueIdKey {
+            home_id,
+            node_id,
+            genre: None,
+            command_class,
+            instance: 1,
+            index: 0,
+            value_type: ValueType::Unknown,
+        }
+    }
+
+    pub fn genre(mut self, genre: ValueGenre) -> Self {
+        self.genre = Some(genre);
+        self
+    }
+
+    pub fn instance(mut self, instance: u8) -> Self {
+        self.instance = instance;
+        self
+    }
+
+    pub fn index(mut self, index: u16) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub fn value_type(mut self, value_type: ValueType) -> Self {
+        self.value_type = value_type;
+        self
+    }
+
+    pub fn from_packed_id(home_id: u32, id: u64) -> Self {
+        let (id, id1) = (id as u32, (id >> 32) as u32);
+        ValueIdKey {
+            home_id,
+            node_id: (id >> 24) as u8,
+            genre: ((id >> 22) as u8 & 0x03).try_into().ok(),
+            command_class: (id >> 14) as u8,
+            instance: (id >> 4) as u8,
+            index: (id1 >> 16) as u16,
+            value_type: (id as u8 & 0x0f).try_into().unwrap_or(ValueType::Unknown),
+        }
+    }
+
+    /// The id OpenZWave knows the value by, `(id1 << 32) | id`. None when the genre or the
+    /// value type is unknown, as for a key read from its string form: OpenZWave never
+    /// produces such an id.
+    pub fn packed_id(&self) -> Option<u64> {
+        if self.value_type == ValueType::Unknown {
+            return None;
+        }
+        let id = (self.node_id as u32) << 24
+            | (self.genre? as u32 & 0x03) << 22
+            | (self.command_class as u32) << 14
+            | (self.instance as u32) << 4
+            | (self.value_type as u32 & 0x0f);
+        let id1 = (self.index as u32) << 16;
+        Some((id1 as u64) << 32 | id as u64)
+    }
+
+    fn identity(&self) -> (u32, u8, u8, u8, u16) {
+        (self.home_id, self.node_id, self.command_class, self.instance, self.index)
+    }
+}
+
+impl PartialEq for ValueIdKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for ValueIdKey {}
+
+impl Hash for ValueIdKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state)
+    }
+}
+
+impl PartialOrd for ValueIdKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueIdKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl fmt::Display for ValueIdKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "0x{:08x}:{}:0x{:02x}:{}:{}",
+            self.home_id, self.node_id, self.command_class, self.instance, self.index
+        )
+    }
+}
+
+/// Reads `home_id:node:command_class:instance:index`, numbers being decimal or `0x` prefixed.
+impl FromStr for ValueIdKey {
+    type Err = ParseError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = key.trim().split(':').collect();
+        match parts[..] {
+            [home_id, node_id, command_class, instance, index] => Ok(ValueIdKey {
+                home_id: parse_number(home_id, "home_id")?,
+                node_id: parse_number(node_id, "node_id")?,
+                genre: None,
+                command_class: parse_number(command_class, "command_class")?,
+                instance: parse_number(instance, "instance")?,
+                index: parse_number(index, "index")?,
+                value_type: ValueType::Unknown,
+            }),
+            _ => Err(ParseError::Format),
+        }
+    }
+}
+
+fn parse_number<T: TryFrom<u64>>(number: &str, field: &'static str) -> Result<T, ParseError> {
+    let number = match number.strip_prefix("0x").or_else(|| number.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => number.parse(),
+    };
+    number
+        .ok()
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or(ParseError::InvalidField(field))
+}
+
+#[cfg(any(feature = "serde_serialization", feature = "config_file"))]
+impl serde::Serialize for ValueIdKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(any(feature = "serde_serialization", feature = "config_file"))]
+impl<'de> serde::Deserialize<'de> for ValueIdKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let key = String::deserialize(deserializer)?;
+        key.parse().map_err(|error| {
+            serde::de::Error::custom(format!("invalid value id key {}: {}", key, error))
+        })
+    }
+}
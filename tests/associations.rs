@@ -0,0 +1,85 @@
+use openzwave::association::InstanceAssociation;
+use openzwave::backend::{SimulatedGroup, SimulatedNetwork, SimulatedNode};
+use openzwave::error::{ConfirmationError, Error};
+use openzwave::node::Node;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+fn switch_with_groups() -> SimulatedNode {
+    let mut groups = BTreeMap::new();
+    groups.insert(1, SimulatedGroup {
+        associations: vec![InstanceAssociation { node_id: 1, instance: 0 }],
+        ..SimulatedGroup::new("Lifeline", 1)
+    });
+    groups.insert(2, SimulatedGroup::new("On/Off control", 2));
+    groups.insert(3, SimulatedGroup {
+        multi_instance: true,
+        ..SimulatedGroup::new("Endpoint control", 5)
+    });
+    SimulatedNode { groups, ..SimulatedNode::default() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_groups() {
+        let network = SimulatedNetwork::new(0x000c_0001);
+        network.add_node(2, switch_with_groups());
+        let associations = Node::from_id(0x000c_0001, 2).associations();
+
+        assert_eq!(associations.num_groups(), 3);
+        let groups = associations.groups();
+        assert_eq!(groups.iter().map(|group| group.label.as_str()).collect::<Vec<_>>(),
+                   vec!["Lifeline", "On/Off control", "Endpoint control"]);
+        assert!(groups[0].contains(1, 0));
+        assert!(groups[0].is_full());
+        assert!(groups[2].multi_instance);
+        assert!(associations.group(0).is_none());
+        assert!(associations.group(4).is_none());
+        assert_eq!(Node::from_id(0x000c_0001, 9).associations().num_groups(), 0);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_change_associations() {
+        let network = SimulatedNetwork::new(0x000c_0002);
+        network.add_node(2, switch_with_groups());
+        network.add_node(3, SimulatedNode::default());
+        let associations = Node::from_id(0x000c_0002, 2).associations();
+        let timeout = Duration::from_secs(1);
+
+        let group = associations.add(2, 3, 0, timeout).await.unwrap();
+        assert_eq!(group.associations, vec![InstanceAssociation { node_id: 3, instance: 0 }]);
+        let group = associations.add(3, 3, 2, timeout).await.unwrap();
+        assert!(group.contains(3, 2));
+
+        let group = associations.remove(2, 3, 0, timeout).await.unwrap();
+        assert!(group.associations.is_empty());
+
+        // the lifeline only takes one node: the device reports it unchanged
+        match associations.add(1, 3, 0, timeout).await {
+            Err(Error::ConfirmationError(ConfirmationError::Rejected)) => {}
+            other => panic!("a full group can't take another node, got {:?}", other),
+        }
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_association_changes() {
+        let network = SimulatedNetwork::new(0x000c_0003);
+        network.add_node(2, switch_with_groups());
+        let associations = Node::from_id(0x000c_0003, 2).associations();
+        let timeout = Duration::from_secs(1);
+
+        assert!(matches!(associations.add(7, 3, 0, timeout).await, Err(Error::InvalidParameter("group", _))));
+        assert!(matches!(associations.add(2, 0, 0, timeout).await, Err(Error::InvalidParameter("target_node_id", _))));
+        assert!(matches!(associations.add(2, 3, 1, timeout).await, Err(Error::InvalidParameter("instance", _))));
+
+        network.set_auto_confirm(false);
+        let unconfirmed = associations.add(2, 3, 0, Duration::from_millis(20)).await;
+        assert!(matches!(unconfirmed, Err(Error::ConfirmationError(ConfirmationError::Timeout))));
+        network.detach();
+    }
+}
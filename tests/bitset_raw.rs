@@ -0,0 +1,136 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::{Error, GetSetError};
+use openzwave::value_classes::value_id::{BitSetBit, BitSetValue, ValueContent, ValueType};
+use std::time::Duration;
+
+fn alarm_settings() -> ValueContent {
+    ValueContent::BitSet(BitSetValue::new(
+        1,
+        vec![(1, "Motion", true), (2, "Tamper", false), (5, "Low battery", true)],
+    )
+    .unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitset_content() {
+        let network = SimulatedNetwork::new(0x000f_0001);
+        network.add_node(2, SimulatedNode::default());
+        let alarms = network.add_value(2, SimulatedValue::new(0x70, 3, "Alarms", alarm_settings()));
+        assert_eq!(alarms.get_type(), ValueType::BitSet);
+
+        let bitset = alarms.as_bitset().unwrap();
+        assert_eq!(bitset.size, 1);
+        assert_eq!(bitset.mask, 0b1_0011);
+        assert_eq!(bitset.to_u32(), 0b1_0001);
+        assert_eq!(
+            bitset.bit(5),
+            Some(&BitSetBit { position: 5, label: String::from("Low battery"), value: true })
+        );
+        assert_eq!(bitset.bit_by_label("Tamper").map(|bit| bit.position), Some(2));
+        match alarms.value() {
+            ValueContent::BitSet(content) => assert_eq!(content, &bitset),
+            other => panic!("expected a bitset, got {:?}", other),
+        }
+
+        assert!(alarms.get_bit(1).unwrap());
+        assert!(!alarms.get_bit(2).unwrap());
+        assert!(matches!(alarms.get_bit(3), Err(Error::InvalidParameter("position", _))));
+        network.detach();
+    }
+
+    #[test]
+    fn test_bitset_positions() {
+        assert!(BitSetValue::new(1, vec![(0, "None", true)]).is_none());
+        assert!(BitSetValue::new(1, vec![(9, "Beyond", true)]).is_none());
+        assert!(BitSetValue::new(8, vec![(33, "Beyond", true)]).is_none());
+        let last = BitSetValue::new(4, vec![(32, "Last", true)]).unwrap();
+        assert_eq!(last.mask, 1 << 31);
+        assert_eq!(last.to_u32(), 1 << 31);
+
+        // built by hand, positions without a bit are left out
+        let bits = [0, 1, 40]
+            .iter()
+            .map(|position| BitSetBit { position: *position, label: String::new(), value: true })
+            .collect();
+        assert_eq!(BitSetValue { size: 8, mask: 1, bits }.to_u32(), 1);
+    }
+
+    #[test]
+    fn test_bitset_setters() {
+        let network = SimulatedNetwork::new(0x000f_0002);
+        network.add_node(2, SimulatedNode::default());
+        let alarms = network.add_value(2, SimulatedValue::new(0x70, 3, "Alarms", alarm_settings()));
+
+        alarms.set_bit(2, true).unwrap();
+        alarms.set_bit(1, false).unwrap();
+        assert_eq!(alarms.as_bitset().unwrap().to_u32(), 0b1_0010);
+        // bits out of the mask aren't used by the device
+        assert!(matches!(alarms.set_bit(3, true), Err(Error::InvalidParameter("position", _))));
+
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        assert!(matches!(level.set_bit(1, true), Err(Error::SetError(GetSetError::WrongType))));
+        assert!(matches!(level.as_bitset(), Err(Error::GetError(GetSetError::WrongType))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_bitset_write() {
+        let network = SimulatedNetwork::new(0x000f_0003);
+        network.add_node(2, SimulatedNode::default());
+        let alarms = network.add_value(2, SimulatedValue::new(0x70, 3, "Alarms", alarm_settings()));
+
+        let wanted = BitSetValue::new(1, vec![(1, "Motion", false), (2, "Tamper", true)]).unwrap();
+        let confirmed = alarms
+            .set_and_confirm(ValueContent::BitSet(wanted), Duration::from_secs(1))
+            .await
+            .unwrap();
+        match confirmed.value() {
+            ValueContent::BitSet(bitset) => assert_eq!(bitset.to_u32(), 0b1_0010),
+            other => panic!("expected a bitset, got {:?}", other),
+        }
+
+        let unknown_bit = BitSetValue::new(1, vec![(8, "Unused", true)]).unwrap();
+        assert!(matches!(
+            alarms.set_and_confirm(ValueContent::BitSet(unknown_bit), Duration::from_secs(1)).await,
+            Err(Error::InvalidParameter("bitset", _))
+        ));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_raw_values() {
+        let network = SimulatedNetwork::new(0x000f_0004);
+        network.add_node(2, SimulatedNode::default());
+        let code = SimulatedValue::new(0x63, 0, "Code", ValueContent::Raw(vec![0x31, 0x32]));
+        let raw = network.add_value(2, code);
+        assert_eq!(raw.get_type(), ValueType::Raw);
+        assert!(matches!(raw.value(), ValueContent::Raw(bytes) if bytes == &vec![0x31, 0x32]));
+
+        raw.set_raw(&vec![0x01, 0x02, 0x03]).unwrap();
+        assert_eq!(*raw.as_raw().unwrap(), vec![0x01, 0x02, 0x03]);
+
+        let confirmed = raw
+            .set_and_confirm(ValueContent::Raw(vec![0xff]), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(confirmed.value().to_string(), "\"ff\"");
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_bitset_raw_serialization() {
+        assert_eq!(serde_json::to_value(ValueContent::Raw(vec![0xde, 0xad])).unwrap(), "dead");
+        let json = serde_json::to_value(alarm_settings()).unwrap();
+        assert_eq!(json["size"], 1);
+        assert_eq!(json["mask"], 0b1_0011);
+        assert_eq!(
+            json["bits"][2],
+            serde_json::json!({ "position": 5, "label": "Low battery", "value": true })
+        );
+    }
+}
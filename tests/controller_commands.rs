@@ -0,0 +1,69 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::controller::Controller;
+use openzwave::error::Error;
+use openzwave::notification::{ControllerCommandType, ControllerError, ControllerState};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_node_commands() {
+        let network = SimulatedNetwork::new(0x0007_0001);
+        network.add_node(2, SimulatedNode::default());
+        network.add_node(3, SimulatedNode { failed: true, ..SimulatedNode::default() });
+        let controller = Controller::new(0x0007_0001);
+
+        assert_eq!(controller.has_node_failed(2).unwrap().await.unwrap(), ControllerState::NodeOK);
+        assert_eq!(controller.has_node_failed(3).unwrap().await.unwrap(), ControllerState::NodeFailed);
+        assert_eq!(
+            controller.request_node_neighbor_update(2).unwrap().await.unwrap(),
+            ControllerState::Completed
+        );
+        match controller.assign_return_route(9).unwrap().await {
+            Err(Error::ControllerCommandError(ControllerState::Failed, ControllerError::NodeNotFound)) => {}
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_remove_failed_node() {
+        let network = SimulatedNetwork::new(0x0007_0002);
+        network.add_node(2, SimulatedNode::default());
+        network.add_node(3, SimulatedNode { failed: true, ..SimulatedNode::default() });
+        let controller = Controller::new(0x0007_0002);
+
+        match controller.remove_failed_node(2).unwrap().await {
+            Err(Error::ControllerCommandError(ControllerState::Failed, ControllerError::NotFound)) => {}
+            other => panic!("a healthy node can't be removed, got {:?}", other),
+        }
+        assert_eq!(
+            controller.remove_failed_node(3).unwrap().await.unwrap(),
+            ControllerState::Completed
+        );
+        assert_eq!(network.node_ids(), vec![1, 2]);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_waiting_command_can_be_cancelled() {
+        let network = SimulatedNetwork::new(0x0007_0003);
+        let controller = Controller::new(0x0007_0003);
+
+        match controller.receive_configuration().unwrap().await {
+            Err(Error::ControllerCommandError(ControllerState::Failed, ControllerError::IsPrimary)) => {}
+            other => panic!("the primary controller can't receive a configuration, got {:?}", other),
+        }
+
+        let command = controller.create_new_primary().unwrap();
+        assert_eq!(command.command_type(), ControllerCommandType::CreateNewPrimary);
+        assert_eq!(command.state(), ControllerState::Waiting);
+        command.cancel();
+        match command.await {
+            Err(Error::ControllerCommandError(ControllerState::Cancel, _)) => {}
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        network.detach();
+    }
+}
@@ -0,0 +1,139 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::ParseError;
+use openzwave::value_classes::value_id::{DecimalValue, ValueContent, ValueID};
+use std::time::Duration;
+
+fn decimal(value: &str) -> DecimalValue {
+    value.parse().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decimal_parsing() {
+        assert_eq!(decimal("12345.678"), DecimalValue::new(12_345_678, 3).unwrap());
+        assert_eq!(decimal("-0.05").mantissa(), -5);
+        assert_eq!(decimal("+7").precision(), 0);
+        assert_eq!(decimal(".5"), DecimalValue::new(5, 1).unwrap());
+        for invalid in &["", "-", ".", "1.2.3", "12a", "1e5", "NaN"] {
+            assert_eq!(invalid.parse::<DecimalValue>(), Err(ParseError::Format), "{} parsed", invalid);
+        }
+        assert_eq!("99999999999999999999".parse::<DecimalValue>(), Err(ParseError::OutOfRange));
+        assert_eq!("0.1234567890123456789".parse::<DecimalValue>(), Err(ParseError::OutOfRange));
+        assert_eq!(decimal("-9223372036854775808").mantissa(), i64::MIN);
+        assert_eq!(decimal("-922337203685477580.8").mantissa(), i64::MIN);
+        assert_eq!("9223372036854775808".parse::<DecimalValue>(), Err(ParseError::OutOfRange));
+
+        // Display gives back the very same digits
+        for value in &["12345.678", "-0.05", "0.000", "23.10", "-42", "0", "-9223372036854775808"] {
+            assert_eq!(decimal(value).to_string(), *value);
+        }
+        assert_eq!(DecimalValue::new(-5, 3).unwrap().to_string(), "-0.005");
+    }
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        assert_eq!(decimal("1.5"), decimal("1.50"));
+        assert!(decimal("-0.1") < decimal("0.01"));
+        assert!(decimal("12345.678") > decimal("12345.67"));
+        assert_eq!(decimal("12345.678").max(decimal("9999.9999")).to_string(), "12345.678");
+
+        assert_eq!((decimal("12345.678") + decimal("0.5")).to_string(), "12346.178");
+        assert_eq!((decimal("1.25") - decimal("2")).to_string(), "-0.75");
+        assert_eq!((decimal("1.5") * decimal("-0.25")).to_string(), "-0.375");
+        assert_eq!((-decimal("3.0")).to_string(), "-3.0");
+
+        assert_eq!(decimal("2.345").rescale(2).unwrap().to_string(), "2.35");
+        assert_eq!(decimal("-2.345").rescale(1).unwrap().to_string(), "-2.3");
+        assert_eq!(decimal("2.5").rescale(3).unwrap().to_string(), "2.500");
+        assert_eq!(decimal("0.125").to_f64(), 0.125);
+    }
+
+    #[test]
+    fn test_decimal_limits() {
+        assert!(DecimalValue::new(1, 19).is_none());
+        assert!(DecimalValue::from_f64(1.0, 19).is_none());
+        assert!(DecimalValue::from_f64(f64::NAN, 2).is_none());
+        assert!(DecimalValue::from_f64(1e30, 0).is_none());
+        assert!(decimal("1.5").rescale(19).is_none());
+        assert!(decimal("9223372036854775807").rescale(1).is_none());
+
+        let max = DecimalValue::new(i64::MAX, 0).unwrap();
+        let min = DecimalValue::new(i64::MIN, 0).unwrap();
+        assert!(max.checked_add(decimal("1")).is_none());
+        assert!(min.checked_sub(decimal("1")).is_none());
+        assert!(max.checked_mul(decimal("2")).is_none());
+        assert!(min.checked_neg().is_none());
+        assert_eq!(max + decimal("1"), max);
+        assert_eq!(min - decimal("1"), min);
+        assert_eq!(max * decimal("-2"), min);
+        assert_eq!(-min, max);
+
+        // fraction digits make room for the integer part
+        let big = decimal("9000000000.000000001");
+        assert_eq!(big.checked_add(big).unwrap().to_string(), "18000000000.00000000");
+        assert_eq!((big * decimal("2")).to_string(), "18000000000.00000000");
+    }
+
+    #[test]
+    fn test_decimal_precision_gaps() {
+        let tiny = decimal("0.000000000000000001");
+        assert_eq!((tiny * tiny).precision(), DecimalValue::MAX_PRECISION);
+        assert_eq!((tiny * tiny).to_string(), "0.000000000000000000");
+        assert_eq!((tiny * decimal("0.5")).to_string(), "0.000000000000000001");
+
+        let huge = DecimalValue::new(i64::MAX, 0).unwrap();
+        assert!(tiny < huge);
+        assert!(DecimalValue::new(i64::MIN, 18).unwrap() < decimal("0"));
+        assert_eq!(DecimalValue::new(i64::MIN, 18).unwrap().rescale(0).unwrap().to_string(), "-9");
+        assert_eq!((huge + tiny).to_string(), "9223372036854775807");
+    }
+
+    #[tokio::test]
+    async fn test_meter_readings_keep_their_digits() {
+        let network = SimulatedNetwork::new(0x0011_0001);
+        network.add_node(2, SimulatedNode::default());
+        let energy = network.add_value(
+            2,
+            SimulatedValue::new(0x32, 0, "Energy", ValueContent::Decimal(decimal("12345.678"))),
+        );
+        // an f32 only has room for 12345.68
+        assert_eq!(energy.value().to_string(), "12345.678");
+
+        energy.set(ValueContent::Decimal(decimal("54321.987"))).unwrap();
+        let current = ValueID::from_packed_id(energy.get_home_id(), energy.id());
+        assert_eq!(current.value().to_string(), "54321.987");
+
+        energy.set_from_str("100000.001").unwrap();
+        let confirmed = energy
+            .set_and_confirm(ValueContent::Decimal(decimal("99999.999")), Duration::from_secs(1))
+            .await
+            .unwrap();
+        match confirmed.value() {
+            ValueContent::Decimal(value) => assert_eq!(*value, decimal("99999.999")),
+            other => panic!("expected a decimal, got {:?}", other),
+        }
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_decimal_serialization() {
+        let json = serde_json::to_value(decimal("12345.670")).unwrap();
+        assert_eq!(json, "12345.670");
+        let back: DecimalValue = serde_json::from_value(json).unwrap();
+        assert_eq!(back.to_string(), "12345.670");
+
+        // plain JSON numbers are read as written
+        let number: DecimalValue = serde_json::from_str("21.5").unwrap();
+        assert_eq!(number.to_string(), "21.5");
+        let integer: DecimalValue = serde_json::from_str("-3").unwrap();
+        assert_eq!(integer, DecimalValue::new(-3, 0).unwrap());
+        assert!(serde_json::from_str::<DecimalValue>("\"abc\"").is_err());
+
+        let content = serde_json::to_value(ValueContent::Decimal(decimal("0.10"))).unwrap();
+        assert_eq!(content, "0.10");
+    }
+}
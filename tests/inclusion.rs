@@ -0,0 +1,125 @@
+use futures::StreamExt;
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::controller::Controller;
+use openzwave::error::Error;
+use openzwave::manager::Manager;
+use openzwave::notification::{ControllerState, Notification, NotificationType};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_include_streams_progress_and_resolves_with_the_node() {
+        let network = SimulatedNetwork::new(0x0008_0001);
+        let controller = Controller::new(0x0008_0001);
+
+        let mut inclusion = controller.include(false, Duration::from_secs(5)).unwrap();
+        assert_eq!(inclusion.state(), ControllerState::Waiting);
+        assert!(network.include_node(7, SimulatedNode::default()));
+
+        let states: Vec<ControllerState> = (&mut inclusion).collect().await;
+        assert_eq!(
+            states,
+            vec![
+                ControllerState::Starting,
+                ControllerState::Waiting,
+                ControllerState::InProgress,
+                ControllerState::Completed
+            ]
+        );
+        let node = inclusion.await.unwrap();
+        assert_eq!(node.get_id(), 7);
+        assert_eq!(network.node_ids(), vec![1, 7]);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_exclude_is_cancelled_on_timeout() {
+        let network = SimulatedNetwork::new(0x0008_0002);
+        network.add_node(4, SimulatedNode::default());
+        let controller = Controller::new(0x0008_0002);
+
+        match controller.exclude(Duration::from_millis(20)).unwrap().await {
+            Err(Error::ControllerCommandTimeout(ControllerState::Waiting)) => {}
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        assert!(!network.exclude_node(4));
+
+        let exclusion = controller.exclude(Duration::from_secs(5)).unwrap();
+        assert!(network.exclude_node(4));
+        assert_eq!(exclusion.await.unwrap().get_id(), 4);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_dropping_cancels() {
+        let network = SimulatedNetwork::new(0x0008_0003);
+        let controller = Controller::new(0x0008_0003);
+
+        let inclusion = controller.include(true, Duration::from_secs(5)).unwrap();
+        drop(inclusion);
+        assert!(!network.include_node(7, SimulatedNode::default()));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_other_nodes_are_not_the_included_one() {
+        let network = SimulatedNetwork::new(0x0008_0004);
+        let manager = Manager::with_backend(network.clone());
+        let controller = Controller::new(0x0008_0004);
+
+        let inclusion = controller.include(false, Duration::from_secs(5)).unwrap();
+        // before the inclusion, e.g. found again while the network is queried
+        network.add_node(3, SimulatedNode::default());
+        // and right after the included node, before the command completes
+        let _stray = {
+            let network = network.clone();
+            manager
+                .add_watcher(move |notification: &Notification| {
+                    if notification.notification_type == NotificationType::NodeAdded
+                        && notification.node_id == 7
+                    {
+                        network.add_node(4, SimulatedNode::default());
+                    }
+                })
+                .unwrap()
+        };
+        assert!(network.include_node(7, SimulatedNode::default()));
+        assert_eq!(inclusion.await.unwrap().get_id(), 7);
+        assert_eq!(network.node_ids(), vec![1, 3, 4, 7]);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_late_states_of_a_cancelled_command() {
+        let network = SimulatedNetwork::new(0x0008_0005);
+        let manager = Manager::with_backend(network.clone());
+        network.add_node(4, SimulatedNode::default());
+        let controller = Controller::new(0x0008_0005);
+
+        let cancelled: Arc<Mutex<Option<Notification>>> = Arc::default();
+        let _keep = {
+            let cancelled = cancelled.clone();
+            manager
+                .add_watcher(move |notification: &Notification| {
+                    if notification.notification_type == NotificationType::ControllerCommand {
+                        *cancelled.lock().unwrap() = Some(notification.clone());
+                    }
+                })
+                .unwrap()
+        };
+        drop(controller.include(false, Duration::from_secs(5)).unwrap());
+        let cancelled = cancelled.lock().unwrap().take().unwrap();
+
+        // OpenZWave reports the end of the inclusion once the exclusion has started
+        let exclusion = controller.exclude(Duration::from_secs(5)).unwrap();
+        network.emit(&cancelled);
+        assert_eq!(exclusion.state(), ControllerState::Waiting);
+        assert!(network.exclude_node(4));
+        assert_eq!(exclusion.await.unwrap().get_id(), 4);
+        network.detach();
+    }
+}
@@ -0,0 +1,89 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::Error;
+use openzwave::value_classes::value_id::{ListItem, ListValue, ValueContent};
+use std::time::Duration;
+
+fn thermostat_modes() -> ValueContent {
+    ValueContent::List(ListValue::new(vec![("Off", 0), ("Heat", 1), ("Cool", 2), ("Auto", 3)], 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_list_content() {
+        let network = SimulatedNetwork::new(0x000d_0001);
+        network.add_node(2, SimulatedNode::default());
+        let mode = network.add_value(2, SimulatedValue::new(0x40, 0, "Mode", thermostat_modes()));
+
+        let list = match mode.value() {
+            ValueContent::List(list) => list.clone(),
+            other => panic!("expected a list, got {:?}", other),
+        };
+        assert_eq!(list.selected, "Heat");
+        assert_eq!(list.selected_value, 1);
+        assert_eq!(list.selected_index(), Some(1));
+        assert_eq!(list.items[3], ListItem { label: String::from("Auto"), value: 3 });
+        assert_eq!(list.item_by_label("Cool").map(|item| item.value), Some(2));
+        assert!(list.with_selection(7).is_none());
+        assert_eq!(mode.as_list().unwrap().content().unwrap(), list);
+        network.detach();
+    }
+
+    #[test]
+    fn test_list_setters() {
+        let network = SimulatedNetwork::new(0x000d_0002);
+        network.add_node(2, SimulatedNode::default());
+        let mode = network.add_value(2, SimulatedValue::new(0x40, 0, "Mode", thermostat_modes()));
+        let selection = || mode.as_list().unwrap().selection_as_string().unwrap();
+
+        mode.set_list_selection_value(2).unwrap();
+        assert_eq!(selection(), "Cool");
+        mode.set_list_selection_index(3).unwrap();
+        assert_eq!(selection(), "Auto");
+        mode.set_list_selection_string("Off").unwrap();
+        assert_eq!(selection(), "Off");
+
+        assert!(matches!(mode.set_list_selection_value(9), Err(Error::InvalidParameter("value", _))));
+        assert!(matches!(mode.set_list_selection_index(4), Err(Error::InvalidParameter("index", _))));
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        assert!(matches!(level.set_list_selection_value(0), Err(Error::SetError(_))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_list_write() {
+        let network = SimulatedNetwork::new(0x000d_0003);
+        network.add_node(2, SimulatedNode::default());
+        let mode = network.add_value(2, SimulatedValue::new(0x40, 0, "Mode", thermostat_modes()));
+
+        let cool = ListValue::new(vec![("Cool", 2)], 2);
+        let confirmed = mode
+            .set_and_confirm(ValueContent::List(cool), Duration::from_secs(1))
+            .await
+            .unwrap();
+        match confirmed.value() {
+            ValueContent::List(list) => {
+                assert_eq!(list.selected, "Cool");
+                assert_eq!(list.items.len(), 4);
+            }
+            other => panic!("expected a list, got {:?}", other),
+        }
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_list_serialization() {
+        let json = serde_json::to_value(ValueContent::List(ListValue::new(vec![("Off", 0), ("On", 255)], 255))).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "selected": "On",
+                "selected_value": 255,
+                "items": [{ "label": "Off", "value": 0 }, { "label": "On", "value": 255 }],
+            })
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::manager::Manager;
+use openzwave::network_state::{NetworkState, StateChange};
+use openzwave::notification::{Notification, NotificationType};
+use openzwave::value_classes::value_id::{ValueContent, ValueID};
+use std::sync::{Arc, Mutex};
+
+fn content(state: &NetworkState, home_id: u32, node_id: u8, cc: u8) -> Option<String> {
+    state
+        .values(home_id, node_id)
+        .iter()
+        .find(|value_id| value_id.get_command_class_id() == cc)
+        .map(|value_id| value_id.value().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_state_follows_the_network() {
+        let network = SimulatedNetwork::new(0x0006_0001);
+        let mut manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+
+        network.add_node(2, SimulatedNode::default());
+        let switch = network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        manager.add_driver("/dev/simulated").unwrap();
+
+        assert_eq!(state.controllers(), vec![0x0006_0001]);
+        assert_eq!(state.nodes(0x0006_0001), vec![1, 2]);
+        assert_eq!(state.query_stage(0x0006_0001, 2).as_deref(), Some("Complete"));
+        assert_eq!(state.values(0x0006_0001, 2).len(), 2);
+        let found = state
+            .value(0x0006_0001, 2, 0x26, level.get_instance(), level.get_index())
+            .unwrap();
+        assert_eq!(found.id(), level.id());
+
+        network.update_value(&switch, ValueContent::Bool(true));
+        assert_eq!(content(&state, 0x0006_0001, 2, 0x25).as_deref(), Some("true"));
+
+        network.remove_value(&level);
+        assert_eq!(state.values(0x0006_0001, 2).len(), 1);
+        assert!(state.value(0x0006_0001, 2, 0x26, level.get_instance(), level.get_index()).is_none());
+
+        network.remove_node(2);
+        assert_eq!(state.nodes(0x0006_0001), vec![1]);
+        assert!(state.node(0x0006_0001, 2).is_none());
+        network.detach();
+    }
+
+    #[test]
+    fn test_query_stage_updates() {
+        let network = SimulatedNetwork::new(0x0006_0002);
+        let manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+
+        network.add_node(3, SimulatedNode { query_stage: "Probe".into(), ..SimulatedNode::default() });
+        assert_eq!(state.query_stage(0x0006_0002, 3).as_deref(), Some("Probe"));
+
+        network.update_node(3, |node| node.query_stage = "Complete".into());
+        network.emit(&Notification {
+            notification_type: NotificationType::NodeQueriesComplete,
+            home_id: 0x0006_0002,
+            node_id: 3,
+            value_id: None,
+            value: None,
+            event: None,
+            command: None,
+            byte: 0,
+            description: String::new(),
+        });
+        assert_eq!(state.query_stage(0x0006_0002, 3).as_deref(), Some("Complete"));
+        network.detach();
+    }
+
+    #[test]
+    fn test_subscription() {
+        let network = SimulatedNetwork::new(0x0006_0003);
+        let manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+        let changes: Arc<Mutex<Vec<String>>> = Arc::default();
+
+        let subscription = {
+            let changes = changes.clone();
+            state.subscribe(move |change: &StateChange| {
+                let name = match change {
+                    StateChange::ControllerAdded(_) => "controller added",
+                    StateChange::NodeAdded { .. } => "node added",
+                    StateChange::ValueAdded(_) => "value added",
+                    StateChange::ValueChanged(_) => "value changed",
+                    _ => "other",
+                };
+                changes.lock().unwrap().push(name.to_string());
+            })
+        };
+
+        network.add_node(4, SimulatedNode::default());
+        let value = network.add_value(4, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        network.update_value(&value, ValueContent::Byte(42));
+        drop(subscription);
+        network.update_value(&value, ValueContent::Byte(43));
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec!["controller added", "node added", "value added", "value changed"]
+        );
+        assert_eq!(content(&state, 0x0006_0003, 4, 0x26).as_deref(), Some("43"));
+        network.detach();
+    }
+
+    #[test]
+    fn test_last_known_content() {
+        let network = SimulatedNetwork::new(0x0006_0004);
+        let manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+        let values: Arc<Mutex<Vec<ValueID>>> = Arc::default();
+        let _subscription = {
+            let values = values.clone();
+            state.subscribe(move |change: &StateChange| match change {
+                StateChange::ValueChanged(value_id) | StateChange::ValueRemoved(value_id) => {
+                    values.lock().unwrap().push(value_id.clone())
+                }
+                _ => {}
+            })
+        };
+
+        network.add_node(4, SimulatedNode::default());
+        let value = network.add_value(4, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        network.update_value(&value, ValueContent::Byte(42));
+        network.update_value(&value, ValueContent::Byte(43));
+        network.remove_node(4);
+
+        let contents: Vec<String> =
+            values.lock().unwrap().iter().map(|value_id| value_id.value().to_string()).collect();
+        assert_eq!(contents, vec!["42", "43", "43"]);
+        network.detach();
+    }
+}
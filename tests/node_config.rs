@@ -0,0 +1,91 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::{ConfirmationError, Error};
+use openzwave::manager::Manager;
+use openzwave::network_state::NetworkState;
+use openzwave::node::Node;
+use openzwave::notification::Notification;
+use openzwave::value_classes::value_id::{ValueContent, ValueGenre};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn config_value(index: u16, label: &str, content: ValueContent) -> SimulatedValue {
+    SimulatedValue {
+        genre: ValueGenre::Config,
+        ..SimulatedValue::new(0x70, index, label, content)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_config_params() {
+        let network = SimulatedNetwork::new(0x000b_0001);
+        let manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+        network.add_node(2, SimulatedNode::default());
+        network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        network.add_value(2, config_value(3, "LED mode", ValueContent::Byte(0)));
+        network.add_value(2, config_value(20, "Overload", ValueContent::Short(2500)));
+
+        let config = Node::from_id(0x000b_0001, 2).config();
+        let params = config.params(&state);
+        assert_eq!(params.keys().cloned().collect::<Vec<_>>(), vec![3, 20]);
+        assert_eq!(params[&20].label(), "Overload");
+
+        let confirmed = config.set(20, 3000, 2, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(confirmed.value().to_string(), "3000");
+        assert_eq!(config.param(&state, 20).unwrap().value().to_string(), "3000");
+
+        // the device reports parameters we didn't know about
+        let created = config.set(7, 1, 1, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(created.label(), "Parameter #7");
+        assert_eq!(config.params(&state).len(), 3);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_writes() {
+        let network = SimulatedNetwork::new(0x000b_0002);
+        network.add_node(2, SimulatedNode::default());
+        let config = Node::from_id(0x000b_0002, 2).config();
+
+        let wrong_size = config.set(1, 1, 3, Duration::from_secs(1)).await;
+        assert!(matches!(wrong_size, Err(Error::InvalidParameter("size", _))));
+        let too_large = config.set(1, 256, 1, Duration::from_secs(1)).await;
+        assert!(matches!(too_large, Err(Error::InvalidParameter("value", _))));
+        let unknown_node = Node::from_id(0x000b_0002, 9).config().set(1, 1, 1, Duration::from_secs(1)).await;
+        assert!(matches!(unknown_node, Err(Error::SetError(_))));
+
+        network.set_auto_confirm(false);
+        let unconfirmed = config.set(1, -1, 1, Duration::from_millis(20)).await;
+        assert!(matches!(unconfirmed, Err(Error::ConfirmationError(ConfirmationError::Timeout))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_request_config_param() {
+        let network = SimulatedNetwork::new(0x000b_0003);
+        network.add_node(2, SimulatedNode::default());
+        network.add_value(2, config_value(3, "LED mode", ValueContent::Byte(1)));
+        let manager = Manager::with_backend(network.clone());
+        let refreshed = Arc::new(Mutex::new(Vec::new()));
+        let seen = refreshed.clone();
+        let _guard = manager
+            .add_watcher(move |notification: &Notification| {
+                if let Some(ref value_id) = notification.value_id {
+                    seen.lock().unwrap().push((notification.notification_type, value_id.label().to_string()));
+                }
+            })
+            .unwrap();
+
+        let config = Node::from_id(0x000b_0003, 2).config();
+        config.request(3);
+        config.request(4);
+        config.request_all();
+        assert_eq!(refreshed.lock().unwrap().len(), 2);
+        assert!(refreshed.lock().unwrap().iter().all(|(_, label)| label == "LED mode"));
+        network.detach();
+    }
+}
@@ -0,0 +1,98 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::controller::Controller;
+use openzwave::manager::Manager;
+use openzwave::notification::{
+    set_notification_descriptions, ControllerCommandType, ControllerError, ControllerState,
+    Notification, NotificationType, UserAlert, UserAlertType,
+};
+use std::sync::{Arc, Mutex};
+
+fn record(manager: &Manager) -> (Arc<Mutex<Vec<Notification>>>, openzwave::manager::WatcherGuard) {
+    let seen: Arc<Mutex<Vec<Notification>>> = Arc::default();
+    let watcher = {
+        let seen = seen.clone();
+        manager
+            .add_watcher(move |notification: &Notification| {
+                seen.lock().unwrap().push(notification.clone())
+            })
+            .unwrap()
+    };
+    (seen, watcher)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_controller_command() {
+        let network = SimulatedNetwork::new(0x0014_0001);
+        let manager = Manager::with_backend(network.clone());
+        let (seen, _watcher) = record(&manager);
+        network.add_node(2, SimulatedNode::default());
+        // only the one test of this file turning descriptions on
+        assert_eq!(seen.lock().unwrap()[0].description, "");
+        set_notification_descriptions(true);
+        let controller = Controller::new(0x0014_0001);
+
+        controller.has_node_failed(2).unwrap().await.unwrap();
+        let commands: Vec<_> = seen.lock().unwrap().iter().filter_map(Notification::controller_command).collect();
+        assert_eq!(
+            commands.last(),
+            Some(&(ControllerCommandType::HasNodeFailed, ControllerState::NodeOK, ControllerError::None))
+        );
+        assert!(commands.iter().all(|(command, _, _)| *command == ControllerCommandType::HasNodeFailed));
+
+        seen.lock().unwrap().clear();
+        assert!(controller.remove_failed_node(2).unwrap().await.is_err());
+        let seen = seen.lock().unwrap();
+        let failed = seen.last().unwrap();
+        assert_eq!(
+            failed.controller_command(),
+            Some((ControllerCommandType::RemoveFailedNode, ControllerState::Failed, ControllerError::NotFound))
+        );
+        assert_eq!(failed.byte, ControllerError::NotFound as u8);
+        assert_eq!(failed.description, "ControllerCommand - Failed");
+        network.detach();
+    }
+
+    #[test]
+    fn test_user_alert() {
+        let network = SimulatedNetwork::new(0x0014_0002);
+        let manager = Manager::with_backend(network.clone());
+        let (seen, _watcher) = record(&manager);
+        network.add_node(2, SimulatedNode::default());
+
+        network.user_alert(0, UserAlert::ConfigOutOfDate);
+        network.user_alert(2, UserAlert::ApplicationStatusRetry(30));
+        let alerts: Vec<(u8, UserAlert)> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|notification| notification.notification_type == NotificationType::UserAlerts)
+            .map(|notification| (notification.node_id, notification.user_alert().unwrap()))
+            .collect();
+        assert_eq!(
+            alerts,
+            vec![(0, UserAlert::ConfigOutOfDate), (2, UserAlert::ApplicationStatusRetry(30))]
+        );
+        assert!(seen.lock().unwrap()[0].user_alert().is_none());
+        network.detach();
+    }
+
+    #[test]
+    fn test_user_alert_types() {
+        assert_eq!(UserAlert::from_type(UserAlertType::None, 0), None);
+        assert_eq!(
+            UserAlert::from_type(UserAlertType::NodeReloadRequired, 5),
+            Some(UserAlert::NodeReloadRequired)
+        );
+        assert_eq!(
+            UserAlertType::from_u8(7).and_then(|alert_type| UserAlert::from_type(alert_type, 5)),
+            Some(UserAlert::ApplicationStatusRetry(5))
+        );
+        assert_eq!(UserAlertType::from_u8(10), None);
+        assert_eq!(ControllerCommandType::from_u8(4), Some(ControllerCommandType::RemoveDevice));
+        assert_eq!(ControllerCommandType::from_u8(17), None);
+    }
+}
@@ -0,0 +1,96 @@
+use futures::StreamExt;
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::manager::Manager;
+use openzwave::notification::NotificationType;
+use openzwave::notification_stream::OverflowPolicy;
+use openzwave::value_classes::value_id::ValueContent;
+use std::thread;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_receives_notifications() {
+        let network = SimulatedNetwork::new(0x0002_0001);
+        let manager = Manager::with_backend(network.clone());
+        let mut stream = manager.notifications(16, OverflowPolicy::Block).unwrap();
+
+        network.add_node(2, SimulatedNode::default());
+        network.remove_node(2);
+
+        let first = stream.next().await.unwrap();
+        assert_eq!(first.notification_type, NotificationType::NodeAdded);
+        assert_eq!(first.node_id, 2);
+        let second = stream.next().await.unwrap();
+        assert_eq!(second.notification_type, NotificationType::NodeRemoved);
+        assert_eq!(stream.lagged(), 0);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let network = SimulatedNetwork::new(0x0002_0002);
+        let manager = Manager::with_backend(network.clone());
+        let mut oldest = manager.notifications(2, OverflowPolicy::DropOldest).unwrap();
+        let mut newest = manager.notifications(2, OverflowPolicy::DropNewest).unwrap();
+
+        for node_id in 2..6 {
+            network.add_node(node_id, SimulatedNode::default());
+        }
+
+        assert_eq!(oldest.lagged(), 2);
+        assert_eq!(oldest.next().await.unwrap().node_id, 4);
+        assert_eq!(oldest.next().await.unwrap().node_id, 5);
+        assert_eq!(newest.lagged(), 2);
+        assert_eq!(newest.next().await.unwrap().node_id, 2);
+        assert_eq!(newest.next().await.unwrap().node_id, 3);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_block_policy_waits_for_consumer() {
+        let network = SimulatedNetwork::new(0x0002_0003);
+        let manager = Manager::with_backend(network.clone());
+        let mut stream = manager.notifications(1, OverflowPolicy::Block).unwrap();
+
+        let producer = {
+            let network = network.clone();
+            thread::spawn(move || {
+                for node_id in 2..12 {
+                    network.add_node(node_id, SimulatedNode::default());
+                }
+            })
+        };
+
+        let mut received = Vec::new();
+        while received.len() < 10 {
+            received.push(stream.next().await.unwrap().node_id);
+        }
+        producer.join().unwrap();
+        assert_eq!(received, (2..12).collect::<Vec<u8>>());
+        assert_eq!(stream.lagged(), 0);
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_queued_values_keep_notified_content() {
+        let network = SimulatedNetwork::new(0x0002_0004);
+        let manager = Manager::with_backend(network.clone());
+        let mut stream = manager.notifications(16, OverflowPolicy::Block).unwrap();
+
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(1)));
+        network.update_value(&level, ValueContent::Byte(2));
+        network.remove_node(2);
+
+        let mut contents = Vec::new();
+        while contents.len() < 2 {
+            if let Some(value_id) = stream.next().await.unwrap().value_id {
+                contents.push(value_id.value().to_string());
+            }
+        }
+        assert_eq!(contents, vec!["1", "2"]);
+        network.detach();
+    }
+}
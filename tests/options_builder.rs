@@ -0,0 +1,82 @@
+use openzwave::error::Error;
+use openzwave::options::{LogLevel, OptionsBuilder, SecurityStrategy};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn invalid_option(builder: OptionsBuilder) -> &'static str {
+        match builder.validate() {
+            Err(Error::InvalidOption(name, _)) => name,
+            other => panic!("expected an invalid option, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let builder = OptionsBuilder::new()
+            .logging(true)
+            .save_log_level(LogLevel::Warning)
+            .queue_log_level(LogLevel::Debug)
+            .dump_trigger_level(LogLevel::Error)
+            .poll_interval(500)
+            .interval_between_polls(true)
+            .network_key([0x01; 16]);
+        assert!(builder.validate().is_ok());
+
+        assert_eq!(invalid_option(builder.clone().poll_interval(-1)), "PollInterval");
+        assert_eq!(invalid_option(builder.clone().retry_timeout(0)), "RetryTimeout");
+        assert_eq!(invalid_option(builder.clone().log_file_name("")), "LogFileName");
+        assert_eq!(invalid_option(builder.clone().user_path("/tmp/\0")), "UserPath");
+        assert_eq!(
+            invalid_option(builder.clone().queue_log_level(LogLevel::Fatal)),
+            "QueueLogLevel"
+        );
+        assert_eq!(
+            invalid_option(builder.clone().custom_secured_cc(vec![0x62])),
+            "CustomSecuredCC"
+        );
+        assert!(builder
+            .security_strategy(SecurityStrategy::Custom)
+            .custom_secured_cc(vec![0x62])
+            .validate()
+            .is_ok());
+    }
+
+    #[cfg(feature = "config_file")]
+    #[test]
+    fn test_config_files() {
+        let expected = OptionsBuilder::new()
+            .user_path("/var/lib/zwave")
+            .log_to_rust(true)
+            .save_log_level(LogLevel::Info)
+            .poll_interval(30000)
+            .enable_sis(false)
+            .security_strategy(SecurityStrategy::Supported);
+
+        let toml = r#"
+            UserPath = "/var/lib/zwave"
+            LogToRust = true
+            SaveLogLevel = "Info"
+            PollInterval = 30000
+            EnableSIS = false
+            SecurityStrategy = "SUPPORTED"
+        "#;
+        assert_eq!(OptionsBuilder::from_toml_str(toml).unwrap(), expected);
+
+        let json = r#"{
+            "UserPath": "/var/lib/zwave",
+            "LogToRust": true,
+            "SaveLogLevel": "Info",
+            "PollInterval": 30000,
+            "EnableSIS": false,
+            "SecurityStrategy": "SUPPORTED"
+        }"#;
+        assert_eq!(OptionsBuilder::from_json_str(json).unwrap(), expected);
+
+        match OptionsBuilder::from_toml_str("PollIntervall = 30000") {
+            Err(Error::ConfigFileError(message)) => assert!(message.contains("PollIntervall")),
+            other => panic!("the typo should be rejected, got {:?}", other),
+        }
+    }
+}
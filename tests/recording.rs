@@ -0,0 +1,186 @@
+#![cfg(feature = "serde_serialization")]
+
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::Error;
+use openzwave::manager::Manager;
+use openzwave::notification::{Notification, NotificationType, NotificationValue, UserAlert};
+use openzwave::recording::{RecordedNotification, Recorder, Replay, ReplaySpeed};
+use openzwave::value_classes::value_id::ValueContent;
+use std::io::{self, Cursor, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// A writer the test can read back once the recorder is done
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn describe(notification: &Notification) -> String {
+    match notification.value_id {
+        Some(ref value_id) => format!(
+            "{:?} {} {}",
+            notification.notification_type,
+            value_id.label(),
+            value_id.value()
+        ),
+        None => format!("{:?}", notification.notification_type),
+    }
+}
+
+fn record_network(home_id: u32) -> Vec<u8> {
+    let network = SimulatedNetwork::new(home_id);
+    let manager = Manager::with_backend(network.clone());
+    let buffer = Buffer::default();
+    let _recorder = manager.add_watcher(Recorder::new(buffer.clone())).unwrap();
+
+    network.add_node(2, SimulatedNode::default());
+    let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+    network.add_value(2, SimulatedValue::new(0x31, 1, "Temperature", ValueContent::Int(20)));
+    network.update_value(&level, ValueContent::Byte(99));
+    network.user_alert(2, UserAlert::ApplicationStatusRetry(5));
+    network.detach();
+
+    let recorded = buffer.0.lock().unwrap().clone();
+    recorded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay() {
+        let recorded = record_network(0x0017_0001);
+        let replay = Replay::from_reader(Cursor::new(recorded)).unwrap();
+        assert_eq!(replay.records().len(), 5);
+        assert_eq!(replay.records()[1].value.as_ref().unwrap().key, "0x00170001:2:0x26:1:0");
+
+        let seen: Arc<Mutex<Vec<String>>> = Arc::default();
+        {
+            let seen = seen.clone();
+            replay.replay(
+                &move |notification: &Notification| {
+                    seen.lock().unwrap().push(describe(notification))
+                },
+                ReplaySpeed::Instant,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "NodeAdded",
+                "ValueAdded Level 0",
+                "ValueAdded Temperature 20",
+                "ValueChanged Level 99",
+                "UserAlerts",
+            ]
+        );
+
+        let alert = replay.notifications().last().unwrap();
+        assert_eq!(alert.user_alert(), Some(UserAlert::ApplicationStatusRetry(5)));
+    }
+
+    #[test]
+    fn test_content_types_survive() {
+        let replay = Replay::from_reader(Cursor::new(record_network(0x0017_0002))).unwrap();
+        let contents: Vec<ValueContent> = replay
+            .notifications()
+            .filter_map(|notification| notification.value_id)
+            .map(|value_id| value_id.value().clone())
+            .collect();
+        assert!(matches!(contents[0], ValueContent::Byte(0)));
+        assert!(matches!(contents[1], ValueContent::Int(20)));
+        assert!(matches!(contents[2], ValueContent::Byte(99)));
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let record = RecordedNotification {
+            elapsed_ms: 12,
+            notification_type: String::from("Notification"),
+            home_id: 0x0017_0003,
+            node_id: 4,
+            byte: 0,
+            description: String::new(),
+            command: None,
+            payload: Some(openzwave::recording::RecordedPayload::Report(String::from("Dead"))),
+            event: None,
+            value: None,
+            node: None,
+        };
+        let line = serde_json::to_string(&record).unwrap();
+        let parsed: RecordedNotification = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed, record);
+        let notification = parsed.to_notification().unwrap();
+        assert_eq!(notification.notification_type, NotificationType::Notification);
+        assert!(matches!(notification.value, Some(NotificationValue::Report(_))));
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        let valid = r#"{"elapsed_ms":0,"type":"NodeAdded","home_id":1,"node_id":2}"#;
+        let unknown = r#"{"elapsed_ms":0,"type":"Teleported","home_id":1,"node_id":2}"#;
+
+        let replay = Replay::from_reader(Cursor::new(format!("{}\n\n{}\n", valid, valid))).unwrap();
+        assert_eq!(replay.records().len(), 2);
+
+        let error = Replay::from_reader(Cursor::new(format!("{}\nnot json\n", valid))).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("line 2:"));
+
+        let error = Replay::from_reader(Cursor::new(unknown)).unwrap_err();
+        assert!(error.to_string().starts_with("line 1:"));
+    }
+
+    #[test]
+    fn test_accelerated_replay() {
+        let lines = [0, 200, 400]
+            .iter()
+            .map(|elapsed_ms| {
+                format!(
+                    r#"{{"elapsed_ms":{},"type":"NodeAdded","home_id":1,"node_id":2}}"#,
+                    elapsed_ms
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let replay = Replay::from_reader(Cursor::new(lines)).unwrap();
+
+        let start = Instant::now();
+        replay.replay(&|_: &Notification| {}, ReplaySpeed::Accelerated(4.0)).unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_invalid_speed() {
+        let line = r#"{"elapsed_ms":2000,"type":"NodeAdded","home_id":1,"node_id":2}"#;
+        let replay = Replay::from_reader(Cursor::new(line)).unwrap();
+        let delivered = Arc::new(Mutex::new(0));
+        let watcher = {
+            let delivered = delivered.clone();
+            move |_: &Notification| *delivered.lock().unwrap() += 1
+        };
+
+        // the last one would wait longer than a Duration holds
+        for factor in &[0.0, -2.0, f64::NAN, f64::INFINITY, 1e-20] {
+            let result = replay.replay(&watcher, ReplaySpeed::Accelerated(*factor));
+            assert!(matches!(result, Err(Error::InvalidParameter("speed", _))), "{} accepted", factor);
+        }
+        assert_eq!(*delivered.lock().unwrap(), 0);
+        replay.replay(&watcher, ReplaySpeed::Accelerated(100.0)).unwrap();
+        assert_eq!(*delivered.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::manager::Manager;
+use openzwave::notification::{Notification, NotificationType};
+use openzwave::router::{NotificationFilter, Router};
+use openzwave::value_classes::value_id::{CommandClass, ValueContent, ValueGenre};
+use std::sync::{Arc, Mutex};
+
+type Seen = Arc<Mutex<Vec<String>>>;
+
+// Records the type and value label of what it gets
+fn recorder(seen: &Seen) -> impl Fn(&Notification) + Send + Sync {
+    let seen = seen.clone();
+    move |notification: &Notification| {
+        let label = notification
+            .value_id
+            .as_ref()
+            .map_or(String::new(), |value_id| value_id.label().to_string());
+        seen.lock()
+            .unwrap()
+            .push(format!("{:?} {}", notification.notification_type, label).trim().to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let network = SimulatedNetwork::new(0x0016_0001);
+        let manager = Manager::with_backend(network.clone());
+        let router = Router::new(&manager).unwrap();
+        let (switches, node_3, temperatures) = (Seen::default(), Seen::default(), Seen::default());
+        let _switches = router.route(
+            NotificationFilter::new()
+                .command_class(CommandClass::SwitchBinary)
+                .notification_type(NotificationType::ValueChanged),
+            recorder(&switches),
+        );
+        let _node_3 = router.route(NotificationFilter::new().home_id(0x0016_0001).node(3), recorder(&node_3));
+        let _temperatures = router.route(
+            NotificationFilter::new().genre(ValueGenre::User).label("^Temp").unwrap(),
+            recorder(&temperatures),
+        );
+
+        network.add_node(2, SimulatedNode::default());
+        network.add_node(3, SimulatedNode::default());
+        let switch = network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        let temperature = network.add_value(3, SimulatedValue::new(0x31, 1, "Temperature", ValueContent::Int(20)));
+        let mut setting = SimulatedValue::new(0x31, 2, "Temperature offset", ValueContent::Int(0));
+        setting.genre = ValueGenre::Config;
+        network.add_value(3, setting);
+        network.update_value(&switch, ValueContent::Bool(true));
+        network.update_value(&temperature, ValueContent::Int(21));
+
+        assert_eq!(*switches.lock().unwrap(), vec!["ValueChanged Switch"]);
+        assert_eq!(
+            *node_3.lock().unwrap(),
+            vec![
+                "NodeAdded",
+                "ValueAdded Temperature",
+                "ValueAdded Temperature offset",
+                "ValueChanged Temperature"
+            ]
+        );
+        assert_eq!(*temperatures.lock().unwrap(), vec!["ValueAdded Temperature", "ValueChanged Temperature"]);
+        network.detach();
+    }
+
+    #[test]
+    fn test_value_criteria_need_a_value() {
+        let network = SimulatedNetwork::new(0x0016_0002);
+        let manager = Manager::with_backend(network.clone());
+        let router = Router::new(&manager).unwrap();
+        let (first, other) = (Seen::default(), Seen::default());
+        let _first = router.route(NotificationFilter::new().instance(1), recorder(&first));
+        let _other = router.route(NotificationFilter::new().instance(2), recorder(&other));
+
+        network.add_node(2, SimulatedNode::default());
+        network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        assert_eq!(*first.lock().unwrap(), vec!["ValueAdded Switch"]);
+        assert!(other.lock().unwrap().is_empty());
+        assert!(NotificationFilter::new().label("(").is_err());
+        network.detach();
+    }
+
+    #[test]
+    fn test_dropped_route() {
+        let network = SimulatedNetwork::new(0x0016_0003);
+        let manager = Manager::with_backend(network.clone());
+        let router = Router::new(&manager).unwrap();
+        let (kept, dropped) = (Seen::default(), Seen::default());
+        let _kept = router.route(NotificationFilter::new(), recorder(&kept));
+        let route = router.route(NotificationFilter::new(), recorder(&dropped));
+
+        network.add_node(2, SimulatedNode::default());
+        drop(route);
+        network.add_node(3, SimulatedNode::default());
+        assert_eq!(kept.lock().unwrap().len(), 2);
+        assert_eq!(dropped.lock().unwrap().len(), 1);
+
+        drop(router);
+        network.add_node(4, SimulatedNode::default());
+        assert_eq!(kept.lock().unwrap().len(), 2);
+        network.detach();
+    }
+
+    #[test]
+    fn test_panicking_handler() {
+        let network = SimulatedNetwork::new(0x0016_0004);
+        let manager = Manager::with_backend(network.clone());
+        manager.set_watcher_panic_hook(|_| {});
+        manager.set_watcher_quarantine_after(Some(1));
+        let router = Router::new(&manager).unwrap();
+        let seen = Seen::default();
+        let _panicking = router.route(NotificationFilter::new(), |_: &Notification| panic!("handler bug"));
+        let _recorder = router.route(NotificationFilter::new(), recorder(&seen));
+
+        network.add_node(2, SimulatedNode::default());
+        network.add_node(3, SimulatedNode::default());
+        assert_eq!(seen.lock().unwrap().len(), 2);
+        assert_eq!(manager.swallowed_watcher_panics(), 1);
+        network.detach();
+    }
+}
@@ -0,0 +1,115 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::{Error, GetSetError};
+use openzwave::value_classes::value_id::{Schedule, SwitchPoint, ValueContent};
+use std::time::Duration;
+
+fn weekday() -> ValueContent {
+    ValueContent::Schedule(Schedule::new(vec![SwitchPoint::new(22, 0, -20), SwitchPoint::new(6, 30, 0)]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_schedule_content() {
+        let network = SimulatedNetwork::new(0x000e_0001);
+        network.add_node(2, SimulatedNode::default());
+        let monday = network.add_value(2, SimulatedValue::new(0x46, 1, "Monday", weekday()));
+
+        let schedule = monday.as_schedule().unwrap();
+        assert_eq!(schedule.switch_points, vec![SwitchPoint::new(6, 30, 0), SwitchPoint::new(22, 0, -20)]);
+        match monday.value() {
+            ValueContent::Schedule(content) => assert_eq!(content, &schedule),
+            other => panic!("expected a schedule, got {:?}", other),
+        }
+
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        assert!(matches!(level.as_schedule(), Err(Error::GetError(GetSetError::WrongType))));
+        network.detach();
+    }
+
+    #[test]
+    fn test_switch_points() {
+        let network = SimulatedNetwork::new(0x000e_0002);
+        network.add_node(2, SimulatedNode::default());
+        let monday = network.add_value(2, SimulatedValue::new(0x46, 1, "Monday", weekday()));
+        let switch_points = || monday.as_schedule().unwrap().switch_points;
+
+        monday.set_switch_point(SwitchPoint::new(12, 0, 121)).unwrap();
+        assert_eq!(switch_points()[1], SwitchPoint::new(12, 0, 121));
+        // a switch point at the same time is replaced
+        monday.set_switch_point(SwitchPoint::new(12, 0, -10)).unwrap();
+        assert_eq!(switch_points().len(), 3);
+        assert_eq!(switch_points()[1].setback, -10);
+
+        monday.remove_switch_point(6, 30).unwrap();
+        assert_eq!(switch_points(), vec![SwitchPoint::new(12, 0, -10), SwitchPoint::new(22, 0, -20)]);
+        assert!(matches!(monday.remove_switch_point(6, 30), Err(Error::SetError(_))));
+
+        monday.clear_switch_points().unwrap();
+        assert!(switch_points().is_empty());
+        network.detach();
+    }
+
+    #[test]
+    fn test_switch_point_limits() {
+        let network = SimulatedNetwork::new(0x000e_0003);
+        network.add_node(2, SimulatedNode::default());
+        let monday = network.add_value(2, SimulatedValue::new(0x46, 1, "Monday", weekday()));
+
+        assert!(matches!(
+            monday.set_switch_point(SwitchPoint::new(24, 0, 0)),
+            Err(Error::InvalidParameter("hours", _))
+        ));
+        assert!(matches!(monday.remove_switch_point(6, 60), Err(Error::InvalidParameter("minutes", _))));
+
+        for hours in 7..14 {
+            monday.set_switch_point(SwitchPoint::new(hours, 0, 0)).unwrap();
+        }
+        assert_eq!(monday.as_schedule().unwrap().switch_points.len(), 9);
+        assert!(matches!(monday.set_switch_point(SwitchPoint::new(15, 0, 0)), Err(Error::SetError(_))));
+        assert_eq!(monday.as_schedule().unwrap().switch_points.len(), 9);
+
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        assert!(matches!(
+            level.set_switch_point(SwitchPoint::new(6, 0, 0)),
+            Err(Error::SetError(GetSetError::WrongType))
+        ));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_schedule_write() {
+        let network = SimulatedNetwork::new(0x000e_0004);
+        network.add_node(2, SimulatedNode::default());
+        let monday = network.add_value(2, SimulatedValue::new(0x46, 1, "Monday", weekday()));
+
+        let weekend = Schedule::new(vec![SwitchPoint::new(9, 0, 0), SwitchPoint::new(23, 30, -30)]);
+        let confirmed = monday
+            .set_and_confirm(ValueContent::Schedule(weekend.clone()), Duration::from_secs(1))
+            .await
+            .unwrap();
+        match confirmed.value() {
+            ValueContent::Schedule(schedule) => assert_eq!(schedule, &weekend),
+            other => panic!("expected a schedule, got {:?}", other),
+        }
+        assert_eq!(monday.as_schedule().unwrap(), weekend);
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_schedule_serialization() {
+        let json = serde_json::to_value(weekday()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "switch_points": [
+                    { "hours": 6, "minutes": 30, "setback": 0 },
+                    { "hours": 22, "minutes": 0, "setback": -20 },
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::{ConfirmationError, Error};
+use openzwave::notification::{Notification, NotificationCode, NotificationType, NotificationValue};
+use openzwave::value_classes::value_id::ValueContent;
+use std::time::Duration;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_confirmed_write() {
+        let network = SimulatedNetwork::new(0x0003_0001);
+        network.add_node(4, SimulatedNode::default());
+        let level = network.add_value(4, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+
+        let confirmed = level
+            .set_and_confirm(ValueContent::Byte(42), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(confirmed.value().to_string(), "42");
+
+        let wrong_type = level
+            .set_and_confirm(ValueContent::Bool(true), Duration::from_secs(1))
+            .await;
+        assert!(matches!(wrong_type, Err(Error::SetError(_))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_write_times_out() {
+        let network = SimulatedNetwork::new(0x0003_0002);
+        network.add_node(4, SimulatedNode::default());
+        let switch = network.add_value(4, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        network.set_auto_confirm(false);
+
+        let result = switch
+            .set_and_confirm(ValueContent::Bool(true), Duration::from_millis(20))
+            .await;
+        assert!(matches!(result, Err(Error::ConfirmationError(ConfirmationError::Timeout))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_dead_node_fails_write() {
+        let network = SimulatedNetwork::new(0x0003_0003);
+        network.add_node(4, SimulatedNode::default());
+        let switch = network.add_value(4, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        network.set_auto_confirm(false);
+
+        let reporter = {
+            let network = network.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                network.emit(&Notification {
+                    notification_type: NotificationType::Notification,
+                    home_id: network.home_id(),
+                    node_id: 4,
+                    value_id: None,
+                    value: Some(NotificationValue::Report(NotificationCode::Dead)),
+                    event: None,
+                    command: None,
+                    byte: NotificationCode::Dead as u8,
+                    description: String::new(),
+                });
+            })
+        };
+
+        let result = switch
+            .set_and_confirm(ValueContent::Bool(true), Duration::from_secs(5))
+            .await;
+        reporter.await.unwrap();
+        assert!(matches!(result, Err(Error::ConfirmationError(ConfirmationError::NodeDead))));
+        network.detach();
+    }
+}
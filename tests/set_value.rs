@@ -0,0 +1,165 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::{Error, GetSetError};
+use openzwave::value_classes::value_id::{
+    BitSetValue, DecimalValue, ListValue, Schedule, SwitchPoint, ValueContent, ValueID,
+};
+use std::time::Duration;
+
+fn dimmer_level() -> SimulatedValue {
+    SimulatedValue { min: 0, max: 99, ..SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)) }
+}
+
+fn heating_setpoint() -> SimulatedValue {
+    let heating = DecimalValue::from_f32(20.0, 1).unwrap();
+    SimulatedValue::new(0x43, 1, "Heating", ValueContent::Decimal(heating))
+}
+
+fn thermostat_mode() -> SimulatedValue {
+    let modes = ListValue::new(vec![("Off", 0), ("Heat", 1), ("Cool", 2)], 0);
+    SimulatedValue::new(0x40, 0, "Mode", ValueContent::List(modes))
+}
+
+// What the network holds now, the ValueID keeps the content it was first read with
+fn current(value_id: &ValueID) -> String {
+    ValueID::from_packed_id(value_id.get_home_id(), value_id.id()).value().to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_checks_type_and_range() {
+        let network = SimulatedNetwork::new(0x0010_0001);
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, dimmer_level());
+
+        level.set(ValueContent::Byte(42)).unwrap();
+        assert_eq!(current(&level), "42");
+        level.set(ValueContent::Byte(99)).unwrap();
+        assert!(matches!(
+            level.set(ValueContent::Byte(100)),
+            Err(Error::SetError(GetSetError::OutOfRange { min: 0, max: 99 }))
+        ));
+        assert!(matches!(level.set(ValueContent::Int(42)), Err(Error::SetError(GetSetError::WrongType))));
+        assert_eq!(current(&level), "99");
+
+        // without a range from OpenZWave, any value of the type goes
+        let setpoint = network.add_value(2, heating_setpoint());
+        setpoint.set(ValueContent::Decimal(DecimalValue::from_f32(-5.5, 1).unwrap())).unwrap();
+        assert_eq!(current(&setpoint), "-5.5");
+        network.detach();
+    }
+
+    #[test]
+    fn test_set_lists_and_read_only() {
+        let network = SimulatedNetwork::new(0x0010_0002);
+        network.add_node(2, SimulatedNode::default());
+        let mode = network.add_value(2, thermostat_mode());
+
+        mode.set(ValueContent::List(ListValue::new(vec![("Cool", 2)], 2))).unwrap();
+        assert_eq!(mode.as_list().unwrap().selection_as_string().unwrap(), "Cool");
+        assert!(matches!(
+            mode.set(ValueContent::List(ListValue::new(vec![("Dry", 8)], 8))),
+            Err(Error::SetError(GetSetError::NotAListItem))
+        ));
+
+        let battery = SimulatedValue::new(0x80, 0, "Battery", ValueContent::Byte(80));
+        let battery = network.add_value(2, SimulatedValue { read_only: true, ..battery });
+        assert!(matches!(battery.set(ValueContent::Byte(10)), Err(Error::SetError(GetSetError::ReadOnly))));
+        assert!(matches!(battery.set_from_str("10"), Err(Error::SetError(GetSetError::ReadOnly))));
+        network.detach();
+    }
+
+    #[test]
+    fn test_set_from_str() {
+        let network = SimulatedNetwork::new(0x0010_0003);
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, dimmer_level());
+        let mode = network.add_value(2, thermostat_mode());
+        let switch = network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        let setpoint = network.add_value(2, heating_setpoint());
+
+        level.set_from_str(" 55 ").unwrap();
+        assert_eq!(current(&level), "55");
+        assert!(matches!(
+            level.set_from_str("150"),
+            Err(Error::SetError(GetSetError::OutOfRange { max: 99, .. }))
+        ));
+        // out of the type rather than of the value
+        assert!(matches!(
+            level.set_from_str("-1"),
+            Err(Error::SetError(GetSetError::OutOfRange { min: 0, max: 255 }))
+        ));
+        assert!(matches!(level.set_from_str("half"), Err(Error::SetError(GetSetError::ParseError))));
+
+        mode.set_from_str("Heat").unwrap();
+        assert_eq!(mode.as_list().unwrap().selection_as_int().unwrap(), 1);
+        assert!(matches!(mode.set_from_str("Dry"), Err(Error::SetError(GetSetError::NotAListItem))));
+
+        switch.set_from_str("True").unwrap();
+        assert_eq!(current(&switch), "true");
+        assert!(matches!(switch.set_from_str("yes"), Err(Error::SetError(GetSetError::ParseError))));
+
+        setpoint.set_from_str("21.5").unwrap();
+        assert_eq!(current(&setpoint), "21.5");
+        assert!(matches!(setpoint.set_from_str("NaN"), Err(Error::SetError(GetSetError::ParseError))));
+        network.detach();
+    }
+
+    #[test]
+    fn test_set_from_str_structured() {
+        let network = SimulatedNetwork::new(0x0010_0004);
+        network.add_node(2, SimulatedNode::default());
+        let monday = network.add_value(
+            2,
+            SimulatedValue::new(0x46, 1, "Monday", ValueContent::Schedule(Schedule::default())),
+        );
+        let code = network.add_value(2, SimulatedValue::new(0x63, 0, "Code", ValueContent::Raw(vec![])));
+        let alarms = network.add_value(
+            2,
+            SimulatedValue::new(
+                0x70,
+                3,
+                "Alarms",
+                ValueContent::BitSet(
+                    BitSetValue::new(1, vec![(1, "Motion", true), (2, "Tamper", false)]).unwrap()
+                ),
+            ),
+        );
+
+        monday.set_from_str("22:00 -20, 06:30 0").unwrap();
+        assert_eq!(
+            monday.as_schedule().unwrap().switch_points,
+            vec![SwitchPoint::new(6, 30, 0), SwitchPoint::new(22, 0, -20)]
+        );
+        assert!(matches!(monday.set_from_str("6h30 0"), Err(Error::SetError(GetSetError::ParseError))));
+
+        code.set_from_str("0x31c2").unwrap();
+        assert_eq!(*code.as_raw().unwrap(), vec![0x31, 0xc2]);
+        assert!(matches!(code.set_from_str("abc"), Err(Error::SetError(GetSetError::ParseError))));
+
+        alarms.set_from_str("[2]").unwrap();
+        assert_eq!(alarms.as_bitset().unwrap().to_u32(), 0b10);
+        assert!(matches!(alarms.set_from_str("3"), Err(Error::InvalidParameter("position", _))));
+        network.detach();
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_write_is_checked() {
+        let network = SimulatedNetwork::new(0x0010_0005);
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, dimmer_level());
+
+        assert!(matches!(
+            level.set_and_confirm(ValueContent::Byte(200), Duration::from_secs(1)).await,
+            Err(Error::SetError(GetSetError::OutOfRange { .. }))
+        ));
+        let confirmed = level
+            .set_and_confirm(ValueContent::Byte(20), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(matches!(confirmed.value(), ValueContent::Byte(20)));
+        network.detach();
+    }
+}
@@ -0,0 +1,117 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::manager::{Manager, NotificationWatcher};
+use openzwave::node::{Node, NodeBasic};
+use openzwave::notification::{Notification, NotificationType};
+use openzwave::value_classes::value_id::{CommandClass, ValueContent, ValueType};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Recorder {
+    seen: Arc<Mutex<Vec<(NotificationType, u8)>>>,
+}
+
+impl NotificationWatcher for Recorder {
+    fn on_notification(&self, notification: &Notification) {
+        self.seen
+            .lock()
+            .unwrap()
+            .push((notification.notification_type, notification.node_id));
+    }
+}
+
+impl Recorder {
+    fn types(&self) -> Vec<NotificationType> {
+        self.seen.lock().unwrap().iter().map(|(t, _)| *t).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_node_accessors() {
+        let network = SimulatedNetwork::new(0x0001_0001);
+        network.add_node(
+            5,
+            SimulatedNode {
+                product_name: "Wall Plug".into(),
+                location: "Kitchen".into(),
+                neighbors: vec![1, 7],
+                ..SimulatedNode::default()
+            },
+        );
+
+        let node = Node::from_id(0x0001_0001, 5);
+        assert_eq!(node.get_product_name(), "Wall Plug");
+        assert_eq!(node.get_location(), "Kitchen");
+        assert_eq!(node.get_basic(), Some(NodeBasic::RoutingSlave));
+        assert!(node.is_listening_device());
+        let neighbors: Vec<u8> = node
+            .get_neighbors()
+            .unwrap()
+            .iter()
+            .map(|n| n.get_id())
+            .collect();
+        assert_eq!(neighbors, vec![1, 7]);
+        assert_eq!(node.get_controller().get_controller_node_id(), 1);
+        network.detach();
+    }
+
+    #[test]
+    fn test_values_and_notifications() {
+        let network = SimulatedNetwork::new(0x0001_0002);
+        let recorder = Recorder::default();
+        let manager = Manager::with_backend(network.clone());
+        let _watcher = manager.add_watcher(recorder.clone()).unwrap();
+
+        network.add_node(3, SimulatedNode::default());
+        let switch = network.add_value(
+            3,
+            SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)),
+        );
+        assert_eq!(switch.get_type(), ValueType::Bool);
+        assert_eq!(switch.get_command_class(), Some(CommandClass::SwitchBinary));
+        assert_eq!(switch.get_node_id(), 3);
+        assert_eq!(switch.label(), "Switch");
+
+        switch.set_bool(true).unwrap();
+        assert!(switch.set_byte(1).is_err());
+        assert_eq!(
+            recorder.types(),
+            vec![
+                NotificationType::NodeAdded,
+                NotificationType::ValueAdded,
+                NotificationType::ValueChanged
+            ]
+        );
+
+        let changed = network.values(3).pop().unwrap();
+        assert_eq!(changed.value().to_string(), "true");
+        network.detach();
+    }
+
+    #[test]
+    fn test_add_driver_announces_network() {
+        let network = SimulatedNetwork::new(0x0001_0003);
+        network.add_node(2, SimulatedNode::default());
+        let recorder = Recorder::default();
+        let mut manager = Manager::with_backend(network.clone());
+        let _watcher = manager.add_watcher(recorder.clone()).unwrap();
+        manager.add_driver("/dev/ttyACM0").unwrap();
+        assert!(manager.add_driver("/dev/ttyACM0").is_err());
+
+        assert_eq!(
+            recorder.types(),
+            vec![
+                NotificationType::DriverReady,
+                NotificationType::NodeAdded,
+                NotificationType::NodeAdded,
+                NotificationType::NodeQueriesComplete,
+                NotificationType::NodeQueriesComplete,
+                NotificationType::AllNodesQueried
+            ]
+        );
+        network.detach();
+    }
+}
@@ -0,0 +1,74 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::controller::Controller;
+use openzwave::node::Node;
+use openzwave::statistics::{DriverStatistics, NodeStatistics};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_driver_statistics() {
+        let network = SimulatedNetwork::new(0x0009_0001);
+        let controller = Controller::new(0x0009_0001);
+        assert_eq!(controller.get_driver_statistics(), DriverStatistics::default());
+
+        network.set_driver_statistics(DriverStatistics {
+            sof_count: 120,
+            ack_count: 118,
+            nak_count: 1,
+            can_count: 1,
+            dropped: 2,
+            ..DriverStatistics::default()
+        });
+        let stats = controller.get_driver_statistics();
+        assert_eq!(stats.sof_count, 120);
+        assert_eq!(stats.ack_count, 118);
+        assert_eq!(stats.dropped, 2);
+        network.detach();
+    }
+
+    #[test]
+    fn test_node_statistics() {
+        let network = SimulatedNetwork::new(0x0009_0002);
+        network.add_node(2, SimulatedNode::default());
+        let node = Node::from_id(0x0009_0002, 2);
+        assert_eq!(node.get_statistics(), NodeStatistics::default());
+
+        network.set_node_statistics(2, NodeStatistics {
+            sent_count: 10,
+            received_count: 8,
+            last_request_rtt: 40,
+            average_request_rtt: 35,
+            received_timestamp: String::from("2026-10-17 10:00:00:000"),
+            quality: 75,
+            last_received_message: vec![0x01, 0x04, 0x00, 0x04, 0x02, 0xf9],
+            ..NodeStatistics::default()
+        });
+        let stats = node.get_statistics();
+        assert_eq!(stats.sent_count, 10);
+        assert_eq!(stats.average_request_rtt, 35);
+        assert_eq!(stats.quality, 75);
+        assert_eq!(stats.last_received_message.len(), 6);
+
+        // unknown nodes have no traffic
+        assert_eq!(Node::from_id(0x0009_0002, 9).get_statistics(), NodeStatistics::default());
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_statistics_serialization() {
+        let stats = NodeStatistics {
+            quality: 60,
+            last_received_message: vec![0x01, 0x02],
+            ..NodeStatistics::default()
+        };
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["quality"], 60);
+        assert_eq!(json["last_received_message"], serde_json::json!([1, 2]));
+
+        let json = serde_json::to_value(DriverStatistics { retries: 3, ..DriverStatistics::default() }).unwrap();
+        assert_eq!(json["retries"], 3);
+    }
+}
@@ -0,0 +1,81 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::controller::Controller;
+use openzwave::node::NodeBasic;
+use openzwave::topology::Topology;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 1 (controller) -- 2 -- 3 -- 4, and 5 behind the battery powered 6
+    fn build_mesh(home_id: u32) -> Arc<SimulatedNetwork> {
+        let network = SimulatedNetwork::new(home_id);
+        for node_id in 2..=4 {
+            network.add_node(node_id, SimulatedNode::default());
+        }
+        network.add_node(5, SimulatedNode::default());
+        network.add_node(6, SimulatedNode { listening: false, routing: false, basic: 3, ..SimulatedNode::default() });
+        network.set_neighbors(1, vec![2, 6]);
+        network.set_neighbors(2, vec![1, 3]);
+        network.set_neighbors(3, vec![2, 4]);
+        network.set_neighbors(4, vec![3]);
+        network.set_neighbors(5, vec![6]);
+        network.set_neighbors(6, vec![1, 5]);
+        network
+    }
+
+    #[test]
+    fn test_topology_analysis() {
+        let network = build_mesh(0x000a_0001);
+        let topology = Topology::build(&Controller::new(0x000a_0001));
+
+        assert_eq!(topology.nodes().map(|node| node.node_id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(topology.node(6).unwrap().basic, Some(NodeBasic::Slave));
+        assert_eq!(topology.edges().collect::<Vec<_>>(), vec![(1, 2), (1, 6), (2, 3), (3, 4), (5, 6)]);
+        assert_eq!(topology.unreachable(), vec![5]);
+        assert_eq!(topology.single_points_of_failure(), vec![2, 3]);
+
+        // a second route to 4 removes 3 as a single point of failure
+        network.set_neighbors(4, vec![2, 3]);
+        let topology = Topology::from_nodes(&Controller::new(0x000a_0001), 1..=6);
+        assert_eq!(topology.single_points_of_failure(), vec![2]);
+
+        // a failed relay cuts the nodes behind it
+        network.update_node(2, |node| node.failed = true);
+        let topology = Topology::build(&Controller::new(0x000a_0001));
+        assert_eq!(topology.unreachable(), vec![3, 4, 5]);
+        network.detach();
+    }
+
+    #[test]
+    fn test_topology_dot() {
+        let network = build_mesh(0x000a_0002);
+        network.update_node(2, |node| node.name = String::from("Hall \"east\""));
+        let dot = Topology::build(&Controller::new(0x000a_0002)).to_dot();
+
+        assert!(dot.starts_with("graph \"zwave_000a0002\" {\n"));
+        assert!(dot.contains("  1 [label=\"1: Simulated Controller\\nStatic Controller\", shape=doublecircle"));
+        assert!(dot.contains("  2 [label=\"2: Hall \\\"east\\\"\\nRouting Slave\""));
+        assert!(dot.contains("  5 [label=\"5: \\nRouting Slave\", style=filled, fillcolor=pink];"));
+        assert!(dot.contains("  6 [label=\"6: \\nSlave\", style=\"dashed,filled\", fillcolor=white];"));
+        assert!(dot.contains("  3 -- 4;\n"));
+        assert!(dot.ends_with("}\n"));
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_topology_json() {
+        let network = build_mesh(0x000a_0003);
+        let json = Topology::build(&Controller::new(0x000a_0003)).to_json();
+
+        assert_eq!(json["controller"], 1);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 6);
+        assert_eq!(json["nodes"][1]["single_point_of_failure"], true);
+        assert_eq!(json["nodes"][4]["reachable"], false);
+        assert_eq!(json["nodes"][5]["basic"], "Slave");
+        assert_eq!(json["edges"][0], serde_json::json!({ "source": 1, "target": 2 }));
+        network.detach();
+    }
+}
@@ -1,62 +1,39 @@
-use openzwave::value_classes::value_id::{DecimalValue, ValueContent};
+use openzwave::value_classes::value_id::{
+    BitSetValue, DecimalValue, ListValue, Schedule, SwitchPoint, ValueContent,
+};
 
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
     fn test_decimal_value() {
-        assert_eq!(
-            DecimalValue {
-                value: 23_f32,
-                precision: 1,
-            }
-            .to_string(),
-            "23.0"
-        );
-
-        assert_eq!(
-            DecimalValue {
-                value: 23_f32,
-                precision: 2,
-            }
-            .to_string(),
-            "23.00"
-        );
-
-        assert_eq!(
-            DecimalValue {
-                value: 123.456123_f32,
-                precision: 3,
-            }
-            .to_string(),
-            "123.456"
-        );
-
-        assert_eq!(
-            DecimalValue {
-                value: 123.123_f32,
-                precision: 0,
-            }
-            .to_string(),
-            "123"
-        );
-
-        assert_eq!(DecimalValue::from_f32(123_f32, 0).to_string(), "123");
-        assert_eq!(DecimalValue::from_f32(123.45_f32, 2).to_string(), "123.45");
+        assert_eq!(DecimalValue::new(230, 1).unwrap().to_string(), "23.0");
+        assert_eq!(DecimalValue::new(2300, 2).unwrap().to_string(), "23.00");
+        assert_eq!(DecimalValue::from_f32(123.456123_f32, 3).unwrap().to_string(), "123.456");
+        assert_eq!(DecimalValue::from_f32(123.123_f32, 0).unwrap().to_string(), "123");
+        assert_eq!(DecimalValue::from_f32(123_f32, 0).unwrap().to_string(), "123");
+        assert_eq!(DecimalValue::from_f32(123.45_f32, 2).unwrap().to_string(), "123.45");
     }
 
     #[test]
     fn test_value_content_unsupported() {
-        // unsupported type yet
-        assert_eq!(ValueContent::Raw.to_string(), "null");
-        assert_eq!(ValueContent::Schedule.to_string(), "null");
+        assert_eq!(ValueContent::Unknown.to_string(), "null");
     }
 
     #[test]
     fn test_value_content_supported() {
         assert_eq!(ValueContent::String("foo".into()).to_string(), "\"foo\"");
-        // Unsure about the List thing.
-        assert_eq!(ValueContent::List("foo".into()).to_string(), "\"foo\"");
+        let list = ListValue::new(vec![("foo", 1), ("bar", 2)], 1);
+        assert_eq!(ValueContent::List(list).to_string(), "\"foo\"");
+        let schedule = Schedule::new(vec![
+            SwitchPoint::new(22, 30, 121),
+            SwitchPoint::new(6, 0, -20),
+        ]);
+        assert_eq!(ValueContent::Schedule(schedule).to_string(), "\"06:00 -20, 22:30 121\"");
+        assert_eq!(ValueContent::Raw(vec![0x0a, 0xff, 0x00]).to_string(), "\"0aff00\"");
+        let bitset =
+            BitSetValue::new(1, vec![(1, "Motion", true), (2, "Tamper", false), (4, "Low battery", true)]).unwrap();
+        assert_eq!(ValueContent::BitSet(bitset).to_string(), "[1, 4]");
         assert_eq!(ValueContent::Bool(true).to_string(), "true");
         assert_eq!(ValueContent::Button(true).to_string(), "true");
         assert_eq!(ValueContent::Byte(42).to_string(), "42");
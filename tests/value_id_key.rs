@@ -0,0 +1,131 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::error::ParseError;
+use openzwave::manager::Manager;
+use openzwave::network_state::NetworkState;
+use openzwave::value_classes::value_id::{ValueContent, ValueGenre, ValueID, ValueIdKey, ValueType};
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_packed_id_round_trip() {
+        let key = ValueIdKey::new(0xabcd_1234, 5, 0x70)
+            .genre(ValueGenre::Config)
+            .instance(2)
+            .index(300)
+            .value_type(ValueType::Short);
+        let id = key.packed_id().unwrap();
+        // the index lives in the upper word
+        assert_eq!(id >> 48, 300);
+
+        let decoded = ValueIdKey::from_packed_id(0xabcd_1234, id);
+        assert_eq!(decoded, key);
+        assert_eq!(decoded.node_id, 5);
+        assert_eq!(decoded.genre, Some(ValueGenre::Config));
+        assert_eq!(decoded.command_class, 0x70);
+        assert_eq!(decoded.instance, 2);
+        assert_eq!(decoded.index, 300);
+        assert_eq!(decoded.value_type, ValueType::Short);
+
+        // without a genre or a value type there is no packed id
+        assert_eq!(ValueIdKey::new(0xabcd_1234, 5, 0x25).packed_id(), None);
+        let untyped = key.value_type(ValueType::Unknown);
+        assert_eq!(untyped.packed_id(), None);
+    }
+
+    #[test]
+    fn test_string_form() {
+        let key = ValueIdKey::new(0xabcd_1234, 5, 0x25);
+        assert_eq!(key.to_string(), "0xabcd1234:5:0x25:1:0");
+        assert_eq!("0xabcd1234:5:0x25:1:0".parse::<ValueIdKey>(), Ok(key));
+        assert_eq!(
+            "2882343476:5:37:1:0".parse::<ValueIdKey>().unwrap().to_string(),
+            "0xabcd1234:5:0x25:1:0"
+        );
+
+        assert_eq!("0xabcd1234:5:0x25:1".parse::<ValueIdKey>(), Err(ParseError::Format));
+        assert_eq!(
+            "0xabcd1234:300:0x25:1:0".parse::<ValueIdKey>(),
+            Err(ParseError::InvalidField("node_id"))
+        );
+        assert_eq!(
+            "0xabcd1234:5:0x25:1:x".parse::<ValueIdKey>().unwrap_err().to_string(),
+            "invalid index"
+        );
+        assert_eq!("".parse::<ValueIdKey>(), Err(ParseError::Format));
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let typed = ValueIdKey::new(0xabcd_1234, 5, 0x25)
+            .genre(ValueGenre::User)
+            .value_type(ValueType::Bool);
+        let parsed: ValueIdKey = "0xabcd1234:5:0x25:1:0".parse().unwrap();
+        // genre and type don't take part in the identity
+        assert_eq!(typed, parsed);
+
+        let mut names = HashMap::new();
+        names.insert(typed, "switch");
+        assert_eq!(names.get(&parsed), Some(&"switch"));
+
+        let sorted: BTreeMap<ValueIdKey, u8> = vec![
+            (ValueIdKey::new(1, 6, 0x25), 3),
+            (ValueIdKey::new(1, 5, 0x26), 2),
+            (ValueIdKey::new(1, 5, 0x25).index(1), 1),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(sorted.values().cloned().collect::<Vec<u8>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_value_id_key() {
+        let network = SimulatedNetwork::new(0x0012_0001);
+        network.add_node(2, SimulatedNode::default());
+        let mut value = SimulatedValue::new(0x70, 300, "Wake up delay", ValueContent::Int(10));
+        value.genre = ValueGenre::Config;
+        value.instance = 3;
+        let delay = network.add_value(2, value);
+
+        let key = delay.key();
+        assert_eq!(key.to_string(), "0x00120001:2:0x70:3:300");
+        assert_eq!(delay.get_node_id(), 2);
+        assert_eq!(delay.get_command_class_id(), 0x70);
+        assert_eq!(delay.get_instance(), 3);
+        assert_eq!(delay.get_index(), 300);
+
+        let found = ValueID::from_key(&key).unwrap();
+        assert_eq!(found.id(), delay.id());
+        assert_eq!(found.label(), "Wake up delay");
+        // a key read from a string doesn't know the genre
+        assert!(ValueID::from_key(&key.to_string().parse().unwrap()).is_none());
+        network.detach();
+    }
+
+    #[test]
+    fn test_state_value_by_key() {
+        let network = SimulatedNetwork::new(0x0012_0002);
+        let mut manager = Manager::with_backend(network.clone());
+        let state = NetworkState::new(&manager).unwrap();
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(0)));
+        manager.add_driver("/dev/simulated").unwrap();
+
+        let key: ValueIdKey = "0x00120002:2:0x26:1:0".parse().unwrap();
+        assert_eq!(state.value_by_key(&key).unwrap().id(), level.id());
+        assert!(state.value_by_key(&key.index(1)).is_none());
+        network.detach();
+    }
+
+    #[cfg(feature = "serde_serialization")]
+    #[test]
+    fn test_key_serialization() {
+        let key = ValueIdKey::new(0xabcd_1234, 5, 0x25);
+        let json = serde_json::to_value(key).unwrap();
+        assert_eq!(json, serde_json::json!("0xabcd1234:5:0x25:1:0"));
+        assert_eq!(serde_json::from_value::<ValueIdKey>(json).unwrap(), key);
+        assert!(serde_json::from_value::<ValueIdKey>(serde_json::json!("5:0x25")).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue, ZWaveBackend};
+use openzwave::value_classes::value_id::{set_metadata_cache, ValueContent, ValueID};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_keeps_content() {
+        let network = SimulatedNetwork::new(0x0013_0001);
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(1)));
+
+        let value_id = ValueID::from_packed_id(0x0013_0001, level.id());
+        let snapshot = value_id.snapshot().clone();
+        network.update_value(&level, ValueContent::Byte(2));
+        network.set_value_label(level.vid(), "Dimmer");
+        assert_eq!(value_id.value().to_string(), "1");
+        assert_eq!(snapshot.value().to_string(), "1");
+        assert_eq!(snapshot.label(), "Level");
+        // a ValueID never read gets what the network holds now
+        assert_eq!(ValueID::from_packed_id(0x0013_0001, level.id()).value().to_string(), "2");
+        network.detach();
+    }
+
+    #[test]
+    fn test_removed_before_first_use() {
+        let network = SimulatedNetwork::new(0x0013_0002);
+        network.add_node(2, SimulatedNode::default());
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(1)));
+
+        let value_id = ValueID::from_packed_id(0x0013_0002, level.id());
+        network.remove_value(&level);
+        assert!(matches!(value_id.value(), ValueContent::Unknown));
+        assert_eq!(value_id.label(), "");
+        assert_eq!(value_id.get_node_id(), 2);
+        network.detach();
+    }
+
+    #[test]
+    fn test_metadata_cache() {
+        let network = SimulatedNetwork::new(0x0013_0003);
+        network.add_node(2, SimulatedNode::default());
+        set_metadata_cache(true);
+        let level = network.add_value(2, SimulatedValue::new(0x26, 0, "Level", ValueContent::Byte(1)));
+        let fresh = || ValueID::from_packed_id(0x0013_0003, level.id());
+        assert_eq!(fresh().label(), "Level");
+
+        // behind the cache's back
+        network.set_value_label(level.vid(), "Renamed");
+        assert_eq!(fresh().label(), "Level");
+        assert_eq!(fresh().get_label(), "Renamed");
+
+        level.set_label("Dimmer").unwrap();
+        assert_eq!(fresh().label(), "Dimmer");
+
+        // a value added again under the same id isn't the old one
+        network.remove_value(&level);
+        network.add_value(2, SimulatedValue::new(0x26, 0, "Brightness", ValueContent::Byte(1)));
+        assert_eq!(fresh().label(), "Brightness");
+
+        set_metadata_cache(false);
+        network.set_value_label(level.vid(), "Renamed");
+        assert_eq!(fresh().label(), "Renamed");
+        network.detach();
+    }
+}
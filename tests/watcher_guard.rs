@@ -0,0 +1,70 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::manager::Manager;
+use openzwave::notification::Notification;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_guard_unregisters_on_drop() {
+        let network = SimulatedNetwork::new(0x0004_0001);
+        let manager = Manager::with_backend(network.clone());
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let guard = {
+            let count = count.clone();
+            manager
+                .add_watcher(move |_: &Notification| {
+                    count.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap()
+        };
+        network.add_node(2, SimulatedNode::default());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        drop(guard);
+        network.add_node(3, SimulatedNode::default());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        network.detach();
+    }
+
+    #[test]
+    fn test_explicit_remove() {
+        let network = SimulatedNetwork::new(0x0004_0002);
+        let manager = Manager::with_backend(network.clone());
+        let guard = manager.add_watcher(|_: &Notification| {}).unwrap();
+        assert!(guard.remove().is_ok());
+        network.detach();
+    }
+
+    #[test]
+    fn test_register_through_shared_manager() {
+        let network = SimulatedNetwork::new(0x0004_0003);
+        let manager = Arc::new(Manager::with_backend(network.clone()));
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let guards: Vec<_> = (0..4)
+            .map(|_| {
+                let manager = manager.clone();
+                let count = count.clone();
+                thread::spawn(move || {
+                    manager
+                        .add_watcher(move |_: &Notification| {
+                            count.fetch_add(1, Ordering::SeqCst);
+                        })
+                        .unwrap()
+                })
+            })
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        network.add_node(2, SimulatedNode::default());
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+        drop(guards);
+        network.detach();
+    }
+}
@@ -0,0 +1,75 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode};
+use openzwave::manager::Manager;
+use openzwave::notification::Notification;
+use openzwave::panic_safety::WatcherPanic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_panics_are_swallowed_and_reported() {
+        let network = SimulatedNetwork::new(0x0005_0001);
+        let manager = Manager::with_backend(network.clone());
+        let reports: Arc<Mutex<Vec<WatcherPanic>>> = Arc::default();
+        {
+            let reports = reports.clone();
+            manager.set_watcher_panic_hook(move |report: &WatcherPanic| {
+                reports.lock().unwrap().push(report.clone())
+            });
+        }
+        let healthy = Arc::new(AtomicUsize::new(0));
+
+        let _panicking = manager
+            .add_watcher(|_: &Notification| panic!("watcher bug"))
+            .unwrap();
+        let _healthy = {
+            let healthy = healthy.clone();
+            manager
+                .add_watcher(move |_: &Notification| {
+                    healthy.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap()
+        };
+
+        network.add_node(2, SimulatedNode::default());
+
+        assert_eq!(healthy.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.swallowed_watcher_panics(), 1);
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].message, "watcher bug");
+        assert_eq!(reports[0].node_id, 2);
+        assert!(!reports[0].quarantined);
+        network.detach();
+    }
+
+    #[test]
+    fn test_repeated_panics_quarantine_the_watcher() {
+        let network = SimulatedNetwork::new(0x0005_0002);
+        let manager = Manager::with_backend(network.clone());
+        manager.set_watcher_panic_hook(|_: &WatcherPanic| {});
+        manager.set_watcher_quarantine_after(Some(2));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let _panicking = {
+            let calls = calls.clone();
+            manager
+                .add_watcher(move |_: &Notification| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    panic!("always");
+                })
+                .unwrap()
+        };
+
+        for node_id in 2..6 {
+            network.add_node(node_id, SimulatedNode::default());
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(manager.swallowed_watcher_panics(), 2);
+        network.detach();
+    }
+}
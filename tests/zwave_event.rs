@@ -0,0 +1,126 @@
+use openzwave::backend::{SimulatedNetwork, SimulatedNode, SimulatedValue};
+use openzwave::controller::Controller;
+use openzwave::error::ParseError;
+use openzwave::event::ZWaveEvent;
+use openzwave::manager::Manager;
+use openzwave::notification::{
+    ControllerCommandType, ControllerError, ControllerState, Event, Notification,
+    NotificationCode, NotificationType, NotificationValue, UserAlert,
+};
+use openzwave::value_classes::value_id::ValueContent;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+fn notification(notification_type: NotificationType, node_id: u8) -> Notification {
+    Notification {
+        notification_type,
+        home_id: 0x0015_0001,
+        node_id,
+        value_id: None,
+        value: None,
+        event: None,
+        command: None,
+        byte: 0,
+        description: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_node_notifications() {
+        let added = ZWaveEvent::try_from(&notification(NotificationType::NodeAdded, 4)).unwrap();
+        assert!(matches!(added, ZWaveEvent::NodeAdded { home_id: 0x0015_0001, node_id: 4 }));
+        assert_eq!(added.home_id(), 0x0015_0001);
+        assert_eq!(added.node_id(), Some(4));
+
+        let mut scene = notification(NotificationType::SceneEvent, 4);
+        scene.value = Some(NotificationValue::Scene(7));
+        assert!(matches!(scene.to_event(), Some(ZWaveEvent::SceneEvent { node_id: 4, scene_id: 7, .. })));
+
+        let mut button = notification(NotificationType::ButtonOn, 4);
+        button.value = Some(NotificationValue::Button(2));
+        assert!(matches!(button.to_event(), Some(ZWaveEvent::ButtonOn { button_id: 2, .. })));
+
+        let mut level = notification(NotificationType::NodeEvent, 4);
+        level.event = Some(Event::Node(99));
+        assert!(matches!(level.to_event(), Some(ZWaveEvent::NodeEvent { node_id: 4, level: 99, .. })));
+
+        let mut dead = notification(NotificationType::Notification, 4);
+        dead.value = Some(NotificationValue::Report(NotificationCode::Dead));
+        assert!(matches!(dead.to_event(), Some(ZWaveEvent::Notification { code: NotificationCode::Dead, .. })));
+
+        let mut alert = notification(NotificationType::UserAlerts, 0);
+        alert.value = Some(NotificationValue::UserAlert(UserAlert::DNSError));
+        let alert = alert.to_event().unwrap();
+        assert!(matches!(alert, ZWaveEvent::UserAlert { alert: UserAlert::DNSError, .. }));
+        assert_eq!(alert.node_id(), None);
+
+        let ready = notification(NotificationType::AllNodesQueried, 1).to_event().unwrap();
+        assert!(matches!(ready, ZWaveEvent::AllNodesQueried { home_id: 0x0015_0001 }));
+        assert_eq!(ready.node_id(), None);
+    }
+
+    #[test]
+    fn test_incomplete_notifications() {
+        assert!(notification(NotificationType::ValueChanged, 4).to_event().is_none());
+        assert!(notification(NotificationType::SceneEvent, 4).to_event().is_none());
+        assert!(notification(NotificationType::NodeEvent, 4).to_event().is_none());
+        assert!(notification(NotificationType::Unknown, 4).to_event().is_none());
+
+        let mut unknown_state = notification(NotificationType::ControllerCommand, 1);
+        unknown_state.event = Some(Event::Controller(None));
+        assert!(unknown_state.to_event().is_none());
+
+        let mut button = notification(NotificationType::ButtonOff, 4);
+        button.value = Some(NotificationValue::Scene(2));
+        assert_eq!(ZWaveEvent::try_from(&button).unwrap_err(), ParseError::Missing("value"));
+        assert_eq!(
+            ZWaveEvent::try_from(&notification(NotificationType::ValueChanged, 4)).unwrap_err(),
+            ParseError::Missing("value_id")
+        );
+        assert_eq!(ZWaveEvent::try_from(&unknown_state).unwrap_err(), ParseError::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_events() {
+        let network = SimulatedNetwork::new(0x0015_0002);
+        let manager = Manager::with_backend(network.clone());
+        let events: Arc<Mutex<Vec<ZWaveEvent>>> = Arc::default();
+        let _watcher = {
+            let events = events.clone();
+            manager
+                .add_watcher(move |notification: &Notification| {
+                    events.lock().unwrap().extend(notification.to_event())
+                })
+                .unwrap()
+        };
+
+        network.add_node(2, SimulatedNode::default());
+        let switch = network.add_value(2, SimulatedValue::new(0x25, 0, "Switch", ValueContent::Bool(false)));
+        network.update_value(&switch, ValueContent::Bool(true));
+        Controller::new(0x0015_0002).has_node_failed(2).unwrap().await.unwrap();
+
+        let events = events.lock().unwrap();
+        let mut seen = Vec::new();
+        for event in events.iter() {
+            match event {
+                ZWaveEvent::NodeAdded { node_id, .. } => seen.push(format!("node {}", node_id)),
+                ZWaveEvent::ValueAdded { value } => seen.push(format!("added {}", value.label())),
+                ZWaveEvent::ValueChanged { value } => seen.push(format!("changed {}", value.value())),
+                ZWaveEvent::ControllerCommand { command, state, error, .. } => {
+                    assert_eq!(*command, ControllerCommandType::HasNodeFailed);
+                    assert_eq!(*error, ControllerError::None);
+                    if *state == ControllerState::NodeOK {
+                        seen.push(String::from("node ok"));
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(seen, vec!["node 2", "added Switch", "changed true", "node ok"]);
+        network.detach();
+    }
+}